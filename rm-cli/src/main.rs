@@ -0,0 +1,204 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{anyhow, Context};
+use clap::Parser as _;
+use rm_core::{
+    archive,
+    compare::{compare, LevelBuild},
+    config::ResumeConfig,
+    data::{ItemCategory, ItemQuery, Level},
+    parser::{parse_str, Parser, ParserMsg},
+    profile,
+    recording::Recorder,
+};
+
+/// Prints what rusted mapper finds in GTFO logs.
+#[derive(clap::Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Log file to parse, `.gz` and `.zip` archives of logs too, or with `--follow` the folder
+    /// to watch, GTFO's by default.
+    path: Option<PathBuf>,
+
+    /// Keep watching the log folder and print messages as GTFO logs them.
+    #[arg(long)]
+    follow: bool,
+
+    /// With `--follow`, only read what GTFO logs from now on.
+    #[arg(long, requires = "follow")]
+    from_end: bool,
+
+    /// With `--follow`, pick the log up where the last `--resume` run left it.
+    #[arg(long, requires = "follow")]
+    resume: bool,
+
+    /// One JSON object per line instead of text.
+    #[arg(long)]
+    json: bool,
+
+    /// Compare the last level of the log with the one in another log, e.g. the host's, and
+    /// print where the seeds or items differ instead of the messages.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["follow", "record"])]
+    compare: Option<PathBuf>,
+
+    /// Also write every message to a file the GUI can replay with `--playback`.
+    #[arg(long, value_name = "PATH")]
+    record: Option<PathBuf>,
+
+    /// Treat the path as a level saved by the GUI and list its items instead of parsing a
+    /// log.
+    #[arg(long, conflicts_with_all = ["follow", "compare", "record"])]
+    items: bool,
+
+    /// With `--items`, only list items whose label contains the text, ignoring case.
+    #[arg(long, value_name = "TEXT", requires = "items")]
+    find: Option<String>,
+
+    /// With `--items`, only list items of a category: keys, cells, containers or
+    /// objective. Can be repeated.
+    #[arg(long = "category", value_name = "CATEGORY", requires = "items")]
+    categories: Vec<ItemCategory>,
+}
+
+/// Where messages go besides stdout.
+struct Output {
+    json: bool,
+    recorder: Option<Recorder>,
+}
+
+fn print(msg: &ParserMsg, out: &mut Output) -> anyhow::Result<()> {
+    if let Some(recorder) = &mut out.recorder {
+        recorder.record(msg)?;
+    }
+    // Parser health is only useful to the GUI.
+    if matches!(msg, ParserMsg::Status(_)) {
+        return Ok(());
+    }
+    let json = out.json;
+
+    if json {
+        println!("{}", serde_json::to_string(msg)?);
+    } else {
+        println!("{msg}");
+    }
+
+    Ok(())
+}
+
+fn parse_file(path: PathBuf, out: &mut Output) -> anyhow::Result<()> {
+    profile::load_user();
+
+    let logs = archive::read_logs(&path).with_context(|| format!("reading {}", path.display()))?;
+    for (_, log) in logs {
+        for msg in parse_str(&log) {
+            print(&msg, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn compare_files(ours: PathBuf, theirs: PathBuf, json: bool) -> anyhow::Result<()> {
+    profile::load_user();
+
+    let mut builds = vec![];
+    for path in [&ours, &theirs] {
+        let log =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        builds.push(LevelBuild::last(&parse_str(&log)));
+    }
+
+    let discrepancies = compare(&builds[0], &builds[1]);
+    for discrepancy in &discrepancies {
+        if json {
+            println!("{}", serde_json::to_string(discrepancy)?);
+        } else {
+            println!("{discrepancy}");
+        }
+    }
+    if discrepancies.is_empty() && !json {
+        let level = builds[0].level.as_deref().unwrap_or("the level");
+        println!("Both logs agree on {level}");
+    }
+
+    Ok(())
+}
+
+fn list_items(path: PathBuf, query: &ItemQuery, json: bool) -> anyhow::Result<()> {
+    let level = Level::load(&path).with_context(|| format!("loading {}", path.display()))?;
+    for (zone, item, label) in level.query(query) {
+        if json {
+            let line = serde_json::json!({ "zone": zone, "item": item, "label": label });
+            println!("{line}");
+        } else {
+            match zone {
+                Some(zone) => println!("{label}: {zone}"),
+                None => println!("{label}"),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn follow(
+    path: Option<PathBuf>,
+    from_end: bool,
+    resume: bool,
+    out: &mut Output,
+) -> anyhow::Result<()> {
+    let mut parser = Parser::new(path);
+    parser.set_tail_from_end(from_end);
+    parser.set_resume(ResumeConfig {
+        enabled: resume,
+        ..Default::default()
+    });
+    parser.start_watcher()?;
+
+    let rx = parser
+        .rx
+        .take()
+        .ok_or_else(|| anyhow!("Parser didn't start"))?;
+    for msg in rx {
+        print(&msg, out)?;
+    }
+
+    Err(anyhow!("Parser stopped"))
+}
+
+fn main() -> anyhow::Result<()> {
+    env_logger::init();
+
+    let args = Args::parse();
+    let mut out = Output {
+        json: args.json,
+        recorder: args
+            .record
+            .as_deref()
+            .map(Recorder::create)
+            .transpose()
+            .context("creating the recording")?,
+    };
+
+    if args.follow {
+        follow(args.path, args.from_end, args.resume, &mut out)
+    } else {
+        let path = args
+            .path
+            .ok_or_else(|| anyhow!("Pass a log file to parse, or --follow to watch GTFO's"))?;
+        if args.items {
+            let mut query = ItemQuery {
+                text: args.find.unwrap_or_default(),
+                ..Default::default()
+            };
+            if !args.categories.is_empty() {
+                query.categories = args.categories.into_iter().collect();
+            }
+            return list_items(path, &query, args.json);
+        }
+        match args.compare {
+            Some(theirs) => compare_files(path, theirs, args.json),
+            None => parse_file(path, &mut out),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Logs archived as `.gz` or `.zip`, i.e. backups of the GTFO log folder, read like plain
+//! ones so old runs can be replayed from them.
+
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use flate2::read::GzDecoder;
+use zip::ZipArchive;
+
+use crate::discovery;
+
+/// Logs in the file at `path` with where they're from: the file itself if it's a plain log,
+/// its content for `.gz`, and every log in a `.zip` under `path/<entry>` in name order, which
+/// for GTFO's dated names is the order they were written in. Only zip entries named like
+/// the watcher's logs are read, see [`discovery::is_log`].
+pub fn read_logs(path: &Path) -> io::Result<Vec<(PathBuf, String)>> {
+    let ext = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("gz") => {
+            let mut log = String::new();
+            GzDecoder::new(File::open(path)?).read_to_string(&mut log)?;
+            // `GTFO.txt.gz` is `GTFO.txt`.
+            Ok(vec![(path.with_extension(""), log)])
+        }
+        Some("zip") => {
+            let mut archive = ZipArchive::new(File::open(path)?)?;
+            let mut names: Vec<String> = archive
+                .file_names()
+                .filter(|name| discovery::is_log(Path::new(name)))
+                .map(String::from)
+                .collect();
+            names.sort();
+
+            let mut logs = vec![];
+            for name in names {
+                let mut log = String::new();
+                archive.by_name(&name)?.read_to_string(&mut log)?;
+                logs.push((path.join(&name), log));
+            }
+            if logs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "archive has no logs",
+                ));
+            }
+
+            Ok(logs)
+        }
+        _ => Ok(vec![(path.to_path_buf(), fs::read_to_string(path)?)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn archived_logs_are_read() {
+        let dir = TempDir::new("archive");
+
+        let gz = dir.join("GTFO.txt.gz");
+        let mut encoder = GzEncoder::new(File::create(&gz).unwrap(), Compression::default());
+        encoder.write_all(b"first log\n").unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(
+            read_logs(&gz).unwrap(),
+            vec![(dir.join("GTFO.txt"), "first log\n".to_string())]
+        );
+
+        let zip = dir.join("logs.zip");
+        let mut writer = ZipWriter::new(File::create(&zip).unwrap());
+        for (name, log) in [
+            ("logs/GTFO.2024.02_NICKNAME_NETSTATUS.txt", "second log\n"),
+            ("readme.md", "not a log"),
+            ("logs/GTFO.2024.02_CRASH.txt", "not one either"),
+            ("logs/GTFO.2024.01_NICKNAME_NETSTATUS.txt", "first log\n"),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(log.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let logs = read_logs(&zip).unwrap();
+        assert_eq!(
+            logs.iter().map(|(_, log)| log.as_str()).collect::<Vec<_>>(),
+            ["first log\n", "second log\n"]
+        );
+        assert_eq!(
+            logs[0].0,
+            zip.join("logs/GTFO.2024.01_NICKNAME_NETSTATUS.txt")
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{GatherItem, Level, Zone};
+
+/// Characters GTFO's chat box takes in one message.
+pub const CHAT_LIMIT: usize = 120;
+
+/// Short message telling the team where a zone's items are, like
+/// `KEY_GREEN_584: Z49 area B; CELL_3: Z49`, for pasting into the game's chat.
+///
+/// In `item`, `{item}` is replaced with the item's serial, or its name without one, `{zone}`
+/// with the zone's alias and `{area}` with `area` for items in an area, where `{area}` is
+/// the area's letter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatTemplate {
+    pub item: String,
+    pub area: String,
+    /// Put between items
+    pub separator: String,
+    /// Items that don't fit are left out, ending the message with `…`
+    pub max_len: usize,
+}
+
+impl Default for ChatTemplate {
+    fn default() -> Self {
+        Self {
+            item: "{item}: Z{zone}{area}".into(),
+            area: " area {area}".into(),
+            separator: "; ".into(),
+            max_len: CHAT_LIMIT,
+        }
+    }
+}
+
+impl ChatTemplate {
+    /// Message for the items in `zone` that weren't picked up yet, those outside its areas
+    /// first. Empty if there are none.
+    pub fn zone(&self, level: &Level, zone: &Zone) -> String {
+        let mut items: Vec<(&Zone, &GatherItem)> = level
+            .items_in(&zone.dimension)
+            .filter(|(z, item)| z.alias == zone.alias && !level.is_collected(item))
+            .collect();
+        items.sort_by_key(|(z, _)| z.area);
+
+        self.render(items.into_iter().map(|(z, item)| {
+            let name = match (level.serial(z.alias, item), item) {
+                (Some(serial), _) => serial.to_string(),
+                (None, GatherItem::Key(name, ..)) => name.clone(),
+                (None, item) => item.to_string(),
+            };
+            (name, z.alias, z.area)
+        }))
+    }
+
+    /// Joins an entry per `(item, zone alias, area)`, with as many as fit in `max_len`.
+    pub fn render(&self, entries: impl IntoIterator<Item = (String, u32, Option<char>)>) -> String {
+        let mut parts = vec![];
+        let mut len = 0;
+        let mut cut = false;
+        for (item, alias, area) in entries {
+            let area = area.map_or_else(String::new, |area| {
+                self.area.replace("{area}", &area.to_string())
+            });
+            let part = self
+                .item
+                .replace("{item}", &item)
+                .replace("{zone}", &alias.to_string())
+                .replace("{area}", &area);
+            let separator = if parts.is_empty() {
+                0
+            } else {
+                self.separator.chars().count()
+            };
+            let added = part.chars().count() + separator;
+            if len + added > self.max_len {
+                cut = true;
+                break;
+            }
+            len += added;
+            parts.push(part);
+        }
+
+        if cut {
+            // Room for the ellipsis.
+            while len + 1 > self.max_len {
+                let Some(part) = parts.pop() else {
+                    return String::new();
+                };
+                len -= part.chars().count();
+                if !parts.is_empty() {
+                    len -= self.separator.chars().count();
+                }
+            }
+        }
+
+        let mut message = parts.join(&self.separator);
+        if cut {
+            message.push('…');
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Dimension;
+
+    #[test]
+    fn zone_items_fit_in_chat() {
+        let zone = |alias| Zone {
+            layer: "MainLayer".into(),
+            ..Zone::placeholder(alias)
+        };
+        let mut level = Level {
+            zones: vec![zone(49), zone(52)],
+            serials: vec![(49, "CELL_584".into())],
+            ..Default::default()
+        };
+        level.add_to_area(
+            49,
+            'B',
+            GatherItem::Key("KEY_GREEN_584".into(), Dimension::Reality, 49, 0),
+        );
+        level.add_to_zone(49, GatherItem::Cell(3));
+        level.add_to_zone(49, GatherItem::Cell(4));
+        level.add_to_zone(52, GatherItem::Cell(1));
+        level.toggle_collected(&GatherItem::Cell(4));
+
+        let template = ChatTemplate::default();
+        assert_eq!(
+            template.zone(&level, &zone(49)),
+            "CELL_584: Z49; KEY_GREEN_584: Z49 area B"
+        );
+        assert_eq!(template.zone(&level, &zone(50)), "");
+
+        let short = ChatTemplate {
+            max_len: 16,
+            ..Default::default()
+        };
+        assert_eq!(short.zone(&level, &zone(49)), "CELL_584: Z49…");
+        let entries = || (0..3).map(|i| (format!("CELL_{i}"), 52, None));
+        assert_eq!(
+            ChatTemplate {
+                max_len: 25,
+                ..Default::default()
+            }
+            .render(entries()),
+            "CELL_0: Z52; CELL_1: Z52…"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Where the tailed log was parsed up to, kept between launches so a mapper restarted
+//! mid-raid picks the log up again instead of only reading what's logged from then on.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{ResumeConfig, ResumeFrom},
+    data::SessionInfo,
+    tail::{file_id, FileId, OpenFrom},
+};
+
+/// Log and how far into it the parser got, stored as JSON in the config dir.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub log: PathBuf,
+    /// Identity of the log, one recreated under the same name starts over
+    pub id: Option<FileId>,
+    /// Byte after the last complete line parsed
+    pub offset: u64,
+    /// Byte the seeds line of the level being parsed starts at, none between levels
+    pub level: Option<u64>,
+    /// What the log's header said, it isn't read again when resuming past it
+    pub session: SessionInfo,
+}
+
+impl Checkpoint {
+    /// Checkpoint of `log` opened at `offset`, before anything of it was parsed.
+    pub fn new(log: &Path, offset: u64) -> Self {
+        Self {
+            log: log.to_path_buf(),
+            id: fs::metadata(log).ok().and_then(|meta| file_id(&meta)),
+            offset,
+            level: None,
+            session: SessionInfo::default(),
+        }
+    }
+
+    /// Default location of the checkpoint.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("checkpoint.json"))
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the checkpoint to `path`, creating its folder if it's missing. The file is
+    /// replaced in one go, so a mapper killed while writing keeps the last one.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Byte to read `log` from again, none if the checkpoint is for another file or the
+    /// log is shorter than what was parsed of it.
+    pub fn resume(&self, log: &Path, from: ResumeFrom) -> Option<u64> {
+        let meta = fs::metadata(log).ok()?;
+        if self.log != log || file_id(&meta) != self.id || meta.len() < self.offset {
+            return None;
+        }
+
+        Some(match from {
+            ResumeFrom::Checkpoint => self.offset,
+            ResumeFrom::Level => self.level.unwrap_or(self.offset),
+        })
+    }
+}
+
+/// Checkpoint file of a [`ResumeConfig`], read when a log is opened and kept up to date by
+/// the parser thread.
+#[derive(Debug)]
+pub(crate) struct CheckpointFile {
+    path: PathBuf,
+    /// Checkpoint in the file, the one it had at start until the parser writes its own
+    last: Option<Checkpoint>,
+}
+
+impl CheckpointFile {
+    /// File of `config`, none if resuming is off or there's no config dir.
+    pub(crate) fn new(config: &ResumeConfig) -> Option<Self> {
+        let path = config
+            .enabled
+            .then(|| config.path.clone().or_else(Checkpoint::path))??;
+        let last = path
+            .is_file()
+            .then(|| Checkpoint::load_from(&path))
+            .and_then(|loaded| {
+                loaded
+                    .inspect_err(|e| warn!("Ignoring checkpoint {}: {e}", path.display()))
+                    .ok()
+            });
+
+        Some(Self { path, last })
+    }
+
+    /// Where to open `log` from, `from` unless the checkpoint is for it.
+    pub(crate) fn open_from(&self, log: &Path, resume: ResumeFrom, from: OpenFrom) -> OpenFrom {
+        match self.last.as_ref().and_then(|last| last.resume(log, resume)) {
+            Some(offset) => OpenFrom::Offset(offset),
+            None => from,
+        }
+    }
+
+    /// Session the log opened at `offset` was resumed with, if it was resumed.
+    pub(crate) fn resumed(&self, log: &Path, offset: u64) -> Option<&SessionInfo> {
+        let last = self.last.as_ref()?;
+        let id = fs::metadata(log).ok().and_then(|meta| file_id(&meta));
+        (offset > 0 && last.log == log && last.id == id).then_some(&last.session)
+    }
+
+    /// Writes `checkpoint` if it changed since the last write.
+    pub(crate) fn write(&mut self, checkpoint: Checkpoint) {
+        if self.last.as_ref() == Some(&checkpoint) {
+            return;
+        }
+
+        match checkpoint.save_to(&self.path) {
+            Ok(()) => self.last = Some(checkpoint),
+            Err(e) => warn!("Failed to write checkpoint {}: {e}", self.path.display()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn resumes_the_same_log_only() {
+        let dir = TempDir::new("checkpoint");
+        let log = dir.join("GTFO.txt");
+        fs::write(&log, "header\nseeds\nbuilding\n").unwrap();
+
+        let checkpoint = Checkpoint {
+            log: log.clone(),
+            id: file_id(&fs::metadata(&log).unwrap()),
+            offset: 22,
+            level: Some(7),
+            session: SessionInfo::default(),
+        };
+        let path = dir.join("checkpoint.json");
+        checkpoint.save_to(&path).unwrap();
+        assert_eq!(Checkpoint::load_from(&path).unwrap(), checkpoint);
+
+        assert_eq!(checkpoint.resume(&log, ResumeFrom::Checkpoint), Some(22));
+        assert_eq!(checkpoint.resume(&log, ResumeFrom::Level), Some(7));
+        let between_levels = Checkpoint {
+            level: None,
+            ..checkpoint.clone()
+        };
+        assert_eq!(between_levels.resume(&log, ResumeFrom::Level), Some(22));
+
+        // Another file, then the same one cut shorter than what was parsed.
+        let other = dir.join("GTFO_2.txt");
+        fs::write(&other, "header\nseeds\nbuilding\n").unwrap();
+        assert_eq!(checkpoint.resume(&other, ResumeFrom::Checkpoint), None);
+        fs::write(&log, "header\n").unwrap();
+        assert_eq!(checkpoint.resume(&log, ResumeFrom::Checkpoint), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{GatherItem, Seeds, ZoneKey},
+    parser::ParserMsg,
+};
+
+/// What two players' logs should agree on about a level, collected from the messages of
+/// the last build in each.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LevelBuild {
+    pub level: Option<String>,
+    pub seeds: Option<Seeds>,
+    /// Items with the zone they were distributed into, if it's known
+    pub items: Vec<(Option<ZoneKey>, GatherItem)>,
+}
+
+impl LevelBuild {
+    /// Build of the last level in `msgs`.
+    pub fn last<'a>(msgs: impl IntoIterator<Item = &'a ParserMsg>) -> Self {
+        let mut build = LevelBuild::default();
+        for msg in msgs {
+            build.push(msg);
+        }
+        build
+    }
+
+    /// Adds what `msg` says about the level, seeds start a new build.
+    pub fn push(&mut self, msg: &ParserMsg) {
+        match msg {
+            ParserMsg::LevelSeeds(seeds, _) => {
+                *self = LevelBuild {
+                    seeds: Some(*seeds),
+                    ..Default::default()
+                }
+            }
+            ParserMsg::LevelInit(level) => self.level = Some(level.to_string()),
+            ParserMsg::Gatherable(item, _) => self.items.push((None, item.clone())),
+            ParserMsg::ZoneGatherable(zone, item, _)
+            | ParserMsg::AreaGatherable(zone, _, item, _) => {
+                self.items.push((Some(zone.as_ref().clone()), item.clone()))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Which of the compared logs something was only found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// Way two builds of what should be the same level differ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Discrepancy {
+    Level {
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    Seeds {
+        ours: Option<Seeds>,
+        theirs: Option<Seeds>,
+    },
+    /// Item only one of the logs has, in the given zone
+    Item {
+        side: Side,
+        zone: Option<ZoneKey>,
+        item: GatherItem,
+    },
+}
+
+impl Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn or_none<T: Display>(value: &Option<T>) -> String {
+            value.as_ref().map_or("none".into(), T::to_string)
+        }
+
+        match self {
+            Discrepancy::Level { ours, theirs } => {
+                write!(f, "Level {} vs {}", or_none(ours), or_none(theirs))
+            }
+            Discrepancy::Seeds { ours, theirs } => {
+                write!(f, "Seeds {} vs {}", or_none(ours), or_none(theirs))
+            }
+            Discrepancy::Item { side, zone, item } => {
+                let side = match side {
+                    Side::Ours => "ours",
+                    Side::Theirs => "theirs",
+                };
+                match zone {
+                    Some(zone) => write!(f, "Only in {side}: {item} in {zone}"),
+                    None => write!(f, "Only in {side}: {item}"),
+                }
+            }
+        }
+    }
+}
+
+/// Everything `ours` and `theirs` disagree on, the level and seeds before the items. Items
+/// are matched regardless of the order they were logged in.
+pub fn compare(ours: &LevelBuild, theirs: &LevelBuild) -> Vec<Discrepancy> {
+    let mut discrepancies = vec![];
+    if ours.level != theirs.level {
+        discrepancies.push(Discrepancy::Level {
+            ours: ours.level.clone(),
+            theirs: theirs.level.clone(),
+        });
+    }
+    if ours.seeds != theirs.seeds {
+        discrepancies.push(Discrepancy::Seeds {
+            ours: ours.seeds,
+            theirs: theirs.seeds,
+        });
+    }
+
+    let mut unmatched = theirs.items.clone();
+    for (zone, item) in &ours.items {
+        match unmatched
+            .iter()
+            .position(|other| other.0 == *zone && other.1 == *item)
+        {
+            Some(i) => {
+                unmatched.remove(i);
+            }
+            None => discrepancies.push(Discrepancy::Item {
+                side: Side::Ours,
+                zone: zone.clone(),
+                item: item.clone(),
+            }),
+        }
+    }
+    discrepancies.extend(unmatched.into_iter().map(|(zone, item)| Discrepancy::Item {
+        side: Side::Theirs,
+        zone,
+        item,
+    }));
+
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeds(host: u32) -> ParserMsg {
+        ParserMsg::LevelSeeds(
+            Seeds {
+                build: 1,
+                host,
+                session: 2,
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn moved_items_and_seeds_differ() {
+        let id = GatherItem::ID("Locker".into(), 7);
+        let ours = [
+            seeds(5),
+            seeds(3),
+            ParserMsg::ZoneGatherable(Box::new(50.into()), GatherItem::Cell(0), None),
+            ParserMsg::ZoneGatherable(Box::new(50.into()), id.clone(), None),
+        ];
+        let theirs = [
+            seeds(4),
+            ParserMsg::AreaGatherable(Box::new(51.into()), 'A', id.clone(), None),
+            ParserMsg::ZoneGatherable(Box::new(50.into()), GatherItem::Cell(0), None),
+        ];
+
+        assert_eq!(
+            compare(&LevelBuild::last(&ours), &LevelBuild::last(&theirs)),
+            vec![
+                Discrepancy::Seeds {
+                    ours: Some(Seeds {
+                        build: 1,
+                        host: 3,
+                        session: 2
+                    }),
+                    theirs: Some(Seeds {
+                        build: 1,
+                        host: 4,
+                        session: 2
+                    }),
+                },
+                Discrepancy::Item {
+                    side: Side::Ours,
+                    zone: Some(50.into()),
+                    item: id.clone(),
+                },
+                Discrepancy::Item {
+                    side: Side::Theirs,
+                    zone: Some(51.into()),
+                    item: id,
+                },
+            ]
+        );
+        assert_eq!(
+            compare(&LevelBuild::last(&ours[1..]), &LevelBuild::last(&ours)),
+            vec![]
+        );
+    }
+}
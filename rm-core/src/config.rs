@@ -0,0 +1,298 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chat::ChatTemplate,
+    data::{Comparison, SaveFormat, SplitPreset},
+    events::EventSink,
+    livesplit, locale, snapshot,
+};
+
+/// Settings kept between launches, stored as TOML in the config dir.
+///
+/// Missing keys take their default, so older files keep loading as settings are added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Log folder to watch instead of the discovered one
+    pub log_dir: Option<PathBuf>,
+    pub autoscroll: bool,
+    /// Scrolls to the zone whose security door opened last, or the one the player walks into
+    /// when `PLAYER_ZONE_ENTERED` is overridden
+    pub follow_zone: bool,
+    /// Where levels are exported on reset, [`crate::data::default_save_dir`] if unset
+    pub save_dir: Option<PathBuf>,
+    pub save_format: SaveFormat,
+    pub window: WindowConfig,
+    pub livesplit: LiveSplitConfig,
+    /// Where expedition events are published for automation clients, like
+    /// `tcp:127.0.0.1:9870`. Nothing is published without one
+    pub events: Option<EventSink>,
+    pub hotkeys: HotkeyConfig,
+    pub poll: PollConfig,
+    pub resume: ResumeConfig,
+    pub snapshot: SnapshotConfig,
+    /// Messages the copy buttons next to zones put on the clipboard
+    pub chat: ChatTemplate,
+    pub split_presets: Vec<SplitPreset>,
+    /// What the timer's splits are compared against
+    pub comparison: Comparison,
+    /// Code of the GUI's [`locale::Locale`], like `en` or `ru`
+    pub language: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            log_dir: None,
+            autoscroll: true,
+            follow_zone: false,
+            save_dir: None,
+            save_format: Default::default(),
+            window: Default::default(),
+            livesplit: Default::default(),
+            events: None,
+            hotkeys: Default::default(),
+            poll: Default::default(),
+            resume: Default::default(),
+            snapshot: Default::default(),
+            chat: Default::default(),
+            split_presets: vec![],
+            comparison: Default::default(),
+            language: locale::DEFAULT_LOCALE.into(),
+        }
+    }
+}
+
+/// Overlay placement and look.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// Background opacity from 0, fully see-through, to 1
+    pub opacity: f32,
+    /// Text size relative to the default
+    pub font_scale: f32,
+    pub theme: Theme,
+    /// Lets mouse input through to the game, toggled back with the click-through hotkey
+    pub click_through: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            position: [1.0, 300.0],
+            size: [330.0, 550.0],
+            opacity: 0.0,
+            font_scale: 1.0,
+            theme: Default::default(),
+            click_through: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveSplitConfig {
+    pub enabled: bool,
+    pub addr: String,
+}
+
+impl Default for LiveSplitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            addr: livesplit::DEFAULT_ADDR.into(),
+        }
+    }
+}
+
+/// Global hotkeys, like `Numpad1` or `Ctrl+Shift+S`. Empty ones are disabled, which they all
+/// are until set, so the mapper doesn't take keys away from other apps.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub split: String,
+    pub reset: String,
+    /// Passes on what the tail has read right away
+    pub refresh: String,
+    pub click_through: String,
+}
+
+/// Text file kept up to date with the level, see [`snapshot::SnapshotWriter`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    /// Nothing is written without one
+    pub path: Option<PathBuf>,
+    pub template: String,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            template: snapshot::DEFAULT_TEMPLATE.into(),
+        }
+    }
+}
+
+/// How often the log is read and parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PollConfig {
+    pub interval_ms: u64,
+    /// Poll every couple of seconds outside of levels and every few milliseconds while one
+    /// generates, `interval_ms` is only used in the level then
+    pub adaptive: bool,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 250,
+            adaptive: false,
+        }
+    }
+}
+
+impl PollConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Picking the log up where a mapper that was closed mid-raid left it, see
+/// [`crate::checkpoint::Checkpoint`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeConfig {
+    pub enabled: bool,
+    pub from: ResumeFrom,
+    /// File the checkpoint is kept in, [`crate::checkpoint::Checkpoint::path`] if unset
+    pub path: Option<PathBuf>,
+}
+
+/// Where a log is read from again after a restart.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResumeFrom {
+    /// The line after the last one parsed, levels that were being parsed aren't mapped
+    #[default]
+    Checkpoint,
+    /// The seeds of the level being parsed, so its map is built again
+    Level,
+}
+
+impl Config {
+    /// Default location of the config file.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to the defaults if there's none. A broken file
+    /// is logged and ignored.
+    pub fn load() -> Self {
+        let Some(path) = Config::path().filter(|path| path.is_file()) else {
+            return Config::default();
+        };
+
+        Config::load_from(&path).unwrap_or_else(|e| {
+            warn!("Ignoring config {}: {e}", path.display());
+            Config::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the config to [`Config::path`].
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Config::path().ok_or_else(|| anyhow::anyhow!("No config dir"))?;
+        self.save_to(&path)
+    }
+
+    /// Writes the config to `path`, creating its folder if it's missing.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{TimerEntry, Zone},
+        testing::TempDir,
+    };
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            log_dir: Some("/games/GTFO".into()),
+            save_format: SaveFormat::Both,
+            window: WindowConfig {
+                opacity: 0.5,
+                theme: Theme::Light,
+                click_through: true,
+                ..Default::default()
+            },
+            split_presets: vec![SplitPreset {
+                level: "R1A1".into(),
+                entries: vec![
+                    TimerEntry::Start,
+                    TimerEntry::Zone(Zone {
+                        alias: 50,
+                        local: 1,
+                        dimension: "Reality".into(),
+                        layer: "MainLayer".into(),
+                        area: None,
+                    }),
+                    TimerEntry::End,
+                ],
+            }],
+            comparison: Comparison::SumOfBest,
+            events: Some("tcp:127.0.0.1:9870".parse().unwrap()),
+            ..Default::default()
+        };
+
+        // Its folder is created too.
+        let tmp = TempDir::new("config");
+        let path = tmp.join("rusted-mapper").join("config.toml");
+        config.save_to(&path).unwrap();
+        assert_eq!(Config::load_from(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let config: Config =
+            toml::from_str("autoscroll = false\n[window]\nopacity = 0.8\n").unwrap();
+
+        assert!(!config.autoscroll);
+        assert_eq!(config.window.opacity, 0.8);
+        assert_eq!(config.window.size, WindowConfig::default().size);
+        assert_eq!(config.livesplit, LiveSplitConfig::default());
+        assert_eq!(config.poll, PollConfig::default());
+        assert_eq!(config.resume, ResumeConfig::default());
+        assert!(config.hotkeys.split.is_empty() && config.hotkeys.reset.is_empty());
+    }
+}
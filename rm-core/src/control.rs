@@ -0,0 +1,461 @@
+//! Control interface for scripts and StreamDeck buttons to drive the mapper through: a Unix
+//! socket, or a named pipe on Windows. Clients send one command per line and get `ok` or
+//! `error: <why>` back for each:
+//!
+//! ```text
+//! reset
+//! open C:\logs\NICKNAME_NETSTATUS.txt
+//! export R1A1_items.csv
+//! ```
+
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use log::{error, info};
+
+/// How long a client waits for the mapper to run its command.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest command line read, a client sending more is answered with an error and dropped.
+const MAX_LINE: u64 = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCmd {
+    /// Clears the current level, like the reset hotkey
+    Reset,
+    /// Shows a saved level, or replays a log if the path doesn't end in `.json`
+    Open(PathBuf),
+    /// Writes the items of the shown level, as CSV if the path ends in `.csv`, JSON otherwise
+    Export(PathBuf),
+}
+
+impl FromStr for ControlCmd {
+    type Err = anyhow::Error;
+
+    /// Accepts `reset`, `open <path>` or `export <path>`. Paths may contain spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (cmd, arg) = s
+            .split_once(char::is_whitespace)
+            .map_or((s, ""), |(cmd, arg)| (cmd, arg.trim()));
+
+        match (cmd, arg) {
+            ("reset", "") => Ok(ControlCmd::Reset),
+            ("open", path) if !path.is_empty() => Ok(ControlCmd::Open(path.into())),
+            ("export", path) if !path.is_empty() => Ok(ControlCmd::Export(path.into())),
+            _ => Err(anyhow!(
+                "unknown command `{s}`, expected `reset`, `open <path>` or `export <path>`"
+            )),
+        }
+    }
+}
+
+impl Display for ControlCmd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlCmd::Reset => write!(f, "reset"),
+            ControlCmd::Open(path) => write!(f, "open {}", path.display()),
+            ControlCmd::Export(path) => write!(f, "export {}", path.display()),
+        }
+    }
+}
+
+/// Command a client sent, the client waits until it's answered with [`ControlRequest::reply`].
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub cmd: ControlCmd,
+    reply: Sender<Result<(), String>>,
+}
+
+impl ControlRequest {
+    pub fn reply(self, result: anyhow::Result<()>) {
+        // The client may have given up waiting.
+        let _ = self.reply.send(result.map_err(|e| e.to_string()));
+    }
+}
+
+/// Where the control server listens by default: `\\.\pipe\rusted-mapper` on Windows,
+/// `rusted-mapper.sock` in the runtime or temp folder elsewhere.
+pub fn default_endpoint() -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(r"\\.\pipe\rusted-mapper")
+    } else {
+        dirs::runtime_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("rusted-mapper.sock")
+    }
+}
+
+/// Listens for control clients, each served on a thread of its own. Commands are handed to
+/// whoever calls [`ControlServer::pending`], i.e. the GUI once per frame.
+#[derive(Debug)]
+pub struct ControlServer {
+    endpoint: PathBuf,
+    rx: Receiver<ControlRequest>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ControlServer {
+    /// Starts listening on `endpoint`. `wake` is called whenever a command comes in, so a
+    /// GUI that doesn't repaint on its own gets to it.
+    pub fn start(
+        endpoint: PathBuf,
+        wake: impl Fn() + Send + Sync + 'static,
+    ) -> anyhow::Result<Self> {
+        let (tx, rx) = channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        listen(&endpoint, tx, Arc::new(wake), stop.clone())?;
+        info!("Listening for commands on {}", endpoint.display());
+
+        Ok(Self { endpoint, rx, stop })
+    }
+
+    pub fn endpoint(&self) -> &Path {
+        &self.endpoint
+    }
+
+    /// Commands received since the last call.
+    pub fn pending(&self) -> Vec<ControlRequest> {
+        self.rx.try_iter().collect()
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.endpoint);
+    }
+}
+
+type Wake = Arc<dyn Fn() + Send + Sync>;
+
+/// Answers the commands of one client until it disconnects.
+fn serve(stream: impl Read + Write, tx: &Sender<ControlRequest>, wake: &Wake) {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        match stream.by_ref().take(MAX_LINE).read_line(&mut line) {
+            Ok(0) => break,
+            Ok(read) if read as u64 == MAX_LINE && !line.ends_with('\n') => {
+                let _ = writeln!(
+                    stream.get_mut(),
+                    "error: command longer than {MAX_LINE} bytes"
+                );
+                break;
+            }
+            Ok(_) if line.trim().is_empty() => continue,
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to read command: {e}");
+                break;
+            }
+        }
+
+        let answer = match line.parse::<ControlCmd>() {
+            Ok(cmd) => {
+                info!("Got command {cmd}");
+                let (reply, replied) = channel();
+                if tx.send(ControlRequest { cmd, reply }).is_err() {
+                    break;
+                }
+                wake();
+
+                match replied.recv_timeout(REPLY_TIMEOUT) {
+                    Ok(Ok(())) => "ok".to_string(),
+                    Ok(Err(e)) => format!("error: {e}"),
+                    Err(_) => "error: the mapper didn't answer".to_string(),
+                }
+            }
+            Err(e) => format!("error: {e}"),
+        };
+        if writeln!(stream.get_mut(), "{answer}").is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn listen(
+    endpoint: &Path,
+    tx: Sender<ControlRequest>,
+    wake: Wake,
+    stop: Arc<AtomicBool>,
+) -> anyhow::Result<()> {
+    use std::{
+        io::ErrorKind,
+        os::unix::net::{UnixListener, UnixStream},
+    };
+
+    // A socket nothing answers on is stale, from a previous run that didn't clean up.
+    if endpoint.exists() {
+        if UnixStream::connect(endpoint).is_ok() {
+            bail!("Another mapper is listening on {}", endpoint.display());
+        }
+        std::fs::remove_file(endpoint)?;
+    }
+    let listener = UnixListener::bind(endpoint)?;
+    // Polled so the thread notices the server was dropped.
+    listener.set_nonblocking(true)?;
+
+    thread::Builder::new()
+        .name("control server".into())
+        .spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        info!("Control client connected");
+                        if let Err(e) = stream.set_nonblocking(false) {
+                            error!("Failed to set up control client: {e}");
+                            continue;
+                        }
+                        let (tx, wake) = (tx.clone(), wake.clone());
+                        thread::spawn(move || serve(stream, &tx, &wake));
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        error!("Failed to accept control client: {e}");
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+/// Waiting for a client blocks, so the pipe thread only notices the server was dropped once
+/// the next client connects.
+#[cfg(windows)]
+fn listen(
+    endpoint: &Path,
+    tx: Sender<ControlRequest>,
+    wake: Wake,
+    stop: Arc<AtomicBool>,
+) -> anyhow::Result<()> {
+    // The first instance is created up front, so a name that's taken fails right away.
+    let mut next = pipe::create(endpoint)?;
+    let endpoint = endpoint.to_path_buf();
+
+    thread::Builder::new()
+        .name("control server".into())
+        .spawn(move || loop {
+            let client = pipe::connect(next);
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            match client {
+                Ok(client) => {
+                    info!("Control client connected");
+                    let (tx, wake) = (tx.clone(), wake.clone());
+                    thread::spawn(move || serve(client, &tx, &wake));
+                }
+                Err(e) => error!("Failed to accept control client: {e}"),
+            }
+
+            next = match pipe::create(&endpoint) {
+                Ok(pipe) => pipe,
+                Err(e) => {
+                    error!("Failed to create control pipe, stopping: {e}");
+                    break;
+                }
+            };
+        })?;
+
+    Ok(())
+}
+
+/// Just the two calls of the named pipe API that std doesn't cover, reading and writing go
+/// through [`std::fs::File`].
+#[cfg(windows)]
+mod pipe {
+    use std::{
+        ffi::c_void,
+        fs::File,
+        io,
+        os::windows::{ffi::OsStrExt, io::AsRawHandle, io::FromRawHandle},
+        path::Path,
+        ptr,
+    };
+
+    const PIPE_ACCESS_DUPLEX: u32 = 0x3;
+    /// Only local clients, the pipe isn't reachable over SMB
+    const PIPE_REJECT_REMOTE_CLIENTS: u32 = 0x8;
+    /// `PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT`, which are all 0
+    const PIPE_MODE: u32 = PIPE_REJECT_REMOTE_CLIENTS;
+    const PIPE_UNLIMITED_INSTANCES: u32 = 255;
+    const BUFFER_SIZE: u32 = 4096;
+    const ERROR_PIPE_CONNECTED: i32 = 535;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateNamedPipeW(
+            name: *const u16,
+            open_mode: u32,
+            pipe_mode: u32,
+            max_instances: u32,
+            out_buffer_size: u32,
+            in_buffer_size: u32,
+            default_timeout: u32,
+            security_attributes: *mut c_void,
+        ) -> *mut c_void;
+        fn ConnectNamedPipe(pipe: *mut c_void, overlapped: *mut c_void) -> i32;
+    }
+
+    /// New instance of the pipe, for the next client to connect to.
+    pub fn create(name: &Path) -> io::Result<File> {
+        let name: Vec<u16> = name.as_os_str().encode_wide().chain([0]).collect();
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_MODE,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        // INVALID_HANDLE_VALUE
+        if handle as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(unsafe { File::from_raw_handle(handle) })
+    }
+
+    /// Waits for a client to connect to `pipe`.
+    pub fn connect(pipe: File) -> io::Result<File> {
+        if unsafe { ConnectNamedPipe(pipe.as_raw_handle(), ptr::null_mut()) } == 0 {
+            let e = io::Error::last_os_error();
+            // The client connected between creating the pipe and waiting for it.
+            if e.raw_os_error() != Some(ERROR_PIPE_CONNECTED) {
+                return Err(e);
+            }
+        }
+
+        Ok(pipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!("reset".parse::<ControlCmd>().unwrap(), ControlCmd::Reset);
+        assert_eq!(
+            " open  C:\\GTFO logs\\log.txt\n"
+                .parse::<ControlCmd>()
+                .unwrap(),
+            ControlCmd::Open("C:\\GTFO logs\\log.txt".into())
+        );
+        assert_eq!(
+            "export items.csv".parse::<ControlCmd>().unwrap(),
+            ControlCmd::Export("items.csv".into())
+        );
+        assert!("open".parse::<ControlCmd>().is_err());
+        assert!("reset now".parse::<ControlCmd>().is_err());
+        assert!("split".parse::<ControlCmd>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clients_get_answers() {
+        use std::os::unix::net::UnixStream;
+
+        let tmp = TempDir::new("control");
+        let endpoint = tmp.join("control.sock");
+        let server = ControlServer::start(endpoint.clone(), || {}).unwrap();
+
+        let client = thread::spawn(move || {
+            let mut stream = UnixStream::connect(&endpoint).unwrap();
+            stream
+                .write_all(b"reset\nsplit\nexport items.csv\n")
+                .unwrap();
+            let answers: Vec<_> = BufReader::new(stream).lines().take(3).collect();
+            answers.into_iter().map(Result::unwrap).collect::<Vec<_>>()
+        });
+
+        let mut handled = vec![];
+        while handled.len() < 2 {
+            for request in server.pending() {
+                let result = match request.cmd {
+                    ControlCmd::Export(_) => Err(anyhow!("no level to export")),
+                    _ => Ok(()),
+                };
+                handled.push(request.cmd.clone());
+                request.reply(result);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let answers = client.join().unwrap();
+        assert_eq!(
+            handled,
+            [ControlCmd::Reset, ControlCmd::Export("items.csv".into())]
+        );
+        assert_eq!(answers[0], "ok");
+        assert!(answers[1].starts_with("error: unknown command `split`"));
+        assert_eq!(answers[2], "error: no level to export");
+
+        let endpoint = server.endpoint().to_path_buf();
+        drop(server);
+        assert!(!endpoint.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn running_server_is_not_taken_over() {
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        let tmp = TempDir::new("control-taken");
+        let endpoint = tmp.join("control-taken.sock");
+        // Left behind by a mapper that didn't clean up.
+        drop(UnixListener::bind(&endpoint).unwrap());
+        let server = ControlServer::start(endpoint.clone(), || {}).unwrap();
+
+        assert!(ControlServer::start(endpoint.clone(), || {}).is_err());
+        assert!(UnixStream::connect(&endpoint).is_ok());
+        drop(server);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn long_commands_are_cut_off() {
+        use std::os::unix::net::UnixStream;
+
+        let tmp = TempDir::new("control-long");
+        let endpoint = tmp.join("control-long.sock");
+        let server = ControlServer::start(endpoint.clone(), || {}).unwrap();
+
+        let mut stream = UnixStream::connect(&endpoint).unwrap();
+        let command = format!("open {}\n", "a".repeat(MAX_LINE as usize));
+        stream.write_all(command.as_bytes()).unwrap();
+        let answers: Vec<_> = BufReader::new(stream).lines().collect();
+        assert_eq!(
+            answers.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            [format!("error: command longer than {MAX_LINE} bytes")]
+        );
+        assert!(server.pending().is_empty());
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::{Splits, TimerEntry};
+
+/// Best times of an expedition run with one split preset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BestSplits {
+    /// Expedition, i.e. `R1A1`
+    pub level: String,
+    /// Names of the preset's entries, empty for runs without a preset
+    pub preset: Vec<String>,
+    /// Splits of the fastest finished run relative to the drop, ending with `End`
+    pub personal_best: Vec<(String, Duration)>,
+    /// Fastest time from the split before to each split over every run, the gold splits
+    pub best_segments: BTreeMap<String, Duration>,
+    /// Splits of every run relative to the drop, oldest first, ending with `End` if it
+    /// finished
+    pub history: Vec<Vec<(String, Duration)>>,
+}
+
+/// What the splits of a run are compared against.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    #[default]
+    PersonalBest,
+    /// Best segments added up, the fastest the run could be
+    SumOfBest,
+    /// Average of each segment over the runs that reached it
+    Average,
+}
+
+impl Comparison {
+    pub const ALL: [Comparison; 3] = [
+        Comparison::PersonalBest,
+        Comparison::SumOfBest,
+        Comparison::Average,
+    ];
+
+    /// Short name shown next to the comparison's time.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Comparison::PersonalBest => "PB",
+            Comparison::SumOfBest => "SoB",
+            Comparison::Average => "Avg",
+        }
+    }
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comparison::PersonalBest => write!(f, "Personal best"),
+            Comparison::SumOfBest => write!(f, "Sum of best segments"),
+            Comparison::Average => write!(f, "Average"),
+        }
+    }
+}
+
+/// How a split compares to a [`Comparison`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitDelta {
+    pub name: String,
+    /// Time since the drop
+    pub at: Duration,
+    /// Difference to the compared split, `None` if there's nothing to compare this one with
+    pub delta: Option<Delta>,
+    /// The segment leading to the split beat the best one
+    pub gold: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delta {
+    Ahead(Duration),
+    Behind(Duration),
+}
+
+/// Splits of a run by name relative to the drop, its final time as `End` once it ended.
+fn split_times(splits: &Splits) -> Vec<(String, Duration)> {
+    splits
+        .splits
+        .iter()
+        .map(|(entry, at)| (entry.to_string(), *at))
+        .chain(splits.end.map(|end| (TimerEntry::End.to_string(), end)))
+        .collect()
+}
+
+/// Time of each split since the one before it.
+fn segments(times: &[(String, Duration)]) -> impl Iterator<Item = (&str, Duration)> {
+    let mut last = Duration::ZERO;
+    times.iter().map(move |(name, at)| {
+        let segment = at.saturating_sub(last);
+        last = *at;
+        (name.as_str(), segment)
+    })
+}
+
+impl BestSplits {
+    pub fn new(level: impl Into<String>, preset: &[TimerEntry]) -> Self {
+        Self {
+            level: level.into(),
+            preset: preset.iter().map(TimerEntry::to_string).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Final time of the personal best.
+    pub fn pb_time(&self) -> Option<Duration> {
+        self.split(&TimerEntry::End.to_string())
+    }
+
+    /// Final time of `comparison`.
+    pub fn final_time(&self, comparison: Comparison) -> Option<Duration> {
+        let end = TimerEntry::End.to_string();
+        self.compared(comparison)
+            .into_iter()
+            .find_map(|(name, at)| (name == end).then_some(at))
+    }
+
+    /// Splits relative to the drop that `comparison` has, in the order of the personal
+    /// best, or of the latest run before there is one.
+    pub fn compared(&self, comparison: Comparison) -> Vec<(String, Duration)> {
+        let order = if self.personal_best.is_empty() {
+            self.history.last().map_or(&[][..], Vec::as_slice)
+        } else {
+            &self.personal_best
+        };
+        let add_up = |segment: &dyn Fn(&str) -> Option<Duration>| {
+            let mut at = Duration::ZERO;
+            order
+                .iter()
+                .map_while(|(name, _)| {
+                    at += segment(name)?;
+                    Some((name.clone(), at))
+                })
+                .collect()
+        };
+
+        match comparison {
+            Comparison::PersonalBest => self.personal_best.clone(),
+            Comparison::SumOfBest => add_up(&|name| self.best_segments.get(name).copied()),
+            Comparison::Average => add_up(&|name| {
+                let runs: Vec<Duration> = self
+                    .history
+                    .iter()
+                    .filter_map(|run| segments(run).find_map(|(n, seg)| (n == name).then_some(seg)))
+                    .collect();
+                let count = u32::try_from(runs.len()).ok().filter(|&n| n > 0)?;
+                Some(runs.iter().sum::<Duration>() / count)
+            }),
+        }
+    }
+
+    /// When the personal best reached the split named `name`.
+    pub fn split(&self, name: &str) -> Option<Duration> {
+        self.personal_best
+            .iter()
+            .find_map(|(split, at)| (split == name).then_some(*at))
+    }
+
+    /// Takes the segments of a run into the history and the gold splits, a finished run
+    /// faster than the personal best replaces it. Returns whether it did.
+    pub fn record(&mut self, splits: &Splits) -> bool {
+        let times = split_times(splits);
+        self.history.push(times.clone());
+        for (name, segment) in segments(&times) {
+            self.best_segments
+                .entry(name.into())
+                .and_modify(|best| *best = segment.min(*best))
+                .or_insert(segment);
+        }
+
+        let faster = splits
+            .end
+            .is_some_and(|end| self.pb_time().is_none_or(|pb| end < pb));
+        if faster {
+            self.personal_best = times;
+        }
+        faster
+    }
+
+    /// Splits of a run against `comparison` and the gold splits. A segment only goes gold
+    /// when beating one run before.
+    pub fn deltas(&self, splits: &Splits, comparison: Comparison) -> Vec<SplitDelta> {
+        let times = split_times(splits);
+        let compared = self.compared(comparison);
+        segments(&times)
+            .zip(&times)
+            .map(|((name, segment), (_, at))| SplitDelta {
+                name: name.into(),
+                at: *at,
+                delta: compared
+                    .iter()
+                    .find_map(|(split, to)| (split == name).then_some(*to))
+                    .map(|to| {
+                        if *at <= to {
+                            Delta::Ahead(to - *at)
+                        } else {
+                            Delta::Behind(*at - to)
+                        }
+                    }),
+                gold: self
+                    .best_segments
+                    .get(name)
+                    .is_some_and(|best| segment < *best),
+            })
+            .collect()
+    }
+}
+
+/// [`BestSplits`] of every expedition and preset run so far, stored as JSON in the config
+/// dir.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bests {
+    pub runs: Vec<BestSplits>,
+    /// File the bests were loaded from and are saved to, nothing is saved without one
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Bests {
+    /// Default location of the bests file.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("bests.json"))
+    }
+
+    /// Loads the bests file, none if there's no file yet. A broken file is logged and
+    /// ignored.
+    pub fn load() -> Self {
+        let Some(path) = Bests::path() else {
+            return Bests::default();
+        };
+        if !path.is_file() {
+            return Bests {
+                path: Some(path),
+                ..Default::default()
+            };
+        }
+
+        Bests::load_from(&path).unwrap_or_else(|e| {
+            warn!("Ignoring bests {}: {e}", path.display());
+            Bests::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let mut bests: Bests = serde_json::from_str(&fs::read_to_string(path)?)?;
+        bests.path = Some(path.into());
+        Ok(bests)
+    }
+
+    /// Writes the bests to the file they were loaded from, creating its folder if it's
+    /// missing.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, level: &str, preset: &[TimerEntry]) -> Option<&BestSplits> {
+        let key = BestSplits::new(level, preset);
+        self.runs
+            .iter()
+            .find(|best| best.level == key.level && best.preset == key.preset)
+    }
+
+    /// Records a run of `level` with `preset` into its [`BestSplits`]. Runs that never
+    /// dropped are ignored.
+    pub fn record(&mut self, level: &str, preset: &[TimerEntry], splits: &Splits) -> bool {
+        if splits.start.is_none() {
+            return false;
+        }
+
+        let key = BestSplits::new(level, preset);
+        let i = match self
+            .runs
+            .iter()
+            .position(|best| best.level == key.level && best.preset == key.preset)
+        {
+            Some(i) => i,
+            None => {
+                self.runs.push(key);
+                self.runs.len() - 1
+            }
+        };
+        self.runs[i].record(splits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(door: u64, end: Option<u64>) -> Splits {
+        Splits {
+            start: Some("20:00:00".parse().unwrap()),
+            splits: vec![(TimerEntry::Custom("Door".into()), Duration::from_secs(door))],
+            end: end.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn golds_and_pb_per_preset() {
+        let preset = [TimerEntry::Start, TimerEntry::End];
+        let mut bests = Bests::default();
+
+        assert!(bests.record("R1A1", &preset, &run(100, Some(400))));
+        assert!(!bests.record("R1A1", &preset, &run(80, None)));
+        assert!(!bests.record("R1A1", &preset, &run(120, Some(450))));
+        assert!(!bests.record("R1A1", &preset, &Splits::default()));
+        assert!(bests.record("R1A1", &[], &run(200, Some(500))));
+
+        let best = bests.get("R1A1", &preset).unwrap();
+        assert_eq!(best.pb_time(), Some(Duration::from_secs(400)));
+        assert_eq!(best.best_segments["Door"], Duration::from_secs(80));
+        assert_eq!(best.best_segments["End"], Duration::from_secs(300));
+        assert_eq!(bests.runs.len(), 2);
+
+        assert_eq!(
+            best.deltas(&run(90, Some(380)), Comparison::PersonalBest),
+            vec![
+                SplitDelta {
+                    name: "Door".into(),
+                    at: Duration::from_secs(90),
+                    delta: Some(Delta::Ahead(Duration::from_secs(10))),
+                    gold: false,
+                },
+                SplitDelta {
+                    name: "End".into(),
+                    at: Duration::from_secs(380),
+                    delta: Some(Delta::Ahead(Duration::from_secs(20))),
+                    gold: true,
+                },
+            ]
+        );
+        assert_eq!(
+            best.deltas(&run(110, None), Comparison::PersonalBest)[0].delta,
+            Some(Delta::Behind(Duration::from_secs(10)))
+        );
+    }
+
+    #[test]
+    fn sum_of_best_and_average() {
+        let mut best = BestSplits::new("R1A1", &[]);
+        assert_eq!(best.compared(Comparison::Average), vec![]);
+        for (door, end) in [(100, Some(400)), (80, None), (120, Some(450))] {
+            best.record(&run(door, end));
+        }
+        let secs = |name: &str, secs| (name.to_string(), Duration::from_secs(secs));
+
+        assert_eq!(best.history.len(), 3);
+        assert_eq!(
+            best.compared(Comparison::SumOfBest),
+            vec![secs("Door", 80), secs("End", 380)]
+        );
+        assert_eq!(
+            best.compared(Comparison::Average),
+            vec![secs("Door", 100), secs("End", 415)]
+        );
+        assert_eq!(
+            best.final_time(Comparison::PersonalBest),
+            Some(Duration::from_secs(400))
+        );
+        assert_eq!(
+            best.deltas(&run(90, None), Comparison::Average)[0].delta,
+            Some(Delta::Ahead(Duration::from_secs(10)))
+        );
+    }
+}
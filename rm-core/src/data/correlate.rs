@@ -0,0 +1,109 @@
+use super::{GatherItem, ItemIdentifier, ZoneKey};
+use crate::diagnostics::{self, Severity};
+
+/// Spawn of a seeded pickup from the FunctionMarkers batch.
+#[derive(Debug, Clone, PartialEq)]
+struct SeedSpawn {
+    /// Item name of the spawn line, i.e. `GLP`, if it has one
+    kind: Option<String>,
+    container: String,
+    seed: u32,
+}
+
+/// Pairs the seeded items of the Distribution batch with the container seeds of the
+/// FunctionMarkers batch. Neither batch has the whole item and they aren't logged in a
+/// guaranteed order, so both sides are buffered until [`SeedCorrelator::resolve`].
+///
+/// A seed goes to the first waiting item of the kind its spawn line names, seeds that name
+/// none of the waiting kinds fall back to spawn order.
+#[derive(Debug, Default)]
+pub struct SeedCorrelator {
+    /// Distributed items with their zone
+    items: Vec<(ZoneKey, ItemIdentifier)>,
+    seeds: Vec<SeedSpawn>,
+}
+
+impl SeedCorrelator {
+    /// Buffers a distributed item, items that don't take a seed are ignored.
+    pub fn add_item(&mut self, zone: impl Into<ZoneKey>, item: ItemIdentifier) {
+        if item.is_seeded() {
+            self.items.push((zone.into(), item));
+        }
+    }
+
+    pub fn add_seed(&mut self, kind: Option<&str>, container: &str, seed: u32) {
+        self.seeds.push(SeedSpawn {
+            kind: kind.map(String::from),
+            container: container.into(),
+            seed,
+        });
+    }
+
+    /// Items in spawn order with their zone. Seeds without an item are kept as
+    /// [`GatherItem::Seeded`] without a zone, items that never got a seed are logged and
+    /// dropped.
+    pub fn resolve(self) -> Vec<(Option<ZoneKey>, GatherItem)> {
+        let mut waiting: Vec<Option<(ZoneKey, ItemIdentifier)>> =
+            self.items.into_iter().map(Some).collect();
+        let mut paired: Vec<Option<(ZoneKey, ItemIdentifier)>> = vec![None; self.seeds.len()];
+
+        // Named kinds first, so a mismatched spawn order can't take another kind's item.
+        for (spawn, pair) in self.seeds.iter().zip(paired.iter_mut()) {
+            let Some(kind) = &spawn.kind else {
+                continue;
+            };
+            *pair = waiting
+                .iter_mut()
+                .find(|item| item.as_ref().is_some_and(|(_, id)| id.matches_name(kind)))
+                .and_then(Option::take);
+        }
+
+        for pair in paired.iter_mut().filter(|pair| pair.is_none()) {
+            *pair = waiting.iter_mut().find_map(Option::take);
+        }
+
+        let unseeded = waiting.iter().flatten().count();
+        if unseeded > 0 {
+            diagnostics::report(
+                Severity::Warning,
+                format!("{unseeded} distributed items never got a seed"),
+            );
+        }
+
+        self.seeds
+            .into_iter()
+            .zip(paired)
+            .map(|(spawn, pair)| {
+                pair.and_then(|(zone, id)| {
+                    Some((Some(zone), id.seeded(&spawn.container, spawn.seed)?))
+                })
+                .unwrap_or((None, GatherItem::Seeded(spawn.container, spawn.seed)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_go_to_their_kind_first() {
+        let mut correlator = SeedCorrelator::default();
+        correlator.add_seed(Some("ID"), "Box3", 99);
+        correlator.add_item(50, ItemIdentifier::PD);
+        correlator.add_item(51, ItemIdentifier::ID);
+        correlator.add_item(52, ItemIdentifier::Cargo);
+        correlator.add_seed(None, "Locker12", 1234);
+        correlator.add_seed(Some("GLP"), "Locker4", 777);
+
+        assert_eq!(
+            correlator.resolve(),
+            vec![
+                (Some(51.into()), GatherItem::ID("Box3".into(), 99)),
+                (Some(50.into()), GatherItem::PD("Locker12".into(), 1234)),
+                (None, GatherItem::Seeded("Locker4".into(), 777)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::{Level, Objective};
+
+/// What's known about an expedition before it's played, from the table built in or the
+/// user's `expeditions.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpeditionInfo {
+    /// Level as it's displayed, i.e. `R1A1`
+    pub level: String,
+    /// Name the game gives the expedition, like `The Admin`
+    pub name: String,
+    /// Number of zones, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zones: Option<u32>,
+    /// Main objective, like `Gather` or `Reactor startup`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub objective: Option<String>,
+    /// Items the warden objectives ask for, shown until the log has the real ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Objective>,
+}
+
+/// Expedition metadata keyed by level, see [`ExpeditionInfo`].
+#[derive(Debug, Default, Clone)]
+pub struct ExpeditionDb {
+    expeditions: HashMap<String, ExpeditionInfo>,
+}
+
+impl ExpeditionDb {
+    /// The table shipped with the mapper.
+    pub fn builtin() -> Self {
+        let mut db = ExpeditionDb::default();
+        db.extend(
+            serde_json::from_str(include_str!("expeditions.json"))
+                .expect("built in expedition table is valid"),
+        );
+        db
+    }
+
+    /// Expeditions the user added or corrected, these replace built in ones of the same
+    /// level.
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("expeditions.json"))
+    }
+
+    /// Built in table with the user's file on top. A broken file is logged and skipped.
+    pub fn load() -> Self {
+        let mut db = ExpeditionDb::builtin();
+        if let Some(path) = ExpeditionDb::user_path().filter(|path| path.is_file()) {
+            if let Err(e) = db.load_file(&path) {
+                warn!("Skipping expeditions in {}: {e}", path.display());
+            }
+        }
+
+        db
+    }
+
+    /// Reads a JSON list of [`ExpeditionInfo`]s, replacing levels that were already known.
+    pub fn load_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        let expeditions: Vec<ExpeditionInfo> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        info!(
+            "Loaded {} expeditions from {}",
+            expeditions.len(),
+            path.display()
+        );
+        self.extend(expeditions);
+
+        Ok(())
+    }
+
+    fn extend(&mut self, expeditions: Vec<ExpeditionInfo>) {
+        self.expeditions.extend(
+            expeditions
+                .into_iter()
+                .map(|expedition| (expedition.level.clone(), expedition)),
+        );
+    }
+
+    pub fn get(&self, level: &Level) -> Option<&ExpeditionInfo> {
+        self.expeditions.get(&level.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{ItemIdentifier, Rundown},
+        testing::TempDir,
+    };
+
+    use super::*;
+
+    #[test]
+    fn user_expeditions_replace_builtin() {
+        let tmp = TempDir::new("expeditions");
+        let path = tmp.join("expeditions.json");
+        fs::write(
+            &path,
+            r#"[{"level": "R1A1", "name": "Admin", "zones": 5, "items": [{"item": "ID", "count": 1}]}]"#,
+        )
+        .unwrap();
+
+        let mut db = ExpeditionDb::builtin();
+        let level = |exp: &str| Level {
+            rundown: Rundown::R1,
+            exp_name: exp.into(),
+            ..Default::default()
+        };
+        assert_eq!(db.get(&level("A1")).unwrap().name, "The Admin");
+
+        db.load_file(&path).unwrap();
+        let info = db.get(&level("A1")).unwrap();
+        assert_eq!(info.zones, Some(5));
+        assert_eq!(
+            info.items,
+            vec![Objective {
+                item: ItemIdentifier::ID,
+                count: 1
+            }]
+        );
+        assert_eq!(db.get(&level("B1")).unwrap().name, "Crib");
+        assert!(db.get(&level("E1")).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::{btree_map::Entry, BTreeMap, VecDeque};
+
+/// Which zones lead into which, built from the [`super::Level::connections`] of the SetupFloor
+/// batch. Every zone but the first is built from the one its security door is in, so the
+/// graph is a tree rooted at the elevator zone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ZoneGraph {
+    /// Aliases in the order the zones were created
+    zones: Vec<u32>,
+    /// Zone each zone was built from, by alias
+    parents: BTreeMap<u32, u32>,
+    /// Zones built from each zone, in the order they were connected
+    children: BTreeMap<u32, Vec<u32>>,
+}
+
+impl ZoneGraph {
+    pub fn new(zones: impl IntoIterator<Item = u32>, connections: &[(u32, u32)]) -> Self {
+        let mut graph = ZoneGraph::default();
+        for alias in zones {
+            if !graph.zones.contains(&alias) {
+                graph.zones.push(alias);
+            }
+        }
+
+        for &(from, to) in connections {
+            for alias in [from, to] {
+                if !graph.zones.contains(&alias) {
+                    graph.zones.push(alias);
+                }
+            }
+            graph.parents.insert(to, from);
+            graph.children.entry(from).or_default().push(to);
+        }
+
+        graph
+    }
+
+    /// Aliases in the order the zones were created.
+    pub fn zones(&self) -> &[u32] {
+        &self.zones
+    }
+
+    /// Zones nothing was built from, i.e. the elevator zone, or one per layer when other
+    /// layers are entered through a bulkhead that isn't logged as a connection.
+    pub fn roots(&self) -> Vec<u32> {
+        self.zones
+            .iter()
+            .copied()
+            .filter(|alias| !self.parents.contains_key(alias))
+            .collect()
+    }
+
+    pub fn parent(&self, alias: u32) -> Option<u32> {
+        self.parents.get(&alias).copied()
+    }
+
+    pub fn children(&self, alias: u32) -> &[u32] {
+        self.children.get(&alias).map_or(&[], Vec::as_slice)
+    }
+
+    /// Zones a door leads to from `alias`, its parent first.
+    pub fn neighbours(&self, alias: u32) -> Vec<u32> {
+        self.parent(alias)
+            .into_iter()
+            .chain(self.children(alias).iter().copied())
+            .collect()
+    }
+
+    /// Doors between `alias` and its root, `0` for a root.
+    pub fn depth(&self, alias: u32) -> usize {
+        std::iter::successors(self.parent(alias), |&zone| self.parent(zone)).count()
+    }
+
+    /// Zones passed through going from `from` to `to`, both included. `None` if they aren't
+    /// connected.
+    pub fn route(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        // Zone each reached zone was first reached from, `None` for `from`
+        let mut came_from = BTreeMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(zone) = queue.pop_front() {
+            if zone == to {
+                let mut route: Vec<u32> =
+                    std::iter::successors(Some(to), |zone| came_from[zone]).collect();
+                route.reverse();
+                return Some(route);
+            }
+
+            for next in self.neighbours(zone) {
+                if let Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(Some(zone));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_go_through_the_tree() {
+        let graph = ZoneGraph::new([49, 50, 51, 52, 53], &[(49, 50), (50, 51), (50, 52)]);
+
+        assert_eq!(graph.roots(), vec![49, 53]);
+        assert_eq!(graph.children(50), &[51, 52]);
+        assert_eq!(graph.neighbours(50), vec![49, 51, 52]);
+        assert_eq!(graph.depth(52), 2);
+        assert_eq!(graph.route(51, 52), Some(vec![51, 50, 52]));
+        assert_eq!(graph.route(49, 49), Some(vec![49]));
+        assert_eq!(graph.route(49, 53), None);
+    }
+}
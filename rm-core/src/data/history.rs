@@ -0,0 +1,131 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use jiff::civil::DateTime;
+use log::warn;
+
+use super::{Level, Outcome, RunStats, Seeds};
+
+/// Summary of a level saved by [`Level::export`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedRun {
+    pub path: PathBuf,
+    /// Expedition, i.e. `R1A1`
+    pub level: String,
+    /// When the level was saved, taken from the file name
+    pub saved_at: Option<DateTime>,
+    pub seeds: Option<Seeds>,
+    /// Final time, `None` for runs that never extracted
+    pub duration: Option<Duration>,
+    pub outcome: Option<Outcome>,
+}
+
+impl SavedRun {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let level = Level::load(path)?;
+        let saved_at = path
+            .file_stem()
+            .and_then(|stem| stem.to_str()?.rsplit('_').next())
+            .and_then(|at| DateTime::strptime("%Y-%m-%dT%H-%M-%S", at).ok());
+
+        Ok(Self {
+            path: path.into(),
+            level: level.to_string(),
+            saved_at,
+            seeds: level.seeds,
+            duration: level.splits.end,
+            outcome: level.outcome,
+        })
+    }
+}
+
+/// JSON saves in the expedition folders of `root`, newest first, without their stats. Files that fail to load
+/// are logged and skipped.
+pub fn saved_runs(root: &Path) -> anyhow::Result<Vec<SavedRun>> {
+    let mut runs = vec![];
+    for dir in fs::read_dir(root)? {
+        let dir = dir?.path();
+        if !dir.is_dir() {
+            continue;
+        }
+
+        for file in fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != "json") || RunStats::is_stats_file(&path) {
+                continue;
+            }
+
+            match SavedRun::load(&path) {
+                Ok(run) => runs.push(run),
+                Err(e) => warn!("Skipping save {}: {e}", path.display()),
+            }
+        }
+    }
+
+    runs.sort_by_key(|run| Reverse(run.saved_at));
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{Rundown, SaveFormat},
+        testing::TempDir,
+    };
+
+    use super::*;
+
+    #[test]
+    fn runs_are_listed_newest_first() {
+        let root = TempDir::new("history");
+        let level = |exp: &str| Level {
+            rundown: Rundown::R1,
+            exp_name: exp.into(),
+            ..Default::default()
+        };
+
+        let mut finished = level("A1");
+        finished.seeds = Some(Seeds {
+            build: 1,
+            host: 2,
+            session: 3,
+        });
+        finished.splits.end = Some(Duration::from_secs(754));
+        finished.outcome = Some(Outcome::Success);
+        let dir = finished.expedition_dir(root.path());
+        fs::create_dir_all(&dir).unwrap();
+        let older = dir.join(format!(
+            "{}.json",
+            finished.file_stem("2024-07-01T20:03:18".parse().unwrap())
+        ));
+        finished.save(&older).unwrap();
+
+        let aborted = level("B2");
+        let newer = aborted
+            .export(&aborted.expedition_dir(root.path()), SaveFormat::Both)
+            .unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let runs = saved_runs(root.path()).unwrap();
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].path, newer[0]);
+        assert_eq!(runs[0].level, "R1B2");
+        assert_eq!(runs[0].duration, None);
+        assert_eq!(
+            runs[1],
+            SavedRun {
+                path: older,
+                level: "R1A1".into(),
+                saved_at: Some("2024-07-01T20:03:18".parse().unwrap()),
+                seeds: finished.seeds,
+                duration: Some(Duration::from_secs(754)),
+                outcome: Some(Outcome::Success),
+            }
+        );
+    }
+}
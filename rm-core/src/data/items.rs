@@ -1,5 +1,9 @@
+use std::fmt::Display;
+
 use serde::{Deserialize, Serialize};
-use strum::FromRepr;
+use strum::{EnumDiscriminants, FromRepr};
+
+use super::Dimension;
 
 /// Main enum which keeps list of all gatherable items in game and related data to them
 /// Keys and Bulkhead Keys and HSU don't have item ID and/or have separate algorithm of
@@ -7,15 +11,19 @@ use strum::FromRepr;
 /// Some items do have names cause there's literaly no other information that can be gotten
 /// for those items. Items that have seed only may have more data, but seed data and other data
 /// are split between 2 different batch jobs and there's no guarantee that the order is preserved.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+///
+/// (De)serialized with named fields, see [`NamedItem`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, EnumDiscriminants)]
+#[strum_discriminants(name(GatherKind), derive(PartialOrd, Ord, Hash))]
+#[serde(from = "ItemRepr", into = "NamedItem")]
 pub enum GatherItem {
     /// Name, Dimension, Zone, ri
-    Key(String, String, u32, u32),
+    Key(String, Dimension, u32, u32),
     /// Name
     BulkheadKey(String),
     /// Local Area ID, Local Area Name
     HSU(u32, char),
-    /// Name, item idx, idx
+    /// Name, objective collection, idx in the collection
     Generator(String, u8, u8),
     /// Container, Item Seed
     ID(String, u32),
@@ -45,11 +53,371 @@ pub enum GatherItem {
     GLP2(String, u32),
     /// Name
     Cargo(String),
+    /// Name - Matter Wave Projector, only known from its spawn line
+    MWP(String),
+    /// Name - terminal serial if the game logs it, the zone and index if not
+    Uplink(String),
     /// Locker, seed
     Seeded(String, u32),
+    /// Item id, Name - items of an id the mapper doesn't know, named from the datablocks
+    /// if a profile has them
+    Unknown(u8, String),
+}
+
+/// [`GatherItem`] as it's (de)serialized, its fields named so consumers of the messages
+/// don't depend on their order.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Serialize, Deserialize)]
+enum NamedItem {
+    Key {
+        name: String,
+        dimension: Dimension,
+        zone: u32,
+        ri: u32,
+    },
+    BulkheadKey {
+        name: String,
+    },
+    HSU {
+        area_id: u32,
+        area: char,
+    },
+    Generator {
+        name: String,
+        collection: u8,
+        idx: u8,
+    },
+    ID {
+        container: String,
+        seed: u32,
+    },
+    PD {
+        container: String,
+        seed: u32,
+    },
+    Cell {
+        zone_idx: u8,
+    },
+    FogTurbine {
+        name: String,
+    },
+    Neonate {
+        name: String,
+    },
+    Cryo {
+        name: String,
+    },
+    GLP1 {
+        container: String,
+        seed: u32,
+    },
+    OSIP {
+        container: String,
+        seed: u32,
+    },
+    Datasphere {
+        zone_idx: u8,
+    },
+    PlantSample {
+        container: String,
+        seed: u32,
+    },
+    HiSec {
+        name: String,
+    },
+    DataCube {
+        container: String,
+        seed: u32,
+    },
+    GLP2 {
+        container: String,
+        seed: u32,
+    },
+    Cargo {
+        name: String,
+    },
+    MWP {
+        name: String,
+    },
+    Uplink {
+        name: String,
+    },
+    Seeded {
+        container: String,
+        seed: u32,
+    },
+    Unknown {
+        id: u8,
+        name: String,
+    },
+}
+
+/// [`GatherItem`] with its fields in order, how saves from before [`NamedItem`] have them.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Deserialize)]
+#[serde(remote = "GatherItem")]
+enum PositionalItem {
+    Key(String, Dimension, u32, u32),
+    BulkheadKey(String),
+    HSU(u32, char),
+    Generator(String, u8, u8),
+    ID(String, u32),
+    PD(String, u32),
+    Cell(u8),
+    FogTurbine(String),
+    Neonate(String),
+    Cryo(String),
+    GLP1(String, u32),
+    OSIP(String, u32),
+    Datasphere(u8),
+    PlantSample(String, u32),
+    HiSec(String),
+    DataCube(String, u32),
+    GLP2(String, u32),
+    Cargo(String),
+    MWP(String),
+    Uplink(String),
+    Seeded(String, u32),
+    Unknown(u8, String),
+}
+
+/// Either form a [`GatherItem`] is read from.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ItemRepr {
+    Named(NamedItem),
+    Positional(#[serde(with = "PositionalItem")] GatherItem),
+}
+
+impl From<ItemRepr> for GatherItem {
+    fn from(repr: ItemRepr) -> Self {
+        match repr {
+            ItemRepr::Named(item) => item.into(),
+            ItemRepr::Positional(item) => item,
+        }
+    }
+}
+
+impl From<NamedItem> for GatherItem {
+    fn from(item: NamedItem) -> Self {
+        match item {
+            NamedItem::Key {
+                name,
+                dimension,
+                zone,
+                ri,
+            } => GatherItem::Key(name, dimension, zone, ri),
+            NamedItem::BulkheadKey { name } => GatherItem::BulkheadKey(name),
+            NamedItem::HSU { area_id, area } => GatherItem::HSU(area_id, area),
+            NamedItem::Generator {
+                name,
+                collection,
+                idx,
+            } => GatherItem::Generator(name, collection, idx),
+            NamedItem::ID { container, seed } => GatherItem::ID(container, seed),
+            NamedItem::PD { container, seed } => GatherItem::PD(container, seed),
+            NamedItem::Cell { zone_idx } => GatherItem::Cell(zone_idx),
+            NamedItem::FogTurbine { name } => GatherItem::FogTurbine(name),
+            NamedItem::Neonate { name } => GatherItem::Neonate(name),
+            NamedItem::Cryo { name } => GatherItem::Cryo(name),
+            NamedItem::GLP1 { container, seed } => GatherItem::GLP1(container, seed),
+            NamedItem::OSIP { container, seed } => GatherItem::OSIP(container, seed),
+            NamedItem::Datasphere { zone_idx } => GatherItem::Datasphere(zone_idx),
+            NamedItem::PlantSample { container, seed } => GatherItem::PlantSample(container, seed),
+            NamedItem::HiSec { name } => GatherItem::HiSec(name),
+            NamedItem::DataCube { container, seed } => GatherItem::DataCube(container, seed),
+            NamedItem::GLP2 { container, seed } => GatherItem::GLP2(container, seed),
+            NamedItem::Cargo { name } => GatherItem::Cargo(name),
+            NamedItem::MWP { name } => GatherItem::MWP(name),
+            NamedItem::Uplink { name } => GatherItem::Uplink(name),
+            NamedItem::Seeded { container, seed } => GatherItem::Seeded(container, seed),
+            NamedItem::Unknown { id, name } => GatherItem::Unknown(id, name),
+        }
+    }
 }
 
-#[derive(FromRepr, Debug, Serialize, Deserialize, Clone)]
+impl From<GatherItem> for NamedItem {
+    fn from(item: GatherItem) -> Self {
+        match item {
+            GatherItem::Key(name, dimension, zone, ri) => NamedItem::Key {
+                name,
+                dimension,
+                zone,
+                ri,
+            },
+            GatherItem::BulkheadKey(name) => NamedItem::BulkheadKey { name },
+            GatherItem::HSU(area_id, area) => NamedItem::HSU { area_id, area },
+            GatherItem::Generator(name, collection, idx) => NamedItem::Generator {
+                name,
+                collection,
+                idx,
+            },
+            GatherItem::ID(container, seed) => NamedItem::ID { container, seed },
+            GatherItem::PD(container, seed) => NamedItem::PD { container, seed },
+            GatherItem::Cell(zone_idx) => NamedItem::Cell { zone_idx },
+            GatherItem::FogTurbine(name) => NamedItem::FogTurbine { name },
+            GatherItem::Neonate(name) => NamedItem::Neonate { name },
+            GatherItem::Cryo(name) => NamedItem::Cryo { name },
+            GatherItem::GLP1(container, seed) => NamedItem::GLP1 { container, seed },
+            GatherItem::OSIP(container, seed) => NamedItem::OSIP { container, seed },
+            GatherItem::Datasphere(zone_idx) => NamedItem::Datasphere { zone_idx },
+            GatherItem::PlantSample(container, seed) => NamedItem::PlantSample { container, seed },
+            GatherItem::HiSec(name) => NamedItem::HiSec { name },
+            GatherItem::DataCube(container, seed) => NamedItem::DataCube { container, seed },
+            GatherItem::GLP2(container, seed) => NamedItem::GLP2 { container, seed },
+            GatherItem::Cargo(name) => NamedItem::Cargo { name },
+            GatherItem::MWP(name) => NamedItem::MWP { name },
+            GatherItem::Uplink(name) => NamedItem::Uplink { name },
+            GatherItem::Seeded(container, seed) => NamedItem::Seeded { container, seed },
+            GatherItem::Unknown(id, name) => NamedItem::Unknown { id, name },
+        }
+    }
+}
+
+impl Display for GatherItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GatherItem::Key(name, dim, alias, ri) => {
+                write!(f, "{name} ZONE_{alias} {dim} ri: {ri}")
+            }
+            GatherItem::BulkheadKey(name)
+            | GatherItem::FogTurbine(name)
+            | GatherItem::Neonate(name)
+            | GatherItem::Cryo(name)
+            | GatherItem::HiSec(name)
+            | GatherItem::Cargo(name)
+            | GatherItem::MWP(name)
+            | GatherItem::Unknown(_, name) => write!(f, "{name}"),
+            GatherItem::Uplink(name) => write!(f, "Uplink {name}"),
+            GatherItem::HSU(id, area) => write!(f, "HSU area {area} ({id})"),
+            GatherItem::Generator(name, item_idx, idx) => {
+                write!(f, "{name} #{idx} item {item_idx}")
+            }
+            GatherItem::ID(container, seed) => write!(f, "ID {container} {seed}"),
+            GatherItem::PD(container, seed) => write!(f, "PD {container} {seed}"),
+            GatherItem::Cell(idx) => write!(f, "CELL_{idx}"),
+            GatherItem::GLP1(container, seed) => write!(f, "GLP-1 {container} {seed}"),
+            GatherItem::OSIP(container, seed) => write!(f, "OSIP {container} {seed}"),
+            GatherItem::Datasphere(zone_idx) => write!(f, "Datasphere zone idx {zone_idx}"),
+            GatherItem::PlantSample(container, seed) => {
+                write!(f, "Plant Sample {container} {seed}")
+            }
+            GatherItem::DataCube(container, seed) => write!(f, "Data Cube {container} {seed}"),
+            GatherItem::GLP2(container, seed) => write!(f, "GLP-2 {container} {seed}"),
+            GatherItem::Seeded(container, seed) => write!(f, "{container} {seed}"),
+        }
+    }
+}
+
+impl GatherItem {
+    /// Whether a pickup could be this item. Keys match on name and zone, named items on
+    /// name and seeded items on seed. Cells and dataspheres only have their spawn index, so
+    /// any carried cell or datasphere matches them. HSUs and uplink terminals can't be picked
+    /// up.
+    pub fn is_picked_up(&self, pickup: &Pickup) -> bool {
+        match self {
+            GatherItem::Key(name, _, alias, _) => *name == pickup.item && *alias == pickup.zone,
+            GatherItem::BulkheadKey(name)
+            | GatherItem::Generator(name, _, _)
+            | GatherItem::FogTurbine(name)
+            | GatherItem::Neonate(name)
+            | GatherItem::Cryo(name)
+            | GatherItem::HiSec(name)
+            | GatherItem::Cargo(name)
+            | GatherItem::MWP(name)
+            | GatherItem::Unknown(_, name) => *name == pickup.item,
+            GatherItem::ID(_, seed)
+            | GatherItem::PD(_, seed)
+            | GatherItem::GLP1(_, seed)
+            | GatherItem::OSIP(_, seed)
+            | GatherItem::PlantSample(_, seed)
+            | GatherItem::DataCube(_, seed)
+            | GatherItem::GLP2(_, seed)
+            | GatherItem::Seeded(_, seed) => *seed == pickup.seed,
+            GatherItem::Cell(_) => pickup.item.to_uppercase().contains("CELL"),
+            GatherItem::Datasphere(_) => pickup.item.to_uppercase().contains("DATASPHERE"),
+            GatherItem::HSU(..) | GatherItem::Uplink(_) => false,
+        }
+    }
+
+    /// Special carry item a spawn line names, i.e. `NEONATE_HSU` or `MWP`, named like the
+    /// ones [`ItemIdentifier::named`] makes.
+    pub fn carried(spawn_name: &str, alias: u32, idx: u32) -> Option<GatherItem> {
+        let spawn_name = spawn_name.to_ascii_uppercase();
+        // HiSec cargo has `CARGO` in its name too.
+        let identifier = [
+            ItemIdentifier::Neonate,
+            ItemIdentifier::Cryo,
+            ItemIdentifier::HiSec,
+            ItemIdentifier::Cargo,
+        ]
+        .into_iter()
+        .find(|identifier| identifier.matches_pickup(&spawn_name));
+
+        match identifier {
+            Some(identifier) => identifier.named(alias, idx),
+            None if spawn_name.contains("MWP") || spawn_name.contains("MATTERWAVE") => {
+                Some(GatherItem::MWP(format!("MWP ZONE_{alias} #{idx}")))
+            }
+            None => None,
+        }
+    }
+
+    /// Whether this is one of the big items players carry to finish an objective, usually
+    /// the first ones they look for.
+    pub fn is_carry_objective(&self) -> bool {
+        matches!(
+            self,
+            GatherItem::Neonate(_)
+                | GatherItem::Cryo(_)
+                | GatherItem::HiSec(_)
+                | GatherItem::Cargo(_)
+                | GatherItem::MWP(_)
+        )
+    }
+
+    /// Container and seed of the items that are placed by one.
+    pub fn container_seed(&self) -> Option<(&str, u32)> {
+        match self {
+            GatherItem::ID(container, seed)
+            | GatherItem::PD(container, seed)
+            | GatherItem::GLP1(container, seed)
+            | GatherItem::OSIP(container, seed)
+            | GatherItem::PlantSample(container, seed)
+            | GatherItem::DataCube(container, seed)
+            | GatherItem::GLP2(container, seed)
+            | GatherItem::Seeded(container, seed) => Some((container, *seed)),
+            _ => None,
+        }
+    }
+
+    /// Prefix of the serial players see on the item and where it's in the order of its zone's
+    /// items of the same kind, for the items that have one.
+    pub fn serial_slot(&self) -> Option<(&'static str, u32)> {
+        match self {
+            GatherItem::Cell(idx) => Some(("CELL", *idx as u32)),
+            GatherItem::Datasphere(idx) => Some(("DATASPHERE", *idx as u32)),
+            GatherItem::HSU(id, _) => Some(("HSU", *id)),
+            _ => None,
+        }
+    }
+}
+
+/// Item picked up by a player during the run.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Pickup {
+    pub item: String,
+    pub zone: u32,
+    pub seed: u32,
+}
+
+impl Display for Pickup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ZONE_{} seed: {}", self.item, self.zone, self.seed)
+    }
+}
+
+#[derive(FromRepr, Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[repr(u8)]
 pub enum ItemIdentifier {
     ID = 128,
@@ -67,4 +435,157 @@ pub enum ItemIdentifier {
     DataCube = 168,
     GLP2 = 169,
     Cargo = 176,
+    /// Id of neither a stock item nor one a profile maps, see [`ItemIdentifier::from_id`].
+    /// `from_repr` can't build it, it would make up the id.
+    Unknown(u8) = 255,
+}
+
+impl TryFrom<u8> for ItemIdentifier {
+    /// The id, wrapped so it isn't lost
+    type Error = ItemIdentifier;
+
+    /// Stock item with `id`, or `Err(Unknown(id))`.
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match ItemIdentifier::from_repr(id) {
+            Some(ItemIdentifier::Unknown(_)) | None => Err(ItemIdentifier::Unknown(id)),
+            Some(item) => Ok(item),
+        }
+    }
+}
+
+impl ItemIdentifier {
+    /// Stock item with `id`, [`ItemIdentifier::Unknown`] for the rest.
+    pub fn from_id(id: u8) -> Self {
+        ItemIdentifier::try_from(id).unwrap_or_else(|unknown| unknown)
+    }
+
+    /// Name shown to players, i.e. `GLP-1`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ItemIdentifier::ID => "ID",
+            ItemIdentifier::PD => "PD",
+            ItemIdentifier::Cell => "Cell",
+            ItemIdentifier::FogTurbine => "Fog Turbine",
+            ItemIdentifier::Neonate => "Neonate",
+            ItemIdentifier::Cryo => "Cryo",
+            ItemIdentifier::GLP1 => "GLP-1",
+            ItemIdentifier::OSIP => "OSIP",
+            ItemIdentifier::Datasphere => "Datasphere",
+            ItemIdentifier::PlantSample => "Plant Sample",
+            ItemIdentifier::HiSec => "HiSec Cargo",
+            ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => "Data Cube",
+            ItemIdentifier::GLP2 => "GLP-2",
+            ItemIdentifier::Cargo => "Cargo",
+            ItemIdentifier::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Kind of the mapped items with this identifier.
+    pub fn kind(&self) -> GatherKind {
+        match self {
+            ItemIdentifier::ID => GatherKind::ID,
+            ItemIdentifier::PD => GatherKind::PD,
+            ItemIdentifier::Cell => GatherKind::Cell,
+            ItemIdentifier::FogTurbine => GatherKind::FogTurbine,
+            ItemIdentifier::Neonate => GatherKind::Neonate,
+            ItemIdentifier::Cryo => GatherKind::Cryo,
+            ItemIdentifier::GLP1 => GatherKind::GLP1,
+            ItemIdentifier::OSIP => GatherKind::OSIP,
+            ItemIdentifier::Datasphere => GatherKind::Datasphere,
+            ItemIdentifier::PlantSample => GatherKind::PlantSample,
+            ItemIdentifier::HiSec => GatherKind::HiSec,
+            ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => GatherKind::DataCube,
+            ItemIdentifier::GLP2 => GatherKind::GLP2,
+            ItemIdentifier::Cargo => GatherKind::Cargo,
+            ItemIdentifier::Unknown(_) => GatherKind::Unknown,
+        }
+    }
+
+    /// Whether the item gets its container and seed from the FunctionMarkers batch.
+    pub fn is_seeded(&self) -> bool {
+        matches!(
+            self,
+            ItemIdentifier::ID
+                | ItemIdentifier::PD
+                | ItemIdentifier::GLP1
+                | ItemIdentifier::OSIP
+                | ItemIdentifier::PlantSample
+                | ItemIdentifier::DataCube
+                | ItemIdentifier::DataCubeR8
+                | ItemIdentifier::GLP2
+        )
+    }
+
+    /// Whether a spawn line's item name, i.e. `GLP` or `Plant Sample`, is this item. Case,
+    /// spaces and dashes don't matter.
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name: String = name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let names: &[&str] = match self {
+            ItemIdentifier::ID => &["ID"],
+            ItemIdentifier::PD => &["PD", "PARTIALDECODER"],
+            ItemIdentifier::GLP1 => &["GLP", "GLP1"],
+            ItemIdentifier::OSIP => &["OSIP"],
+            ItemIdentifier::PlantSample => &["PLANT", "PLANTSAMPLE"],
+            ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => &["CUBE", "DATACUBE"],
+            ItemIdentifier::GLP2 => &["GLP", "GLP2"],
+            _ => &[],
+        };
+
+        names.contains(&name.as_str())
+    }
+
+    /// Whether a pickup line's item name is this item, like [`ItemIdentifier::matches_name`]
+    /// but also for the items that aren't seeded.
+    pub fn matches_pickup(&self, name: &str) -> bool {
+        let marker = match self {
+            ItemIdentifier::Cell => "CELL",
+            ItemIdentifier::FogTurbine => "TURBINE",
+            ItemIdentifier::Neonate => "NEONATE",
+            ItemIdentifier::Cryo => "CRYO",
+            ItemIdentifier::Datasphere => "DATASPHERE",
+            ItemIdentifier::HiSec => "HISEC",
+            ItemIdentifier::Cargo => "CARGO",
+            _ => return self.matches_name(name),
+        };
+
+        name.to_ascii_uppercase().contains(marker)
+    }
+
+    /// Item for a seeded identifier, see [`ItemIdentifier::is_seeded`].
+    pub fn seeded(&self, container: &str, seed: u32) -> Option<GatherItem> {
+        let container = container.to_string();
+        Some(match self {
+            ItemIdentifier::ID => GatherItem::ID(container, seed),
+            ItemIdentifier::PD => GatherItem::PD(container, seed),
+            ItemIdentifier::GLP1 => GatherItem::GLP1(container, seed),
+            ItemIdentifier::OSIP => GatherItem::OSIP(container, seed),
+            ItemIdentifier::PlantSample => GatherItem::PlantSample(container, seed),
+            ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => {
+                GatherItem::DataCube(container, seed)
+            }
+            ItemIdentifier::GLP2 => GatherItem::GLP2(container, seed),
+            _ => return None,
+        })
+    }
+
+    /// Item for an identifier that the Distribution batch alone describes. These have no
+    /// seed, so they are named after the zone and the distribution index inside it. Cells
+    /// and dataspheres only keep the index.
+    pub fn named(&self, alias: u32, idx: u32) -> Option<GatherItem> {
+        let name = |label: &str| format!("{label} ZONE_{alias} #{idx}");
+        Some(match self {
+            ItemIdentifier::Cell => GatherItem::Cell(u8::try_from(idx).ok()?),
+            ItemIdentifier::FogTurbine => GatherItem::FogTurbine(name("Fog Turbine")),
+            ItemIdentifier::Neonate => GatherItem::Neonate(name("Neonate")),
+            ItemIdentifier::Cryo => GatherItem::Cryo(name("Cryo")),
+            ItemIdentifier::Datasphere => GatherItem::Datasphere(u8::try_from(idx).ok()?),
+            ItemIdentifier::HiSec => GatherItem::HiSec(name("HiSec Cargo")),
+            ItemIdentifier::Cargo => GatherItem::Cargo(name("Cargo")),
+            _ => return None,
+        })
+    }
 }
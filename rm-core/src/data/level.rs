@@ -1,44 +1,1394 @@
-use std::{collections::HashMap, fmt::Display, ops::Index};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Write},
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use anyhow::anyhow;
+use jiff::civil::{DateTime, Time};
 use serde::{Deserialize, Serialize};
 
-use super::{GatherItem, GatherableMap, Rundown, TimerEntry, Zone};
+use crate::profile::RundownProfile;
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+use super::{
+    elapsed, format_duration, Dimension, GatherItem, GatherKind, GatherableMap, ItemQuery,
+    Objective, Pickup, RunStats, Rundown, Seeds, Splits, TimerEntry, Zone, ZoneGraph, ZoneKey,
+};
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Level {
     /// General info about level
     pub rundown: Rundown,
+    /// Profile of a [`Rundown::Modded`] rundown, if one describes it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modded: Option<RundownProfile>,
     pub exp_name: String,
+    pub seeds: Option<Seeds>,
     pub timer_zones: Vec<TimerEntry>,
 
     /// Learning mode
     pub zones: Vec<Zone>,
-    pub gathatable_items: HashMap<Zone, GatherItem>,
+    /// Zone alias pairs connected during floor setup, `(from, to)`
+    pub connections: Vec<(u32, u32)>,
+    #[serde(with = "zone_map")]
+    pub gathatable_items: HashMap<Zone, Vec<GatherItem>>,
+    /// Items in the order the parser found them
+    pub gatherables: Vec<GatherItem>,
+    /// Serials logged during distribution by zone alias, in the order they were logged
+    #[serde(default)]
+    pub serials: Vec<(u32, String)>,
+    /// Items the warden objectives ask for
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /// Mapped items that were picked up this run
+    pub collected: Vec<GatherItem>,
+    /// Pickups that didn't match any mapped item
+    pub unmapped_pickups: Vec<Pickup>,
+    /// How the run ended, `None` while it's still going
+    pub outcome: Option<Outcome>,
+    /// Log time generation finished, the level is ready once it's set
+    pub started: Option<Time>,
+    #[serde(default)]
+    pub splits: Splits,
+    #[serde(default)]
+    pub timeline: Timeline,
     pub maps: Vec<GatherableMap>,
 }
 
-impl Index<(u32, String)> for Level {
-    type Output = Zone;
+/// Log times of what happened on the level. Durations between them, or since the drop in
+/// [`Splits::start`], come from [`elapsed`].
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Timeline {
+    /// When each zone was generated, by alias
+    pub zones: Vec<(u32, Time)>,
+    /// When each item was distributed
+    pub items: Vec<(GatherItem, Time)>,
+    /// When each mapped item was picked up
+    pub collected: Vec<(GatherItem, Time)>,
+    /// Scans and alarms during the run, in the order they started
+    pub events: Vec<(LevelEvent, Time)>,
+}
 
-    fn index(&self, index: (u32, String)) -> &Self::Output {
-        self.zones
-            .iter()
-            .find(|v| v.alias == index.0 && v.dimension.eq(&index.1))
-            .unwrap()
+/// Something during the run worth finding again when reviewing it, like an alarm.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelEvent {
+    /// Security scan started, on the door into the zone with the given alias if it's known.
+    /// Has the alarm class if it's an alarm, i.e. `Class III Alarm`
+    Scan(Option<u32>, Option<String>),
+    /// Error alarm started, in the zone with the given alias if it's known
+    ErrorAlarm(Option<u32>),
+}
+
+impl Display for LevelEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let zone = |alias: &Option<u32>| alias.map(|alias| format!(" ZONE_{alias}"));
+        match self {
+            LevelEvent::Scan(alias, class) => {
+                write!(f, "Security scan{}", zone(alias).unwrap_or_default())?;
+                match class {
+                    Some(class) => write!(f, " ({class})"),
+                    None => Ok(()),
+                }
+            }
+            LevelEvent::ErrorAlarm(alias) => {
+                write!(f, "Error alarm{}", zone(alias).unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Success,
+    Failed,
+    /// Back to the lobby or the game was closed before the run ended
+    Aborted,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Success => write!(f, "Success"),
+            Outcome::Failed => write!(f, "Failed"),
+            Outcome::Aborted => write!(f, "Aborted"),
+        }
+    }
+}
+
+/// Format version written by [`Level::save`]. Bump it when a change to [`Level`] needs
+/// older saves converted on load.
+pub const SAVE_VERSION: u64 = 1;
+
+/// Saved level with the format it was written in.
+#[derive(Serialize)]
+struct SavedLevel<'a> {
+    version: u64,
+    level: &'a Level,
+}
+
+/// Row of the item table written by [`Level::export_csv`] and [`Level::export_json`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemRow {
+    /// Zone the item is in, empty if it isn't known
+    pub zone: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub name: String,
+    pub container: Option<String>,
+    pub seed: Option<u32>,
+}
+
+/// Which files [`Level::export`] writes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveFormat {
+    #[default]
+    Json,
+    Markdown,
+    Both,
+}
+
+/// Default folder for exported levels.
+pub fn default_save_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("levels"))
+}
+
+/// JSON only takes string keys, so zone keyed items are stored as a list of pairs.
+///
+/// Saves from when a zone held a single item have the bare item instead of a list.
+mod zone_map {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::data::{GatherItem, Zone};
+
+    pub fn serialize<S: Serializer>(
+        map: &HashMap<Zone, Vec<GatherItem>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        map.iter().collect::<Vec<_>>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Zone, Vec<GatherItem>>, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Items {
+            Many(Vec<GatherItem>),
+            One(GatherItem),
+        }
+
+        let mut map: HashMap<Zone, Vec<GatherItem>> = HashMap::new();
+        for (zone, items) in Vec::<(Zone, Items)>::deserialize(deserializer)? {
+            let entry = map.entry(zone).or_default();
+            match items {
+                Items::Many(items) => entry.extend(items),
+                Items::One(item) => entry.push(item),
+            }
+        }
+
+        Ok(map)
     }
 }
 
 impl Display for Level {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Tutorial only has the one expedition, the name would just be noise.
+        if self.rundown == Rundown::Tutorial {
+            return write!(f, "Tutorial");
+        }
+
         let expedition = if self.exp_name != "E3" {
             &self.exp_name
         } else {
             &"E2".to_string()
         };
-        write!(f, "{:?}{}", self.rundown, expedition)
+        match &self.modded {
+            Some(modded) => write!(f, "{}{}", modded.name, expedition),
+            None => write!(f, "{:?}{}", self.rundown, expedition),
+        }
     }
 }
 
 impl Level {
-    // TODO: impl fn on Level to load level from file
+    /// Generated zone with `alias` in `dimension`, `None` if it wasn't generated.
+    pub fn get_zone(&self, alias: u32, dimension: &Dimension) -> Option<&Zone> {
+        self.zones
+            .iter()
+            .find(|zone| zone.alias == alias && zone.dimension == *dimension)
+    }
+
+    /// Generated zone of `key`. Keys that don't know the layer, i.e. from an alias alone,
+    /// fall back to the zone with their alias in their dimension.
+    pub fn zone(&self, key: &ZoneKey) -> Option<&Zone> {
+        self.zones
+            .iter()
+            .find(|zone| zone.is(key))
+            .or_else(|| self.get_zone(key.alias, &key.dimension))
+    }
+
+    /// What generation decided about the level, without anything about the run: its zones,
+    /// their connections, items, serials and objectives.
+    pub fn layout(&self) -> Level {
+        Level {
+            rundown: self.rundown.clone(),
+            modded: self.modded.clone(),
+            exp_name: self.exp_name.clone(),
+            seeds: self.seeds,
+            timer_zones: self.timer_zones.clone(),
+            zones: self.zones.clone(),
+            connections: self.connections.clone(),
+            gathatable_items: self.gathatable_items.clone(),
+            gatherables: self.gatherables.clone(),
+            serials: self.serials.clone(),
+            objectives: self.objectives.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Forgets the zones, items and serials of the level, i.e. a layout shown ahead of the
+    /// log that the log's own is about to replace.
+    pub fn clear_layout(&mut self) {
+        self.timer_zones.clear();
+        self.zones.clear();
+        self.connections.clear();
+        self.gathatable_items.clear();
+        self.gatherables.clear();
+        self.serials.clear();
+    }
+
+    /// Records a connection between two zones, ignoring duplicates in either direction.
+    pub fn connect(&mut self, from: u32, to: u32) {
+        if !self
+            .connections
+            .iter()
+            .any(|&(a, b)| (a, b) == (from, to) || (a, b) == (to, from))
+        {
+            self.connections.push((from, to));
+        }
+    }
+
+    /// Connections between the level's zones as a graph, for route planning and laying out
+    /// the map.
+    pub fn zone_graph(&self) -> ZoneGraph {
+        ZoneGraph::new(self.zones.iter().map(|zone| zone.alias), &self.connections)
+    }
+
+    /// Marks the first uncollected item matching the pickup as collected, zone-keyed items
+    /// also have to be in the pickup's zone. Returns `false` and keeps the pickup in
+    /// `unmapped_pickups` if nothing matched.
+    ///
+    /// `at` is the log time of the pickup, kept in the [`Timeline`].
+    pub fn collect(&mut self, pickup: Pickup, at: Option<Time>) -> bool {
+        let found = self
+            .all_items()
+            .filter(|(zone, _)| zone.is_none_or(|zone| zone.alias == pickup.zone))
+            .map(|(_, item)| item)
+            .find(|item| !self.collected.contains(item) && item.is_picked_up(&pickup))
+            .cloned();
+
+        match found {
+            Some(item) => {
+                if let Some(at) = at {
+                    self.timeline.collected.push((item.clone(), at));
+                }
+                self.collected.push(item);
+                true
+            }
+            None => {
+                self.unmapped_pickups.push(pickup);
+                false
+            }
+        }
+    }
+
+    /// Every item on the level with its zone, if known: first `gathatable_items` in no
+    /// particular order, then `gatherables` in the order they were parsed. Keys in
+    /// `gatherables` are paired with their zone from `zones`. Zones of items filed under
+    /// an area have it set.
+    pub fn all_items(&self) -> impl Iterator<Item = (Option<&Zone>, &GatherItem)> {
+        let keyed = self
+            .gathatable_items
+            .iter()
+            .flat_map(|(zone, items)| items.iter().map(move |item| (Some(zone), item)));
+        let listed = self.gatherables.iter().map(|item| {
+            let zone = match item {
+                GatherItem::Key(_, dim, alias, _) => self.get_zone(*alias, dim),
+                _ => None,
+            };
+            (zone, item)
+        });
+
+        keyed.chain(listed)
+    }
+
+    /// Files an item under the zone of `key`. Zones that weren't generated get a
+    /// [`Zone::placeholder`], so the item still shows up with its zone.
+    pub fn add_to_zone(&mut self, key: impl Into<ZoneKey>, item: GatherItem) {
+        self.file_item(key.into(), None, item);
+    }
+
+    /// Files an item under `area` of the zone of `key`, like [`Level::add_to_zone`].
+    pub fn add_to_area(&mut self, key: impl Into<ZoneKey>, area: char, item: GatherItem) {
+        self.file_item(key.into(), Some(area), item);
+    }
+
+    fn file_item(&mut self, key: ZoneKey, area: Option<char>, item: GatherItem) {
+        let mut zone = match self.zone(&key) {
+            Some(zone) => zone.clone(),
+            None => {
+                let zone = Zone::placeholder(key);
+                self.zones.push(zone.clone());
+                zone
+            }
+        };
+        zone.area = area;
+
+        self.gathatable_items.entry(zone).or_default().push(item);
+    }
+
+    /// Areas of `zone` that have items, sorted.
+    pub fn areas(&self, zone: &Zone) -> Vec<char> {
+        let mut areas: Vec<char> = self
+            .gathatable_items
+            .keys()
+            .filter(|z| z.alias == zone.alias && z.dimension == zone.dimension)
+            .filter_map(|z| z.area)
+            .collect();
+        areas.sort();
+        areas.dedup();
+        areas
+    }
+
+    /// Splits on the zone with `alias` the first time its door opens. Returns `false` if the
+    /// zone wasn't generated or didn't split.
+    pub fn split_zone(&mut self, alias: u32, at: Time) -> bool {
+        let Some(entry) = self
+            .timer_zones
+            .iter()
+            .find(|entry| matches!(entry, TimerEntry::Zone(zone) if zone.alias == alias))
+        else {
+            return false;
+        };
+
+        self.splits.split(entry.clone(), at)
+    }
+
+    /// Dimensions with generated zones, in the order they were generated.
+    pub fn dimensions(&self) -> Vec<&Dimension> {
+        let mut dimensions = vec![];
+        for zone in &self.zones {
+            if !dimensions.contains(&&zone.dimension) {
+                dimensions.push(&zone.dimension);
+            }
+        }
+
+        dimensions
+    }
+
+    /// Serials in the zone with `alias` that start with `prefix`, like the zone's terminals
+    /// for `TERMINAL`.
+    pub fn zone_serials<'a>(
+        &'a self,
+        alias: u32,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = &'a str> {
+        self.serials
+            .iter()
+            .filter(move |(zone, serial)| {
+                *zone == alias
+                    && serial
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('_'))
+            })
+            .map(|(_, serial)| serial.as_str())
+    }
+
+    /// Serial players see on `item` in the zone with `alias`, see
+    /// [`GatherItem::serial_slot`]. The game hands them out in spawn order, so the zone's
+    /// items of a kind get its serials of that kind in the same order.
+    pub fn serial(&self, alias: u32, item: &GatherItem) -> Option<&str> {
+        let (prefix, slot) = item.serial_slot()?;
+        let mut slots: Vec<u32> = self
+            .all_items()
+            .filter(|(zone, _)| zone.is_some_and(|zone| zone.alias == alias))
+            .filter_map(|(_, other)| other.serial_slot())
+            .filter(|(other, _)| *other == prefix)
+            .map(|(_, slot)| slot)
+            .collect();
+        slots.sort_unstable();
+        slots.dedup();
+
+        let nth = slots.iter().position(|&other| other == slot)?;
+        self.zone_serials(alias, prefix).nth(nth)
+    }
+
+    /// How `item` is listed, with the serial players see on it in the zone with `alias` when
+    /// it was logged.
+    pub fn item_label(&self, alias: Option<u32>, item: &GatherItem) -> String {
+        match alias.and_then(|alias| self.serial(alias, item)) {
+            Some(serial) => format!("{serial} {item}"),
+            None => item.to_string(),
+        }
+    }
+
+    /// Items of [`Level::all_items`] that pass `query`, with their [`Level::item_label`].
+    pub fn query(&self, query: &ItemQuery) -> Vec<(Option<&Zone>, &GatherItem, String)> {
+        self.all_items()
+            .filter(|(zone, _)| zone.is_none_or(|zone| query.shows_layer(&zone.layer)))
+            .filter_map(|(zone, item)| {
+                let label = self.item_label(zone.map(|zone| zone.alias), item);
+                query.matches(item, &label).then_some((zone, item, label))
+            })
+            .collect()
+    }
+
+    /// Layers with generated zones, in the order they were generated.
+    pub fn layers(&self) -> Vec<&str> {
+        let mut layers = vec![];
+        for zone in &self.zones {
+            if !layers.contains(&zone.layer.as_str()) {
+                layers.push(zone.layer.as_str());
+            }
+        }
+
+        layers
+    }
+
+    /// Collected and total number of mapped items in zones of `layer`.
+    pub fn layer_progress(&self, layer: &str) -> (usize, usize) {
+        let items: Vec<_> = self
+            .all_items()
+            .filter(|(zone, _)| zone.is_some_and(|zone| zone.layer == layer))
+            .collect();
+        let collected = items
+            .iter()
+            .filter(|(_, item)| self.is_collected(item))
+            .count();
+
+        (collected, items.len())
+    }
+
+    /// Generated zones of `dimension`, sorted by alias.
+    pub fn zones_in(&self, dimension: &Dimension) -> Vec<&Zone> {
+        let mut zones: Vec<_> = self
+            .zones
+            .iter()
+            .filter(|zone| zone.dimension == *dimension)
+            .collect();
+        zones.sort_by_key(|zone| zone.alias);
+        zones
+    }
+
+    /// Items of [`Level::all_items`] with a zone in `dimension`.
+    pub fn items_in<'a>(
+        &'a self,
+        dimension: &'a Dimension,
+    ) -> impl Iterator<Item = (&'a Zone, &'a GatherItem)> {
+        self.all_items()
+            .filter_map(|(zone, item)| Some((zone?, item)))
+            .filter(move |(zone, _)| zone.dimension == *dimension)
+    }
+
+    /// Ticks `item` off by hand, or unticks it if it was collected. For pickups the
+    /// patterns missed.
+    pub fn toggle_collected(&mut self, item: &GatherItem) {
+        match self
+            .collected
+            .iter()
+            .position(|collected| collected == item)
+        {
+            Some(idx) => {
+                self.collected.remove(idx);
+            }
+            None => self.collected.push(item.clone()),
+        }
+    }
+
+    /// Collected and total number of mapped items.
+    pub fn progress(&self) -> (usize, usize) {
+        let total = self.all_items().count();
+        let collected = self
+            .all_items()
+            .filter(|(_, item)| self.is_collected(item))
+            .count();
+
+        (collected, total)
+    }
+
+    /// Located and collected mapped items of the objective's kind.
+    pub fn objective_progress(&self, objective: &Objective) -> (usize, usize) {
+        let kind = objective.item.kind();
+        let items: Vec<&GatherItem> = self
+            .all_items()
+            .map(|(_, item)| item)
+            .filter(|item| GatherKind::from(*item) == kind)
+            .collect();
+        let collected = items.iter().filter(|item| self.is_collected(item)).count();
+
+        (items.len(), collected)
+    }
+
+    /// Progress towards an objective, i.e. `GLP-1: 4/6 located, 2/6 collected`.
+    pub fn objective_summary(&self, objective: &Objective) -> String {
+        let (located, collected) = self.objective_progress(objective);
+        let count = objective.count;
+        format!(
+            "{}: {located}/{count} located, {collected}/{count} collected",
+            objective.item.label()
+        )
+    }
+
+    /// Time from the elevator drop to `at`, `None` before the drop.
+    pub fn since_drop(&self, at: Time) -> Option<Duration> {
+        Some(elapsed(self.splits.start?, at))
+    }
+
+    /// Scans and alarms with their time since the elevator drop, `None` for ones before it.
+    pub fn events_since_drop(&self) -> Vec<(Option<Duration>, &LevelEvent)> {
+        self.timeline
+            .events
+            .iter()
+            .map(|(event, at)| {
+                let before_drop = self.splits.start.is_some_and(|start| *at < start);
+                let since = self.since_drop(*at).filter(|_| !before_drop);
+                (since, event)
+            })
+            .collect()
+    }
+
+    /// Timeline of the run's scans and alarms as text for syncing with a VOD, one event per
+    /// line, i.e. `00:03:12 Security scan ZONE_50 (Class III Alarm)`.
+    pub fn events_text(&self) -> String {
+        let mut out = String::new();
+        for (since, event) in self.events_since_drop() {
+            let since = since.map_or("--:--:--".to_string(), format_duration);
+            let _ = writeln!(out, "{since} {event}");
+        }
+        out
+    }
+
+    /// Log time `item` was picked up, if it was and the line had one.
+    pub fn collected_at(&self, item: &GatherItem) -> Option<Time> {
+        self.timeline
+            .collected
+            .iter()
+            .find(|(collected, _)| collected == item)
+            .map(|(_, at)| *at)
+    }
+
+    pub fn is_collected(&self, item: &GatherItem) -> bool {
+        self.collected.contains(item)
+    }
+
+    /// Writes the level as pretty printed JSON, tagged with [`SAVE_VERSION`].
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let saved = SavedLevel {
+            version: SAVE_VERSION,
+            level: self,
+        };
+        fs::write(path, serde_json::to_string_pretty(&saved)?)?;
+        Ok(())
+    }
+
+    /// Reads a level written by [`Level::save`]. Saves from before the format had a
+    /// version are the bare level.
+    pub fn load(path: &Path) -> anyhow::Result<Level> {
+        let mut saved: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        let version = match saved.get("version") {
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| anyhow!("invalid save version {version}"))?,
+            None => 0,
+        };
+
+        match version {
+            0 => Ok(serde_json::from_value(saved)?),
+            SAVE_VERSION => Ok(serde_json::from_value(saved["level"].take())?),
+            _ => Err(anyhow!(
+                "save format {version} is newer than the supported {SAVE_VERSION}"
+            )),
+        }
+    }
+
+    /// Folder for this expedition's saves inside `root`, i.e. `root/R1A1`.
+    pub fn expedition_dir(&self, root: &Path) -> PathBuf {
+        root.join(self.to_string())
+    }
+
+    /// File name without extension, i.e. `R1_A1_12-34-56_2024-07-01T20-03-18`.
+    pub fn file_stem(&self, at: DateTime) -> String {
+        let mut stem = format!("{:?}_{}", self.rundown, self.exp_name);
+        if let Some(seeds) = self.seeds {
+            let _ = write!(stem, "_{}", seeds.tuple());
+        }
+        let _ = write!(stem, "_{}", at.strftime("%Y-%m-%dT%H-%M-%S"));
+
+        stem
+    }
+
+    /// Writes the level into `dir` in the given format, creating `dir` if it's missing, and
+    /// its [`RunStats`] next to it.
+    ///
+    /// Returns the written files, the stats last.
+    pub fn export(&self, dir: &Path, format: SaveFormat) -> anyhow::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+
+        let stem = self.file_stem(jiff::Zoned::now().datetime());
+        let mut written = vec![];
+
+        if matches!(format, SaveFormat::Json | SaveFormat::Both) {
+            let path = dir.join(format!("{stem}.json"));
+            self.save(&path)?;
+            written.push(path);
+        }
+        if matches!(format, SaveFormat::Markdown | SaveFormat::Both) {
+            let path = dir.join(format!("{stem}.md"));
+            fs::write(&path, self.to_markdown())?;
+            written.push(path);
+        }
+        let path = dir.join(format!("{stem}.stats.json"));
+        RunStats::new(self).save(&path)?;
+        written.push(path);
+
+        Ok(written)
+    }
+
+    /// Every item as a table row, sorted like [`Level::to_markdown`].
+    pub fn item_rows(&self) -> Vec<ItemRow> {
+        let mut rows: Vec<_> = self
+            .all_items()
+            .map(|(zone, item)| {
+                let zone = match (zone, item) {
+                    (Some(zone), _) => Some((zone.alias, &zone.dimension, zone.area)),
+                    (None, GatherItem::Key(_, dim, alias, _)) => Some((*alias, dim, None)),
+                    (None, _) => None,
+                };
+                let (container, seed) = item
+                    .container_seed()
+                    .map(|(container, seed)| (container.to_string(), seed))
+                    .unzip();
+                let kind = GatherKind::from(item);
+
+                // Unknown zones sort last.
+                (
+                    (zone.is_none(), zone, kind),
+                    ItemRow {
+                        zone: zone.map_or_else(String::new, |(alias, dim, area)| {
+                            self.zone_label(alias, dim, area)
+                        }),
+                        kind: format!("{kind:?}"),
+                        name: item.to_string(),
+                        container,
+                        seed,
+                    },
+                )
+            })
+            .collect();
+        rows.sort_by(|(a, a_row), (b, b_row)| a.cmp(b).then_with(|| a_row.name.cmp(&b_row.name)));
+
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+
+    /// Writes [`Level::item_rows`] as CSV with a header row.
+    pub fn export_csv(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(path)?;
+        for row in self.item_rows() {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes [`Level::item_rows`] as a pretty printed JSON list.
+    pub fn export_json(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(&self.item_rows())?)?;
+        Ok(())
+    }
+
+    /// Zone heading used by the text and table exports.
+    fn zone_label(&self, alias: u32, dim: &Dimension, area: Option<char>) -> String {
+        self.get_zone(alias, dim).map_or_else(
+            || format!("ZONE_{alias} {dim}"),
+            |z| Zone { area, ..z.clone() }.to_string(),
+        )
+    }
+
+    /// Tier letter taken from the expedition name, `A1` is tier `A`.
+    pub fn tier(&self) -> Option<char> {
+        self.exp_name
+            .chars()
+            .next()
+            .filter(char::is_ascii_alphabetic)
+    }
+
+    /// [`Level::tier`] as the modded rundown's profile names it, the letter otherwise.
+    pub fn tier_name(&self) -> Option<String> {
+        let tier = self.tier()?.to_string();
+        Some(
+            self.modded
+                .as_ref()
+                .and_then(|modded| modded.tiers.get(&tier))
+                .cloned()
+                .unwrap_or(tier),
+        )
+    }
+
+    /// Text summary of the level for pasting into chat.
+    ///
+    /// Zones are sorted by alias and items by type then name, so the same level always
+    /// produces the same text. Items in an area get a heading after their zone's. Items
+    /// without a known zone are listed last.
+    pub fn to_markdown(&self) -> String {
+        let mut zoned: BTreeMap<(u32, &Dimension, Option<char>), Vec<&GatherItem>> =
+            BTreeMap::new();
+        let mut unzoned: Vec<&GatherItem> = vec![];
+
+        for (zone, item) in self.all_items() {
+            match (zone, item) {
+                (Some(zone), _) => zoned
+                    .entry((zone.alias, &zone.dimension, zone.area))
+                    .or_default()
+                    .push(item),
+                // Key in a zone that wasn't generated, still worth its own heading.
+                (None, GatherItem::Key(_, dim, alias, _)) => {
+                    zoned.entry((*alias, dim, None)).or_default().push(item)
+                }
+                (None, _) => unzoned.push(item),
+            }
+        }
+
+        let mut out = format!("## {self}\n\n");
+        let _ = writeln!(out, "- Rundown: {:?}", self.rundown);
+        if let Some(tier) = self.tier_name() {
+            let _ = writeln!(out, "- Tier: {tier}");
+        }
+        let _ = writeln!(out, "- Expedition: {}", self.exp_name);
+        if let Some(seeds) = self.seeds {
+            let _ = writeln!(out, "- Seeds: {seeds}");
+        }
+        if let Some(outcome) = self.outcome {
+            let _ = writeln!(out, "- Outcome: {outcome}");
+        }
+        for objective in &self.objectives {
+            let _ = writeln!(out, "- Objective: {}", self.objective_summary(objective));
+        }
+
+        let section = |out: &mut String, heading: String, mut items: Vec<&GatherItem>| {
+            items.sort_by_cached_key(|item| (GatherKind::from(*item), item.to_string()));
+
+            let _ = writeln!(out, "\n### {heading}\n");
+            for item in items {
+                let _ = writeln!(out, "- {item}");
+            }
+        };
+
+        for ((alias, dim, area), items) in zoned {
+            section(&mut out, self.zone_label(alias, dim, area), items);
+        }
+        if !unzoned.is_empty() {
+            section(&mut out, "Unknown zone".into(), unzoned);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{ItemCategory, ItemIdentifier},
+        testing::TempDir,
+    };
+
+    #[test]
+    fn connect_dedups_reversed_pairs() {
+        let mut level = Level::default();
+
+        level.connect(49, 50);
+        level.connect(50, 49);
+        level.connect(49, 50);
+        level.connect(50, 51);
+
+        assert_eq!(level.connections, vec![(49, 50), (50, 51)]);
+    }
+
+    #[test]
+    fn events_are_timed_from_the_drop() {
+        let t = |s: &str| s.parse::<Time>().unwrap();
+        let mut level = Level::default();
+        level.timeline.events = vec![
+            (LevelEvent::ErrorAlarm(None), t("20:03:00")),
+            (
+                LevelEvent::Scan(Some(50), Some("Class III Alarm".into())),
+                t("20:06:12"),
+            ),
+        ];
+        level.splits.start(t("20:03:00"));
+
+        assert_eq!(
+            level.events_text(),
+            "00:00:00 Error alarm\n00:03:12 Security scan ZONE_50 (Class III Alarm)\n"
+        );
+    }
+
+    #[test]
+    fn file_stem_has_level_seeds_and_time() {
+        let level = Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            seeds: Some(Seeds {
+                build: 12,
+                host: 34,
+                session: 56,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            level.file_stem(jiff::civil::date(2024, 7, 1).at(20, 3, 18, 0)),
+            "R1_A1_12-34-56_2024-07-01T20-03-18"
+        );
+    }
+
+    #[test]
+    fn zone_keyed_items_serialize_to_json() {
+        let zone = Zone {
+            alias: 49,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            gathatable_items: HashMap::from([(zone, vec![GatherItem::Cell(2)])]),
+            ..Default::default()
+        };
+
+        let tmp = TempDir::new("level");
+        let path = tmp.join("level.json");
+        level.save(&path).unwrap();
+        assert_eq!(Level::load(&path).unwrap(), level);
+    }
+
+    #[test]
+    fn single_item_zones_still_load() {
+        let zone = Zone {
+            alias: 49,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            gathatable_items: HashMap::from([(
+                zone.clone(),
+                vec![GatherItem::Cell(2), GatherItem::HSU(3, 'B')],
+            )]),
+            ..Default::default()
+        };
+
+        let mut json = serde_json::to_value(&level).unwrap();
+        json["gathatable_items"] = serde_json::json!([
+            [&zone, GatherItem::Cell(2)],
+            [&zone, GatherItem::HSU(3, 'B')],
+        ]);
+
+        assert_eq!(serde_json::from_value::<Level>(json).unwrap(), level);
+    }
+
+    #[test]
+    fn objectives_count_items_of_their_kind() {
+        let glp = |seed| GatherItem::GLP1("Box".into(), seed);
+        let level = Level {
+            gatherables: vec![glp(1), glp(2), glp(3), GatherItem::ID("Box".into(), 4)],
+            collected: vec![glp(2)],
+            ..Default::default()
+        };
+        let objective = Objective {
+            item: ItemIdentifier::GLP1,
+            count: 6,
+        };
+
+        assert_eq!(level.objective_progress(&objective), (3, 1));
+        assert_eq!(
+            level.objective_summary(&objective),
+            "GLP-1: 3/6 located, 1/6 collected"
+        );
+    }
+
+    #[test]
+    fn load_checks_save_version() {
+        let level = Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            ..Default::default()
+        };
+        let tmp = TempDir::new("version");
+        let path = tmp.join("version.json");
+
+        // Saves from before versioning are the bare level.
+        fs::write(&path, serde_json::to_string(&level).unwrap()).unwrap();
+        assert_eq!(Level::load(&path).unwrap(), level);
+
+        fs::write(&path, r#"{"version": 99, "level": {}}"#).unwrap();
+        assert!(Level::load(&path).is_err());
+    }
+
+    #[test]
+    fn all_items_pairs_keys_with_zones() {
+        let zone = Zone {
+            alias: 49,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            zones: vec![zone.clone()],
+            gathatable_items: HashMap::from([(zone.clone(), vec![GatherItem::Cell(2)])]),
+            gatherables: vec![
+                GatherItem::Key("KEY_RED_1".into(), "Reality".into(), 49, 2),
+                GatherItem::Key("KEY_RED_2".into(), "Reality".into(), 51, 2),
+                GatherItem::ID("Box".into(), 3),
+            ],
+            ..Default::default()
+        };
+
+        let zones: Vec<_> = level
+            .all_items()
+            .map(|(zone, _)| zone.map(|zone| zone.alias))
+            .collect();
+        assert_eq!(zones, vec![Some(49), Some(49), None, None]);
+    }
+
+    #[test]
+    fn items_are_counted_per_layer() {
+        let zone = |alias, layer: &str| Zone {
+            alias,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: layer.into(),
+            area: None,
+        };
+        let mut level = Level {
+            zones: vec![
+                zone(49, "MainLayer"),
+                zone(50, "MainLayer"),
+                zone(51, "ThirdLayer"),
+            ],
+            ..Default::default()
+        };
+        level.add_to_zone(49, GatherItem::Cell(0));
+        level.add_to_zone(50, GatherItem::Cell(1));
+        level.add_to_zone(51, GatherItem::HSU(3, 'B'));
+        level.toggle_collected(&GatherItem::Cell(1));
+
+        assert_eq!(level.layers(), vec!["MainLayer", "ThirdLayer"]);
+        assert_eq!(level.layer_progress("MainLayer"), (1, 2));
+        assert_eq!(level.layer_progress("ThirdLayer"), (0, 1));
+        assert_eq!(level.layer_progress("SecondaryLayer"), (0, 0));
+    }
+
+    #[test]
+    fn query_filters_by_text_category_and_layer() {
+        let zone = |alias, layer: &str| Zone {
+            alias,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: layer.into(),
+            area: None,
+        };
+        let mut level = Level {
+            zones: vec![zone(49, "MainLayer"), zone(51, "ThirdLayer")],
+            serials: vec![(49, "CELL_584".into())],
+            gatherables: vec![GatherItem::BulkheadKey("BULKHEAD_KEY_123".into())],
+            ..Default::default()
+        };
+        level.add_to_zone(49, GatherItem::Cell(0));
+        level.add_to_zone(51, GatherItem::HSU(3, 'B'));
+        let labels = |query: &ItemQuery| -> Vec<String> {
+            level.query(query).into_iter().map(|(_, _, l)| l).collect()
+        };
+
+        assert_eq!(labels(&ItemQuery::default()).len(), 3);
+        let mut query = ItemQuery {
+            text: "cell_58".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            labels(&query),
+            vec![level.item_label(Some(49), &GatherItem::Cell(0))]
+        );
+
+        query.text.clear();
+        query.toggle(ItemCategory::Cells);
+        query.hidden_layers.push("ThirdLayer".into());
+        assert_eq!(
+            labels(&query),
+            vec![GatherItem::BulkheadKey("BULKHEAD_KEY_123".into()).to_string()]
+        );
+    }
+
+    #[test]
+    fn serials_go_to_items_in_spawn_order() {
+        let mut level = Level {
+            serials: vec![
+                (50, "CELL_4".into()),
+                (50, "TERMINAL_123".into()),
+                (50, "CELL_9".into()),
+                (51, "CELL_2".into()),
+            ],
+            ..Default::default()
+        };
+        level.add_to_zone(50, GatherItem::Cell(3));
+        level.add_to_zone(50, GatherItem::Cell(1));
+        level.add_to_area(50, 'B', GatherItem::HSU(3, 'B'));
+
+        assert_eq!(level.serial(50, &GatherItem::Cell(1)), Some("CELL_4"));
+        assert_eq!(level.serial(50, &GatherItem::Cell(3)), Some("CELL_9"));
+        assert_eq!(level.serial(50, &GatherItem::HSU(3, 'B')), None);
+        assert_eq!(
+            level.zone_serials(50, "TERMINAL").collect::<Vec<_>>(),
+            vec!["TERMINAL_123"]
+        );
+    }
+
+    #[test]
+    fn items_are_filed_under_their_zone() {
+        let zone = Zone {
+            alias: 49,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let mut level = Level {
+            zones: vec![zone.clone()],
+            ..Default::default()
+        };
+
+        level.add_to_zone(49, GatherItem::ID("Box".into(), 3));
+        level.add_to_zone(49, GatherItem::PD("Locker".into(), 4));
+        level.add_to_zone(51, GatherItem::HSU(3, 'B'));
+
+        assert_eq!(
+            level.gathatable_items[&zone],
+            vec![
+                GatherItem::ID("Box".into(), 3),
+                GatherItem::PD("Locker".into(), 4)
+            ]
+        );
+        assert_eq!(level.zones, vec![zone, Zone::placeholder(51)]);
+        assert_eq!(
+            level.gathatable_items[&Zone::placeholder(51)],
+            vec![GatherItem::HSU(3, 'B')]
+        );
+    }
+
+    #[test]
+    fn items_keep_the_dimension_of_their_zone() {
+        let reality = Zone {
+            alias: 50,
+            local: 1,
+            dimension: Dimension::Reality,
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let portal = Zone {
+            local: 0,
+            dimension: Dimension::Numbered(1),
+            ..reality.clone()
+        };
+        let mut level = Level {
+            zones: vec![reality.clone(), portal.clone()],
+            ..Default::default()
+        };
+
+        level.add_to_zone(portal.key(), GatherItem::Cell(0));
+        level.add_to_zone(50, GatherItem::Cell(1));
+
+        assert_eq!(level.gathatable_items[&portal], vec![GatherItem::Cell(0)]);
+        assert_eq!(level.gathatable_items[&reality], vec![GatherItem::Cell(1)]);
+        assert_eq!(level.zone(&portal.key()), Some(&portal));
+        assert_eq!(level.zones.len(), 2);
+    }
+
+    #[test]
+    fn zone_doors_split() {
+        let zone = |alias| Zone {
+            alias,
+            local: alias - 49,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let mut level = Level {
+            timer_zones: vec![
+                TimerEntry::Start,
+                TimerEntry::Zone(zone(49)),
+                TimerEntry::Zone(zone(50)),
+                TimerEntry::End,
+            ],
+            ..Default::default()
+        };
+        let t = |s: &str| s.parse::<Time>().unwrap();
+
+        level.splits.start(t("20:00:00"));
+        assert!(level.split_zone(50, t("20:02:00")));
+        assert!(!level.split_zone(51, t("20:03:00")));
+
+        assert_eq!(
+            level.splits.splits,
+            vec![(
+                TimerEntry::Zone(zone(50)),
+                std::time::Duration::from_secs(120)
+            )]
+        );
+    }
+
+    #[test]
+    fn dimensions_in_generation_order() {
+        let zone = |alias, dimension: &str| Zone {
+            alias,
+            local: 0,
+            dimension: dimension.into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            zones: vec![
+                zone(49, "Reality"),
+                zone(50, "Dimension_1"),
+                zone(51, "Reality"),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            level.dimensions(),
+            vec![&Dimension::Reality, &Dimension::Numbered(1)]
+        );
+        assert_eq!(
+            level
+                .zones_in(&Dimension::Reality)
+                .iter()
+                .map(|zone| zone.alias)
+                .collect::<Vec<_>>(),
+            vec![49, 51]
+        );
+    }
+
+    #[test]
+    fn tutorial_display() {
+        let level = |rundown| Level {
+            rundown,
+            exp_name: "A1".into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            level(Rundown::from_repr(39).unwrap()).to_string(),
+            "Tutorial"
+        );
+        assert_eq!(level(Rundown::R1).to_string(), "R1A1");
+    }
+
+    #[test]
+    fn modded_rundown_uses_its_profile() {
+        let mut level = Level {
+            exp_name: "A1".into(),
+            ..Default::default()
+        };
+        assert_eq!(level.to_string(), "ModdedA1");
+        assert_eq!(level.tier_name().as_deref(), Some("A"));
+
+        level.modded = Some(RundownProfile {
+            id: 70,
+            name: "MOD1".into(),
+            tiers: [("A".into(), "Alpha".into())].into(),
+        });
+        assert_eq!(level.to_string(), "MOD1A1");
+        assert_eq!(level.tier_name().as_deref(), Some("Alpha"));
+    }
+
+    #[test]
+    fn areas_get_their_own_heading() {
+        let zone = Zone {
+            alias: 49,
+            local: 0,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let mut level = Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            zones: vec![zone.clone()],
+            ..Default::default()
+        };
+
+        level.add_to_area(49, 'C', GatherItem::ID("Box".into(), 3));
+        level.add_to_zone(49, GatherItem::Cell(2));
+        level.add_to_area(49, 'B', GatherItem::HSU(3, 'B'));
+
+        assert_eq!(level.areas(&zone), vec!['B', 'C']);
+        assert!(level.to_markdown().ends_with(concat!(
+            "\n### ZONE_49 MainLayer Reality\n\n",
+            "- CELL_2\n",
+            "\n### ZONE_49 Area B MainLayer Reality\n\n",
+            "- HSU area B (3)\n",
+            "\n### ZONE_49 Area C MainLayer Reality\n\n",
+            "- ID Box 3\n",
+        )));
+    }
+
+    #[test]
+    fn checklist_ticks_off_cells_in_order() {
+        let mut level = Level {
+            gatherables: vec![
+                GatherItem::Cell(0),
+                GatherItem::Cell(1),
+                GatherItem::HSU(3, 'B'),
+            ],
+            ..Default::default()
+        };
+        let cell = Pickup {
+            item: "CELL".into(),
+            zone: 50,
+            seed: 0,
+        };
+
+        assert!(level.collect(cell.clone(), None));
+        assert_eq!(level.progress(), (1, 3));
+        assert!(level.is_collected(&GatherItem::Cell(0)));
+
+        level.toggle_collected(&GatherItem::HSU(3, 'B'));
+        assert!(level.collect(cell.clone(), None));
+        assert!(!level.collect(cell, None));
+        assert_eq!(level.progress(), (3, 3));
+
+        level.toggle_collected(&GatherItem::Cell(0));
+        assert_eq!(level.progress(), (2, 3));
+    }
+
+    #[test]
+    fn pickups_match_mapped_items() {
+        let mut level = Level {
+            gatherables: vec![
+                GatherItem::Key("KEY_RED_1".into(), "Reality".into(), 50, 2),
+                GatherItem::ID("Box".into(), 3),
+            ],
+            ..Default::default()
+        };
+        let pickup = |item: &str, zone, seed| Pickup {
+            item: item.into(),
+            zone,
+            seed,
+        };
+
+        let at = Time::constant(20, 5, 0, 0);
+        level.splits.start(Time::constant(20, 3, 30, 0));
+        assert!(level.collect(pickup("KEY_RED_1", 50, 0), Some(at)));
+        assert!(level.collect(pickup("ID", 51, 3), None));
+        assert!(!level.collect(pickup("KEY_RED_1", 49, 0), None));
+
+        assert!(level
+            .gatherables
+            .iter()
+            .all(|item| level.is_collected(item)));
+        assert_eq!(level.unmapped_pickups, vec![pickup("KEY_RED_1", 49, 0)]);
+        assert_eq!(level.collected_at(&level.gatherables[0]), Some(at));
+        assert_eq!(level.collected_at(&level.gatherables[1]), None);
+        assert_eq!(level.since_drop(at), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn markdown_is_sorted() {
+        let zone = |alias| Zone {
+            alias,
+            local: alias - 49,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            seeds: Some(Seeds {
+                build: 12,
+                host: 34,
+                session: 56,
+            }),
+            zones: vec![zone(50), zone(49)],
+            gatherables: vec![
+                GatherItem::Seeded("Locker".into(), 7),
+                GatherItem::Key("KEY_RED_1".into(), "Reality".into(), 50, 2),
+                GatherItem::ID("Box".into(), 3),
+                GatherItem::Key("KEY_BLUE_2".into(), "Reality".into(), 49, 1),
+                GatherItem::ID("Box".into(), 1),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            level.to_markdown(),
+            concat!(
+                "## R1A1\n\n",
+                "- Rundown: R1\n",
+                "- Tier: A\n",
+                "- Expedition: A1\n",
+                "- Seeds: build 12, host 34, session 56\n",
+                "\n### ZONE_49 MainLayer Reality\n\n",
+                "- KEY_BLUE_2 ZONE_49 Reality ri: 1\n",
+                "\n### ZONE_50 MainLayer Reality\n\n",
+                "- KEY_RED_1 ZONE_50 Reality ri: 2\n",
+                "\n### Unknown zone\n\n",
+                "- ID Box 1\n",
+                "- ID Box 3\n",
+                "- Locker 7\n",
+            )
+        );
+    }
+
+    #[test]
+    fn csv_lists_zone_type_and_seed() {
+        let mut level = Level {
+            zones: vec![Zone {
+                alias: 49,
+                local: 0,
+                dimension: "Reality".into(),
+                layer: "MainLayer".into(),
+                area: None,
+            }],
+            gatherables: vec![
+                GatherItem::Seeded("Locker, 2".into(), 7),
+                GatherItem::Key("KEY_RED_1".into(), "Reality".into(), 49, 2),
+            ],
+            ..Default::default()
+        };
+        level.add_to_area(50, 'B', GatherItem::GLP1("Box3".into(), 99));
+
+        let tmp = TempDir::new("items");
+        let path = tmp.join("items.csv");
+        level.export_csv(&path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+
+        assert_eq!(
+            csv,
+            concat!(
+                "zone,type,name,container,seed\n",
+                "ZONE_49 MainLayer Reality,Key,KEY_RED_1 ZONE_49 Reality ri: 2,,\n",
+                "ZONE_50 Area B Unknown Reality,GLP1,GLP-1 Box3 99,Box3,99\n",
+                ",Seeded,\"Locker, 2 7\",\"Locker, 2\",7\n",
+            )
+        );
+    }
 }
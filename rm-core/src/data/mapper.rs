@@ -1,8 +1,129 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
 use glam::Vec2;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::Level;
+
+/// Floor plan of a zone, in map units.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct GatherableMap {
+    /// Alias of the zone the outline belongs to, if known
+    #[serde(default)]
+    pub zone: Option<u32>,
     pub outline_poly: Vec<Vec2>,
     pub blockouts: Vec<[Vec2; 4]>,
+    /// Item locations, labelled like the item in the item list
+    #[serde(default)]
+    pub markers: Vec<(Vec2, String)>,
+}
+
+/// Map geometry of the expeditions that have some, read from `<level>.json` files holding a
+/// list of [`GatherableMap`]s, i.e. `R1A1.json`.
+#[derive(Debug, Default, Clone)]
+pub struct MapPack {
+    levels: HashMap<String, Vec<GatherableMap>>,
+}
+
+impl MapPack {
+    /// Folder of the maps shipped next to the executable.
+    pub fn bundled_dir() -> Option<PathBuf> {
+        env::current_exe()
+            .ok()?
+            .parent()
+            .map(|dir| dir.join("maps"))
+    }
+
+    /// Folder of user maps, these replace bundled maps of the same level.
+    pub fn user_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("maps"))
+    }
+
+    /// Bundled maps with the user's on top. Missing folders are skipped.
+    pub fn load() -> Self {
+        let mut pack = MapPack::default();
+        for dir in [MapPack::bundled_dir(), MapPack::user_dir()]
+            .into_iter()
+            .flatten()
+            .filter(|dir| dir.is_dir())
+        {
+            if let Err(e) = pack.load_dir(&dir) {
+                warn!("Failed to read maps from {}: {e}", dir.display());
+            }
+        }
+
+        pack
+    }
+
+    /// Reads every map file in `dir`, replacing maps of levels that were already loaded.
+    /// Broken files are logged and skipped.
+    pub fn load_dir(&mut self, dir: &Path) -> anyhow::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(level) = path
+                .file_stem()
+                .filter(|_| path.extension().is_some_and(|ext| ext == "json"))
+                .map(|stem| stem.to_string_lossy().into_owned())
+            else {
+                continue;
+            };
+
+            let maps = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str::<Vec<GatherableMap>>(&json)?));
+            match maps {
+                Ok(maps) => {
+                    info!("Loaded {} maps for {level}", maps.len());
+                    self.levels.insert(level, maps);
+                }
+                Err(e) => warn!("Skipping maps in {}: {e}", path.display()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Maps of `level`'s expedition, if the pack has any.
+    pub fn get(&self, level: &Level) -> Option<&[GatherableMap]> {
+        self.levels.get(&level.to_string()).map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{data::Rundown, testing::TempDir};
+
+    use super::*;
+
+    #[test]
+    fn maps_are_keyed_by_expedition() {
+        let dir = TempDir::new("maps");
+        fs::write(
+            dir.join("R1A1.json"),
+            r#"[{"zone": 49, "outline_poly": [[0, 0], [10, 0], [10, 5]], "blockouts": []}]"#,
+        )
+        .unwrap();
+        fs::write(dir.join("R1B1.json"), "not json").unwrap();
+        fs::write(dir.join("notes.txt"), "[]").unwrap();
+
+        let mut pack = MapPack::default();
+        pack.load_dir(dir.path()).unwrap();
+
+        let level = |exp: &str| Level {
+            rundown: Rundown::R1,
+            exp_name: exp.into(),
+            ..Default::default()
+        };
+        let maps = pack.get(&level("A1")).unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].zone, Some(49));
+        assert_eq!(maps[0].outline_poly[1], Vec2::new(10.0, 0.0));
+        assert!(pack.get(&level("B1")).is_none());
+        assert_eq!(pack.levels.len(), 1);
+    }
 }
@@ -1,13 +1,39 @@
+// Everything is re-exported by glob, so a second `Level` (or any other duplicate name) in a
+// submodule has to fail the build instead of one silently shadowing the other.
+#![deny(ambiguous_glob_reexports)]
+
+mod bests;
+mod correlate;
+mod expedition;
+mod graph;
+mod history;
 mod items;
 mod level;
 mod mapper;
+mod objective;
+mod query;
 mod rundown;
+mod seed_db;
+mod seeds;
+mod session;
 mod splitter;
+mod stats;
 mod zone;
 
+pub use bests::*;
+pub use correlate::*;
+pub use expedition::*;
+pub use graph::*;
+pub use history::*;
 pub use items::*;
 pub use level::*;
 pub use mapper::*;
+pub use objective::*;
+pub use query::*;
 pub use rundown::*;
+pub use seed_db::*;
+pub use seeds::*;
+pub use session::*;
 pub use splitter::*;
+pub use stats::*;
 pub use zone::*;
@@ -0,0 +1,18 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::ItemIdentifier;
+
+/// Warden objective asking for `count` of an item, like `Gather 6 GLP_1`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Objective {
+    pub item: ItemIdentifier,
+    pub count: u32,
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Gather {} {}", self.count, self.item.label())
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::GatherItem;
+
+/// Groups of items the list can be narrowed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ItemCategory {
+    /// Keys and bulkhead keys
+    Keys,
+    Cells,
+    /// Items found in lockers and boxes by their seed, like IDs and PDs
+    Containers,
+    /// Objective items that aren't cells or in containers, and unknown items
+    Objective,
+}
+
+impl ItemCategory {
+    pub const ALL: [ItemCategory; 4] = [
+        ItemCategory::Keys,
+        ItemCategory::Cells,
+        ItemCategory::Containers,
+        ItemCategory::Objective,
+    ];
+
+    pub fn of(item: &GatherItem) -> Self {
+        match item {
+            GatherItem::Key(..) | GatherItem::BulkheadKey(_) => ItemCategory::Keys,
+            GatherItem::Cell(_) => ItemCategory::Cells,
+            GatherItem::ID(..)
+            | GatherItem::PD(..)
+            | GatherItem::GLP1(..)
+            | GatherItem::OSIP(..)
+            | GatherItem::PlantSample(..)
+            | GatherItem::DataCube(..)
+            | GatherItem::GLP2(..)
+            | GatherItem::Seeded(..) => ItemCategory::Containers,
+            GatherItem::HSU(..)
+            | GatherItem::Generator(..)
+            | GatherItem::FogTurbine(_)
+            | GatherItem::Neonate(_)
+            | GatherItem::Cryo(_)
+            | GatherItem::Datasphere(_)
+            | GatherItem::HiSec(_)
+            | GatherItem::Cargo(_)
+            | GatherItem::MWP(_)
+            | GatherItem::Uplink(_)
+            | GatherItem::Unknown(..) => ItemCategory::Objective,
+        }
+    }
+}
+
+impl Display for ItemCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemCategory::Keys => write!(f, "Keys"),
+            ItemCategory::Cells => write!(f, "Cells"),
+            ItemCategory::Containers => write!(f, "IDs/PDs"),
+            ItemCategory::Objective => write!(f, "Objective"),
+        }
+    }
+}
+
+impl FromStr for ItemCategory {
+    type Err = anyhow::Error;
+
+    /// Case-insensitive name, `keys`, `cells`, `containers` or `objective`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "keys" => Ok(ItemCategory::Keys),
+            "cells" => Ok(ItemCategory::Cells),
+            "containers" => Ok(ItemCategory::Containers),
+            "objective" => Ok(ItemCategory::Objective),
+            _ => Err(anyhow!(
+                "unknown item category `{s}`, expected keys, cells, containers or objective"
+            )),
+        }
+    }
+}
+
+/// Which items of a level to list, see [`super::Level::query`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemQuery {
+    /// Text the item's label has to contain, ignoring case, like `green` or a serial
+    pub text: String,
+    /// Categories that are listed
+    pub categories: BTreeSet<ItemCategory>,
+    /// Layers left out, by the name the game logs. Items without a zone are always listed.
+    pub hidden_layers: Vec<String>,
+}
+
+impl Default for ItemQuery {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            categories: ItemCategory::ALL.into(),
+            hidden_layers: vec![],
+        }
+    }
+}
+
+impl ItemQuery {
+    pub fn shows(&self, category: ItemCategory) -> bool {
+        self.categories.contains(&category)
+    }
+
+    /// Lists `category` if it wasn't, or stops listing it.
+    pub fn toggle(&mut self, category: ItemCategory) {
+        if !self.categories.remove(&category) {
+            self.categories.insert(category);
+        }
+    }
+
+    pub fn shows_layer(&self, layer: &str) -> bool {
+        !self.hidden_layers.iter().any(|hidden| hidden == layer)
+    }
+
+    /// Whether `item` with the rendered `label` passes, layers aside.
+    pub fn matches(&self, item: &GatherItem, label: &str) -> bool {
+        let text = self.text.trim().to_lowercase();
+        self.shows(ItemCategory::of(item))
+            && (text.is_empty() || label.to_lowercase().contains(&text))
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use strum::FromRepr;
 
 /// Values are corelated to the R8 live build
-#[derive(FromRepr, Debug, Default, Serialize, Deserialize, Clone)]
+#[derive(FromRepr, Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
 #[repr(u16)]
 pub enum Rundown {
     #[default]
@@ -14,5 +14,6 @@ pub enum Rundown {
     R8 = 35,
     R4 = 37,
     R5 = 38,
+    Tutorial = 39,
     R6 = 41,
 }
@@ -0,0 +1,167 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use jiff::civil::DateTime;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::{Level, Seeds};
+
+/// Layout a level had the last time its build seed was played.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeenSeed {
+    /// Expedition, i.e. `R1A1`
+    pub level: String,
+    pub seeds: Seeds,
+    /// How often the build seed came up on the expedition
+    pub times: u32,
+    pub last_seen: Option<DateTime>,
+    /// See [`Level::layout`]
+    pub layout: Level,
+}
+
+/// Levels by expedition and build seed, stored as JSON in the config dir. Generation only
+/// depends on the build seed, so a seed that comes up again has the layout stored here
+/// before the log gets to distributing items.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeedDb {
+    pub seeds: Vec<SeenSeed>,
+    /// File the seeds were loaded from and are saved to, nothing is saved without one
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl SeedDb {
+    /// Default location of the seed database.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("seeds.json"))
+    }
+
+    /// Loads the seed database, empty if there's no file yet. A broken file is logged and
+    /// ignored.
+    pub fn load() -> Self {
+        let Some(path) = SeedDb::path() else {
+            return SeedDb::default();
+        };
+        if !path.is_file() {
+            return SeedDb {
+                path: Some(path),
+                ..Default::default()
+            };
+        }
+
+        SeedDb::load_from(&path).unwrap_or_else(|e| {
+            warn!("Ignoring seeds {}: {e}", path.display());
+            SeedDb::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let mut db: SeedDb = serde_json::from_str(&fs::read_to_string(path)?)?;
+        db.path = Some(path.into());
+        Ok(db)
+    }
+
+    /// Writes the seeds to the file they were loaded from, creating its folder if it's
+    /// missing.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// What's stored for `build` on `level`, i.e. `R1A1`.
+    pub fn get(&self, level: &str, build: u32) -> Option<&SeenSeed> {
+        self.seeds
+            .iter()
+            .find(|seen| seen.level == level && seen.seeds.build == build)
+    }
+
+    /// Stores the layout of `level` under its build seed, replacing the one stored before.
+    /// Levels without seeds or zones are skipped. Returns whether the seed was seen before.
+    pub fn record(&mut self, level: &Level, at: DateTime) -> bool {
+        let Some(seeds) = level.seeds.filter(|_| !level.zones.is_empty()) else {
+            return false;
+        };
+
+        let name = level.to_string();
+        let seen = SeenSeed {
+            level: name.clone(),
+            seeds,
+            times: 1,
+            last_seen: Some(at),
+            layout: level.layout(),
+        };
+        match self
+            .seeds
+            .iter_mut()
+            .find(|seen| seen.level == name && seen.seeds.build == seeds.build)
+        {
+            Some(stored) => {
+                *stored = SeenSeed {
+                    times: stored.times + 1,
+                    ..seen
+                };
+                true
+            }
+            None => {
+                self.seeds.push(seen);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data::{GatherItem, Rundown, Zone},
+        testing::TempDir,
+    };
+
+    use super::*;
+
+    #[test]
+    fn seeds_are_stored_per_expedition() {
+        let at: DateTime = "2024-07-01T20:03:18".parse().unwrap();
+        let level = |exp: &str, build| Level {
+            rundown: Rundown::R1,
+            exp_name: exp.into(),
+            seeds: Some(Seeds {
+                build,
+                host: 2,
+                session: 3,
+            }),
+            zones: vec![Zone::placeholder(49)],
+            gatherables: vec![GatherItem::Cell(1)],
+            collected: vec![GatherItem::Cell(1)],
+            ..Default::default()
+        };
+
+        let mut db = SeedDb::default();
+        assert!(!db.record(&level("A1", 12), at));
+        assert!(!db.record(&level("B1", 12), at));
+        assert!(db.record(&level("A1", 12), at));
+        assert!(!db.record(&Level::default(), at));
+
+        let seen = db.get("R1A1", 12).unwrap();
+        assert_eq!(seen.times, 2);
+        assert_eq!(seen.layout.gatherables, vec![GatherItem::Cell(1)]);
+        assert!(seen.layout.collected.is_empty());
+        assert!(db.get("R1A1", 13).is_none());
+
+        let tmp = TempDir::new("seeds");
+        let path = tmp.join("seeds.json");
+        db.path = Some(path.clone());
+        db.save().unwrap();
+        assert_eq!(SeedDb::load_from(&path).unwrap(), db);
+    }
+}
@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Dimension, GatherItem, GatherKind, Level};
+
+/// Seeds the level was generated with, logged by `Builder.Build`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Seeds {
+    pub build: u32,
+    pub host: u32,
+    pub session: u32,
+}
+
+impl Seeds {
+    /// All three seeds in one word, i.e. `12-34-56`, like saves are named.
+    pub fn tuple(&self) -> String {
+        format!("{}-{}-{}", self.build, self.host, self.session)
+    }
+}
+
+impl Display for Seeds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "build {}, host {}, session {}",
+            self.build, self.host, self.session
+        )
+    }
+}
+
+/// Roll below the level seeds that the log shows, i.e. the seed of an ID in its container or
+/// the random index a key was placed with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubSeed {
+    /// What was rolled, i.e. `ID Locker12` or `KEY_RED_1 ri`
+    pub label: String,
+    pub seed: u32,
+}
+
+impl Display for SubSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.label, self.seed)
+    }
+}
+
+/// Rolls of the items in one zone, in the order the items were mapped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneSeeds {
+    pub alias: u32,
+    pub dimension: Dimension,
+    pub seeds: Vec<SubSeed>,
+}
+
+/// Everything about a level's randomness that can be read from the log: its seeds and the
+/// rolls of the items placed with them, by zone. The game doesn't log how zones derive their
+/// own seeds, so item seeds and key indices are as far down as it goes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedBreakdown {
+    pub level: Option<Seeds>,
+    /// Zones with rolls, sorted by dimension then alias
+    pub zones: Vec<ZoneSeeds>,
+    /// Rolls of items whose zone isn't known
+    pub unzoned: Vec<SubSeed>,
+}
+
+impl SeedBreakdown {
+    pub fn new(level: &Level) -> Self {
+        let mut zones: BTreeMap<(&Dimension, u32), Vec<SubSeed>> = BTreeMap::new();
+        let mut unzoned = vec![];
+
+        for (zone, item) in level.all_items() {
+            let (seed, zone) = match item {
+                GatherItem::Key(name, dim, alias, ri) => (
+                    SubSeed {
+                        label: format!("{name} ri"),
+                        seed: *ri,
+                    },
+                    Some((zone.map_or(dim, |zone| &zone.dimension), *alias)),
+                ),
+                item => {
+                    let Some((container, seed)) = item.container_seed() else {
+                        continue;
+                    };
+                    let label = match item {
+                        GatherItem::Seeded(..) => container.to_string(),
+                        item => format!("{:?} {container}", GatherKind::from(item)),
+                    };
+                    (
+                        SubSeed { label, seed },
+                        zone.map(|zone| (&zone.dimension, zone.alias)),
+                    )
+                }
+            };
+
+            match zone {
+                Some(zone) => zones.entry(zone).or_default().push(seed),
+                None => unzoned.push(seed),
+            }
+        }
+
+        Self {
+            level: level.seeds,
+            zones: zones
+                .into_iter()
+                .map(|((dimension, alias), seeds)| ZoneSeeds {
+                    alias,
+                    dimension: dimension.clone(),
+                    seeds,
+                })
+                .collect(),
+            unzoned,
+        }
+    }
+
+    /// The whole breakdown as text for comparing runs, one line per zone, i.e.
+    ///
+    /// ```text
+    /// Seeds 12-34-56
+    /// ZONE_50 Reality: ID Locker12 1234, KEY_RED_1 ri 3
+    /// Unknown zone: Box3 99
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        if let Some(seeds) = self.level {
+            let _ = writeln!(out, "Seeds {}", seeds.tuple());
+        }
+
+        let join = |seeds: &[SubSeed]| {
+            seeds
+                .iter()
+                .map(SubSeed::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        for zone in &self.zones {
+            let _ = writeln!(
+                out,
+                "ZONE_{} {}: {}",
+                zone.alias,
+                zone.dimension,
+                join(&zone.seeds)
+            );
+        }
+        if !self.unzoned.is_empty() {
+            let _ = writeln!(out, "Unknown zone: {}", join(&self.unzoned));
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::data::Zone;
+
+    #[test]
+    fn rolls_are_grouped_by_zone() {
+        let zone = |alias, dimension: &str| Zone {
+            alias,
+            local: 0,
+            dimension: dimension.into(),
+            layer: "MainLayer".into(),
+            area: None,
+        };
+        let level = Level {
+            seeds: Some(Seeds {
+                build: 12,
+                host: 34,
+                session: 56,
+            }),
+            zones: vec![zone(50, "Reality"), zone(50, "Dimension_1")],
+            gathatable_items: HashMap::from([
+                (
+                    zone(50, "Reality"),
+                    vec![GatherItem::ID("Locker12".into(), 1234), GatherItem::Cell(2)],
+                ),
+                (
+                    zone(50, "Dimension_1"),
+                    vec![GatherItem::GLP1("Box4".into(), 777)],
+                ),
+            ]),
+            gatherables: vec![
+                GatherItem::Key("KEY_RED_1".into(), "Reality".into(), 50, 3),
+                GatherItem::Seeded("Box3".into(), 99),
+            ],
+            ..Default::default()
+        };
+
+        let breakdown = SeedBreakdown::new(&level);
+        assert_eq!(breakdown.zones.len(), 2);
+        assert_eq!(
+            breakdown.to_text(),
+            concat!(
+                "Seeds 12-34-56\n",
+                "ZONE_50 Reality: ID Locker12 1234, KEY_RED_1 ri 3\n",
+                "ZONE_50 Dimension_1: GLP1 Box4 777\n",
+                "Unknown zone: Box3 99\n",
+            )
+        );
+    }
+}
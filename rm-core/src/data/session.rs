@@ -0,0 +1,125 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::Level;
+
+/// What the log header says about the game that wrote the log.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    /// Build number of the game
+    pub build: Option<u32>,
+    pub revision: Option<u32>,
+    /// Nickname of the local player
+    pub player: Option<String>,
+    /// Store the game runs from, i.e. `STEAM`
+    pub platform: Option<String>,
+}
+
+impl SessionInfo {
+    /// Whether the header had nothing the mapper knows.
+    pub fn is_empty(&self) -> bool {
+        *self == SessionInfo::default()
+    }
+}
+
+impl Display for SessionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        match (self.build, self.revision) {
+            (Some(build), Some(revision)) => parts.push(format!("build {build} rev {revision}")),
+            (Some(build), None) => parts.push(format!("build {build}")),
+            (None, _) => {}
+        }
+        parts.extend(self.platform.clone());
+        parts.extend(self.player.clone());
+
+        write!(f, "{}", parts.join(" | "))
+    }
+}
+
+/// Levels dropped into since the mapper started. Reset levels are kept, so earlier runs of
+/// the session can still be looked at.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Session {
+    /// Every level of the session, the active one last
+    pub levels: Vec<Level>,
+    /// Whether the last level is still being played
+    pub live: bool,
+}
+
+impl Session {
+    /// Level being played, `None` between levels.
+    pub fn active(&self) -> Option<&Level> {
+        self.levels.last().filter(|_| self.live)
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Level> {
+        self.levels.last_mut().filter(|_| self.live)
+    }
+
+    /// Makes `level` the active one. A level that was never reset is replaced, like the
+    /// parser starting it over.
+    pub fn start(&mut self, level: Level) {
+        if self.live {
+            self.levels.pop();
+        }
+        self.levels.push(level);
+        self.live = true;
+    }
+
+    /// Keeps the active level as done, i.e. once it's been reset.
+    pub fn finish(&mut self) {
+        self.live = false;
+    }
+
+    /// Levels that were reset, in the order they were played.
+    pub fn done(&self) -> &[Level] {
+        match self.live {
+            true => &self.levels[..self.levels.len() - 1],
+            false => &self.levels,
+        }
+    }
+
+    /// Done level with index `tab`, the active one for `None`.
+    pub fn shown(&self, tab: Option<usize>) -> Option<&Level> {
+        match tab {
+            Some(tab) => self.done().get(tab),
+            None => self.active(),
+        }
+    }
+
+    pub fn shown_mut(&mut self, tab: Option<usize>) -> Option<&mut Level> {
+        match tab {
+            Some(tab) if tab < self.done().len() => self.levels.get_mut(tab),
+            Some(_) => None,
+            None => self.active_mut(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Rundown;
+
+    #[test]
+    fn reset_levels_are_kept() {
+        let level = |exp_name: &str| Level {
+            rundown: Rundown::R1,
+            exp_name: exp_name.into(),
+            ..Default::default()
+        };
+        let mut session = Session::default();
+        session.start(level("A1"));
+        session.finish();
+        assert!(session.active().is_none());
+        session.start(level("B1"));
+
+        assert_eq!(session.active().unwrap().exp_name, "B1");
+        assert_eq!(session.done(), [level("A1")]);
+        assert_eq!(session.shown(Some(0)).unwrap().exp_name, "A1");
+        assert!(session.shown_mut(Some(1)).is_none());
+        assert_eq!(session.shown(None), session.active());
+    }
+}
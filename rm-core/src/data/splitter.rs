@@ -1,7 +1,10 @@
-use jiff::Timestamp;
+use std::{fmt::Display, time::Duration};
+
+use jiff::{civil::Time, Timestamp};
 use serde::{Deserialize, Serialize};
 
 use super::{GatherItem, ItemIdentifier, Zone};
+use crate::parser::ParserMsg;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Record {
@@ -10,7 +13,7 @@ pub struct Record {
     pub zone: Option<Zone>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum TimerEntry {
     Start,
     Zone(Zone),
@@ -19,8 +22,118 @@ pub enum TimerEntry {
     End,
 }
 
-#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+impl Display for TimerEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimerEntry::Start => write!(f, "Start"),
+            TimerEntry::Zone(zone) => write!(f, "{zone}"),
+            TimerEntry::Custom(name) => write!(f, "{name}"),
+            TimerEntry::Invariance(zones, method) => {
+                write!(f, "{method:?} of {} zones", zones.len())
+            }
+            TimerEntry::End => write!(f, "End"),
+        }
+    }
+}
+
+/// Splits a runner picked for a level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitPreset {
+    /// Level the preset is for, i.e. `R1A1`
+    pub level: String,
+    pub entries: Vec<TimerEntry>,
+}
+
+impl SplitPreset {
+    /// Preset that only times the whole run.
+    pub fn new(level: impl Into<String>) -> Self {
+        Self {
+            level: level.into(),
+            entries: vec![TimerEntry::Start, TimerEntry::End],
+        }
+    }
+}
+
+/// Goes through the entries of a [`SplitPreset`] in order as parser messages come in.
+/// Only the next entry can be reached, so a door opened out of order doesn't split.
+///
+/// - `Start` is reached by the elevator drop and `End` by extraction
+/// - `Zone` by its security door opening
+/// - `Custom` by a manual split, or by the bulkhead scan or checkpoint it's named after,
+///   like `Bulkhead ZONE_51` or `Checkpoint 1`
+/// - `Invariance` once its [`Invariance`] is satisfied, counting from when it became next
+#[derive(Debug, Clone, PartialEq)]
+pub struct Splitter {
+    entries: Vec<TimerEntry>,
+    next: usize,
+    invariance: Invariance,
+}
+
+impl Splitter {
+    pub fn new(preset: &SplitPreset) -> Self {
+        Self {
+            entries: preset.entries.clone(),
+            next: 0,
+            invariance: Invariance::default(),
+        }
+    }
+
+    /// Every entry of the preset, reached or not.
+    pub fn entries(&self) -> &[TimerEntry] {
+        &self.entries
+    }
+
+    /// Entry waiting to be reached, `None` once the preset is done.
+    pub fn next(&self) -> Option<&TimerEntry> {
+        self.entries.get(self.next)
+    }
+
+    /// Entries reached so far.
+    pub fn reached(&self) -> &[TimerEntry] {
+        &self.entries[..self.next]
+    }
+
+    /// Moves past the next entry if `msg` reaches it, returning the entry and the log time.
+    pub fn advance(&mut self, msg: &ParserMsg) -> Option<(TimerEntry, Time)> {
+        let entry = self.entries.get(self.next)?;
+        let invariance = &mut self.invariance;
+        let at = match (entry, msg) {
+            (TimerEntry::Start, ParserMsg::ElevatorDrop(at))
+            | (TimerEntry::End, ParserMsg::LevelFinish(at))
+            | (TimerEntry::Custom(_), ParserMsg::Split(at)) => *at,
+            (TimerEntry::Zone(zone), ParserMsg::ZoneDoorOpened(alias, at))
+                if zone.alias == *alias =>
+            {
+                *at
+            }
+            (TimerEntry::Custom(name), ParserMsg::BulkheadScanned(alias, at))
+                if *name == format!("Bulkhead ZONE_{alias}") =>
+            {
+                *at
+            }
+            (TimerEntry::Custom(name), ParserMsg::CheckpointActivated(at))
+                if name.starts_with("Checkpoint") =>
+            {
+                *at
+            }
+            (
+                TimerEntry::Invariance(zones, method),
+                ParserMsg::ZoneDoorOpened(_, at) | ParserMsg::Collected(_, at),
+            ) if invariance.update(zones, method, msg) => *at,
+            _ => None,
+        }?;
+
+        let entry = entry.clone();
+        self.next += 1;
+        self.invariance = Invariance::default();
+        Some((entry, at))
+    }
+}
+
+/// When a [`TimerEntry::Invariance`] splits, evaluated by [`Invariance`].
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum InvarianceMethod {
+    /// Once every zone's door opened
     #[default]
     All,
     /// Number of Zones, filter Item, max num of Item
@@ -28,5 +141,255 @@ pub enum InvarianceMethod {
     /// Filter by Item(if item was provided) N zones
     /// Max N of items only to have a treshold
     Any(u32, Option<ItemIdentifier>, Option<u32>),
+    /// Once the item is picked up in any of the zones
     ByGatherable(ItemIdentifier),
 }
+
+/// Progress towards a [`TimerEntry::Invariance`] entry.
+///
+/// Zones count as reached when their security door opens. With an item filter they only
+/// count once that item was picked up in them instead, for `Any` and `ByGatherable`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Invariance {
+    /// Aliases of the zones that count, without duplicates
+    reached: Vec<u32>,
+    /// Filtered items picked up in the zones
+    items: u32,
+}
+
+impl Invariance {
+    /// Feeds `msg` and returns whether `method` is satisfied for `zones` now.
+    pub fn update(&mut self, zones: &[Zone], method: &InvarianceMethod, msg: &ParserMsg) -> bool {
+        let filter = match method {
+            InvarianceMethod::All => None,
+            InvarianceMethod::Any(_, filter, _) => filter.as_ref(),
+            InvarianceMethod::ByGatherable(item) => Some(item),
+        };
+        let in_zones = |alias: u32| zones.iter().any(|zone| zone.alias == alias);
+
+        let reached = match (msg, filter) {
+            (ParserMsg::ZoneDoorOpened(alias, _), None) => Some(*alias),
+            (ParserMsg::Collected(pickup, _), Some(item))
+                if in_zones(pickup.zone) && item.matches_pickup(&pickup.item) =>
+            {
+                self.items += 1;
+                Some(pickup.zone)
+            }
+            _ => None,
+        };
+        if let Some(alias) = reached.filter(|&alias| in_zones(alias)) {
+            if !self.reached.contains(&alias) {
+                self.reached.push(alias);
+            }
+        }
+
+        match method {
+            InvarianceMethod::All => zones.iter().all(|zone| self.reached.contains(&zone.alias)),
+            InvarianceMethod::Any(count, _, max) => {
+                self.reached.len() >= *count as usize || max.is_some_and(|max| self.items >= max)
+            }
+            InvarianceMethod::ByGatherable(_) => self.items > 0,
+        }
+    }
+}
+
+/// Time from `from` to `to`. Log times have no date, so this wraps past midnight.
+pub fn elapsed(from: Time, to: Time) -> Duration {
+    const DAY: i64 = 24 * 3600 * 1_000_000_000;
+    let nanos = |t: Time| {
+        (i64::from(t.hour()) * 3600 + i64::from(t.minute()) * 60 + i64::from(t.second()))
+            * 1_000_000_000
+            + i64::from(t.subsec_nanosecond())
+    };
+
+    Duration::from_nanos((nanos(to) - nanos(from)).rem_euclid(DAY) as u64)
+}
+
+/// `HH:MM:SS`, fractions of a second are dropped.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Split times of a run, relative to the elevator drop.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Splits {
+    /// Log time the elevator drop finished
+    pub start: Option<Time>,
+    /// First time each entry was reached, in the order they were reached
+    pub splits: Vec<(TimerEntry, Duration)>,
+    /// Final time, set on extraction
+    pub end: Option<Duration>,
+}
+
+impl Splits {
+    /// Starts the timer, later starts are ignored.
+    pub fn start(&mut self, at: Time) {
+        self.start.get_or_insert(at);
+    }
+
+    /// Records when `entry` was first reached. Returns `false` if the timer isn't running
+    /// or the entry already has a split.
+    pub fn split(&mut self, entry: TimerEntry, at: Time) -> bool {
+        let Some(start) = self.start else {
+            return false;
+        };
+        if self.end.is_some() || self.splits.iter().any(|(e, _)| *e == entry) {
+            return false;
+        }
+
+        self.splits.push((entry, elapsed(start, at)));
+        true
+    }
+
+    /// Stops the timer, only the first end counts.
+    pub fn end(&mut self, at: Time) {
+        if let (Some(start), None) = (self.start, self.end) {
+            self.end = Some(elapsed(start, at));
+        }
+    }
+
+    /// Running time at `now`, or the final time once the run ended.
+    pub fn elapsed(&self, now: Time) -> Option<Duration> {
+        self.end.or_else(|| Some(elapsed(self.start?, now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Pickup;
+
+    fn t(s: &str) -> Time {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn elapsed_wraps_past_midnight() {
+        assert_eq!(
+            format_duration(elapsed(t("20:03:18"), t("21:05:20.9"))),
+            "01:02:02"
+        );
+        assert_eq!(
+            elapsed(t("23:59:50"), t("00:00:10.5")),
+            Duration::from_millis(20_500)
+        );
+    }
+
+    #[test]
+    fn splitter_follows_preset_order() {
+        let zone = |alias| TimerEntry::Zone(Zone::placeholder(alias));
+        let mut splitter = Splitter::new(&SplitPreset {
+            level: "R1A1".into(),
+            entries: vec![
+                TimerEntry::Start,
+                zone(50),
+                TimerEntry::Custom("Bulkhead ZONE_51".into()),
+                TimerEntry::End,
+            ],
+        });
+        let at = Some(t("20:05:00"));
+
+        assert_eq!(splitter.advance(&ParserMsg::ZoneDoorOpened(50, at)), None);
+        assert_eq!(
+            splitter.advance(&ParserMsg::ElevatorDrop(at)),
+            Some((TimerEntry::Start, t("20:05:00")))
+        );
+        assert_eq!(splitter.advance(&ParserMsg::ZoneDoorOpened(49, at)), None);
+        assert!(splitter
+            .advance(&ParserMsg::ZoneDoorOpened(50, at))
+            .is_some());
+        assert_eq!(splitter.advance(&ParserMsg::BulkheadScanned(52, at)), None);
+        assert!(splitter
+            .advance(&ParserMsg::BulkheadScanned(51, at))
+            .is_some());
+        assert_eq!(splitter.next(), Some(&TimerEntry::End));
+        assert!(splitter.advance(&ParserMsg::LevelFinish(at)).is_some());
+        assert_eq!(splitter.next(), None);
+        assert_eq!(splitter.reached().len(), 4);
+    }
+
+    #[test]
+    fn invariance_methods() {
+        let zones: Vec<Zone> = [49, 50, 51].map(Zone::placeholder).into();
+        let door = |alias| ParserMsg::ZoneDoorOpened(alias, None);
+        let pickup = |item: &str, zone| {
+            ParserMsg::Collected(
+                Pickup {
+                    item: item.into(),
+                    zone,
+                    seed: 0,
+                },
+                None,
+            )
+        };
+        let run = |method: InvarianceMethod, msgs: &[ParserMsg]| {
+            let mut invariance = Invariance::default();
+            msgs.iter()
+                .map(|msg| invariance.update(&zones, &method, msg))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run(
+                InvarianceMethod::All,
+                &[door(49), door(52), door(49), door(50), door(51)]
+            ),
+            [false, false, false, false, true]
+        );
+        assert_eq!(
+            run(
+                InvarianceMethod::Any(2, None, None),
+                &[door(49), door(49), door(51)]
+            ),
+            [false, false, true]
+        );
+        assert_eq!(
+            run(
+                InvarianceMethod::Any(3, Some(ItemIdentifier::Cell), Some(2)),
+                &[
+                    door(49),
+                    pickup("CELL", 49),
+                    pickup("ID", 50),
+                    pickup("Cell", 49)
+                ]
+            ),
+            [false, false, false, true]
+        );
+        assert_eq!(
+            run(
+                InvarianceMethod::ByGatherable(ItemIdentifier::ID),
+                &[pickup("ID", 52), door(50), pickup("ID", 50)]
+            ),
+            [false, false, true]
+        );
+    }
+
+    #[test]
+    fn splits_are_relative_to_start() {
+        let mut splits = Splits::default();
+
+        assert!(!splits.split(TimerEntry::Custom("early".into()), t("20:00:00")));
+
+        splits.start(t("20:00:00"));
+        splits.start(t("20:05:00"));
+        assert!(splits.split(TimerEntry::Custom("door".into()), t("20:01:30")));
+        assert!(!splits.split(TimerEntry::Custom("door".into()), t("20:02:00")));
+        assert_eq!(
+            splits.elapsed(t("20:02:00")),
+            Some(Duration::from_secs(120))
+        );
+
+        splits.end(t("20:10:00"));
+        splits.end(t("20:11:00"));
+        assert!(!splits.split(TimerEntry::Custom("late".into()), t("20:10:30")));
+        assert_eq!(
+            splits.splits,
+            vec![(TimerEntry::Custom("door".into()), Duration::from_secs(90))]
+        );
+        assert_eq!(
+            splits.elapsed(t("20:30:00")),
+            Some(Duration::from_secs(600))
+        );
+    }
+}
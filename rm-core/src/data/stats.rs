@@ -0,0 +1,209 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use super::{elapsed, GatherKind, Level, Outcome};
+
+/// Numbers about a saved run, written next to its level by [`Level::export`] as
+/// `<stem>.stats.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunStats {
+    /// Expedition, i.e. `R1A1`
+    pub level: String,
+    pub outcome: Option<Outcome>,
+    /// Final time, `None` for runs that never extracted
+    pub duration: Option<Duration>,
+    /// From generation finishing to the elevator drop
+    pub build_to_drop: Option<Duration>,
+    /// Splits by their entry's name, each relative to the drop
+    pub splits: Vec<(String, Duration)>,
+    /// Mapped items in each zone, by alias
+    pub items_per_zone: BTreeMap<u32, usize>,
+    /// Mapped items of each kind, by kind name like `Cell`
+    pub items_per_kind: BTreeMap<String, usize>,
+    /// Mapped items picked up
+    pub collected: usize,
+}
+
+impl RunStats {
+    pub fn new(level: &Level) -> Self {
+        let mut items_per_zone = BTreeMap::new();
+        let mut items_per_kind = BTreeMap::new();
+        for (zone, item) in level.all_items() {
+            if let Some(zone) = zone {
+                *items_per_zone.entry(zone.alias).or_default() += 1;
+            }
+            let kind = GatherKind::from(item);
+            *items_per_kind.entry(format!("{kind:?}")).or_default() += 1;
+        }
+
+        Self {
+            level: level.to_string(),
+            outcome: level.outcome,
+            duration: level.splits.end,
+            build_to_drop: level
+                .started
+                .zip(level.splits.start)
+                .map(|(built, drop)| elapsed(built, drop)),
+            splits: level
+                .splits
+                .splits
+                .iter()
+                .map(|(entry, at)| (entry.to_string(), *at))
+                .collect(),
+            items_per_zone,
+            items_per_kind,
+            collected: level.collected.len(),
+        }
+    }
+
+    /// Stats file of the level saved at `level_path`.
+    pub fn path(level_path: &Path) -> PathBuf {
+        level_path.with_extension("stats.json")
+    }
+
+    pub fn is_stats_file(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".stats.json"))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Time of each split since the one before it, the first since the drop.
+    pub fn segments(&self) -> Vec<(&str, Duration)> {
+        let mut last = Duration::ZERO;
+        self.splits
+            .iter()
+            .map(|(name, at)| {
+                let segment = at.saturating_sub(last);
+                last = *at;
+                (name.as_str(), segment)
+            })
+            .collect()
+    }
+
+    /// When the split named `name` was reached.
+    pub fn split(&self, name: &str) -> Option<Duration> {
+        self.splits
+            .iter()
+            .find_map(|(split, at)| (split == name).then_some(*at))
+    }
+
+    /// Fastest successful run of `level` with stats in `dir`, its expedition folder. Files
+    /// that fail to load are logged and skipped.
+    pub fn personal_best(dir: &Path, level: &str) -> anyhow::Result<Option<Self>> {
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut best: Option<Self> = None;
+        for file in fs::read_dir(dir)? {
+            let path = file?.path();
+            if !Self::is_stats_file(&path) {
+                continue;
+            }
+
+            let stats = match Self::load(&path) {
+                Ok(stats) => stats,
+                Err(e) => {
+                    warn!("Skipping stats {}: {e}", path.display());
+                    continue;
+                }
+            };
+            if stats.level != level || stats.outcome != Some(Outcome::Success) {
+                continue;
+            }
+            let Some(duration) = stats.duration else {
+                continue;
+            };
+            if best
+                .as_ref()
+                .and_then(|best| best.duration)
+                .is_none_or(|best| duration < best)
+            {
+                best = Some(stats);
+            }
+        }
+
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::time;
+
+    use crate::{
+        data::{GatherItem, Rundown, SaveFormat, TimerEntry},
+        testing::TempDir,
+    };
+
+    use super::*;
+
+    #[test]
+    fn fastest_success_is_the_personal_best() {
+        let dir = TempDir::new("stats");
+        let run = |secs: u64, outcome: Outcome| {
+            let mut level = Level {
+                rundown: Rundown::R1,
+                exp_name: "A1".into(),
+                outcome: Some(outcome),
+                started: Some(time(20, 0, 0, 0)),
+                ..Default::default()
+            };
+            level.gatherables = vec![GatherItem::Cell(0), GatherItem::Cell(1)];
+            level.splits.start = Some(time(20, 0, 45, 0));
+            level.splits.splits = vec![
+                (TimerEntry::Start, Duration::ZERO),
+                (
+                    TimerEntry::Custom("Reactor".into()),
+                    Duration::from_secs(300),
+                ),
+            ];
+            level.splits.end = Some(Duration::from_secs(secs));
+            level
+        };
+
+        let slow = run(900, Outcome::Success);
+        let paths = slow.export(dir.path(), SaveFormat::Json).unwrap();
+        assert_eq!(paths[1], RunStats::path(&paths[0]));
+        let stats = RunStats::load(&paths[1]).unwrap();
+        assert_eq!(stats.build_to_drop, Some(Duration::from_secs(45)));
+        assert_eq!(stats.items_per_kind["Cell"], 2);
+        assert_eq!(
+            stats.segments(),
+            vec![
+                ("Start", Duration::ZERO),
+                ("Reactor", Duration::from_secs(300))
+            ]
+        );
+
+        for (i, secs, outcome) in [(1, 600, Outcome::Success), (2, 300, Outcome::Failed)] {
+            RunStats::new(&run(secs, outcome))
+                .save(&dir.join(format!("{i}.stats.json")))
+                .unwrap();
+        }
+        fs::write(dir.join("broken.stats.json"), "{").unwrap();
+
+        let best = RunStats::personal_best(dir.path(), "R1A1").unwrap();
+        let other = RunStats::personal_best(dir.path(), "R1B1").unwrap();
+
+        assert_eq!(best.unwrap().duration, Some(Duration::from_secs(600)));
+        assert_eq!(other, None);
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{convert::Infallible, fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
@@ -6,13 +6,167 @@ use serde::{Deserialize, Serialize};
 pub struct Zone {
     pub alias: u32,
     pub local: u32,
-    pub dimension: String,
+    pub dimension: Dimension,
     pub layer: String,
+    /// Area inside the zone, set on the zones items are filed under
     pub area: Option<char>,
 }
 
-impl Display for Zone {
+impl Zone {
+    /// Stand-in for a zone items were distributed into before it was generated.
+    pub fn placeholder(key: impl Into<ZoneKey>) -> Self {
+        let key = key.into();
+        Zone {
+            alias: key.alias,
+            local: 0,
+            dimension: key.dimension,
+            layer: key.layer,
+            area: None,
+        }
+    }
+
+    /// What tells this zone apart from the level's others.
+    pub fn key(&self) -> ZoneKey {
+        ZoneKey {
+            alias: self.alias,
+            dimension: self.dimension.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+
+    /// Whether this is the zone of `key`.
+    pub fn is(&self, key: &ZoneKey) -> bool {
+        self.alias == key.alias && self.dimension == key.dimension && self.layer == key.layer
+    }
+}
+
+/// Identifies a zone of a level. Aliases alone don't, portal levels reuse them in every
+/// dimension.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct ZoneKey {
+    pub alias: u32,
+    pub dimension: Dimension,
+    pub layer: String,
+}
+
+/// Key of a zone only known by its alias, taken to be in `Reality` on an unknown layer.
+impl From<u32> for ZoneKey {
+    fn from(alias: u32) -> Self {
+        ZoneKey {
+            alias,
+            dimension: Dimension::Reality,
+            layer: "Unknown".into(),
+        }
+    }
+}
+
+impl Display for ZoneKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "ZONE_{} {} {}", self.alias, self.layer, self.dimension)
     }
 }
+
+/// Name to show for a zone's layer, `Overload` for the `ThirdLayer` the game logs.
+pub fn layer_label(layer: &str) -> &str {
+    match layer {
+        "MainLayer" => "Main",
+        "SecondaryLayer" => "Secondary",
+        "ThirdLayer" => "Overload",
+        layer => layer,
+    }
+}
+
+impl Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ZONE_{}", self.alias)?;
+        if let Some(area) = self.area {
+            write!(f, " Area {area}")?;
+        }
+        write!(f, " {} {}", self.layer, self.dimension)
+    }
+}
+
+/// Dimension a zone was generated in. Portal levels, like R7 and R8, have zones in
+/// `Dimension_1` and up next to `Reality`.
+///
+/// Kept as the raw log name when (de)serialized, so older saves keep loading.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Dimension {
+    Reality,
+    /// `Dimension_<n>`
+    Numbered(u32),
+    /// Anything the game may add that isn't one of the above
+    Other(String),
+}
+
+impl Dimension {
+    /// Name to show as a heading, `Dimension 1` instead of `Dimension_1`.
+    pub fn label(&self) -> String {
+        match self {
+            Dimension::Numbered(n) => format!("Dimension {n}"),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl From<&str> for Dimension {
+    fn from(name: &str) -> Self {
+        if name == "Reality" {
+            return Dimension::Reality;
+        }
+
+        match name.strip_prefix("Dimension_").map(str::parse) {
+            Some(Ok(n)) => Dimension::Numbered(n),
+            _ => Dimension::Other(name.into()),
+        }
+    }
+}
+
+impl From<String> for Dimension {
+    fn from(name: String) -> Self {
+        Dimension::from(name.as_str())
+    }
+}
+
+impl From<Dimension> for String {
+    fn from(dimension: Dimension) -> Self {
+        dimension.to_string()
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Dimension::from(s))
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dimension::Reality => write!(f, "Reality"),
+            Dimension::Numbered(n) => write!(f, "Dimension_{n}"),
+            Dimension::Other(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dimension_names_round_trip() {
+        for name in ["Reality", "Dimension_1", "Dimension_12", "Arena"] {
+            assert_eq!(Dimension::from(name).to_string(), name);
+        }
+        assert_eq!(Dimension::from("Dimension_3"), Dimension::Numbered(3));
+        assert_eq!(Dimension::from("Dimension_3").label(), "Dimension 3");
+        assert_eq!(
+            serde_json::to_string(&Dimension::Numbered(2)).unwrap(),
+            r#""Dimension_2""#
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::{cell::RefCell, collections::VecDeque, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Something the parser noticed about the log, like a line that looked like it should have
+/// matched a pattern but didn't. Mostly useful for modded rundowns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.severity, self.message)
+    }
+}
+
+thread_local! {
+    static PENDING: RefCell<Vec<Diagnostic>> = const { RefCell::new(vec![]) };
+}
+
+/// Logs `message` and keeps it for the next [`take`] on this thread. The tail and parser
+/// threads pass theirs on as [`crate::parser::ParserMsg::Diagnostic`].
+pub fn report(severity: Severity, message: impl Into<String>) {
+    let message = message.into();
+    match severity {
+        Severity::Info => log::debug!("{message}"),
+        Severity::Warning => log::warn!("{message}"),
+        Severity::Error => log::error!("{message}"),
+    }
+
+    PENDING.with_borrow_mut(|pending| pending.push(Diagnostic { severity, message }));
+}
+
+/// Diagnostics reported on this thread since the last call.
+pub fn take() -> Vec<Diagnostic> {
+    PENDING.take()
+}
+
+/// The last diagnostics, older ones are dropped once it's full.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticLog {
+    entries: VecDeque<Diagnostic>,
+    capacity: usize,
+}
+
+impl Default for DiagnosticLog {
+    fn default() -> Self {
+        DiagnosticLog::new(500)
+    }
+}
+
+impl DiagnosticLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(diagnostic);
+    }
+
+    /// Diagnostics at least as severe as `min`, oldest first.
+    pub fn iter(&self, min: Severity) -> impl Iterator<Item = &Diagnostic> {
+        self.entries.iter().filter(move |d| d.severity >= min)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_drops_oldest_and_filters() {
+        let mut log = DiagnosticLog::new(2);
+        for (severity, message) in [
+            (Severity::Warning, "dropped"),
+            (Severity::Info, "missed line"),
+            (Severity::Error, "malformed"),
+        ] {
+            log.push(Diagnostic {
+                severity,
+                message: message.into(),
+            });
+        }
+
+        assert_eq!(log.len(), 2);
+        let messages: Vec<_> = log
+            .iter(Severity::Warning)
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["malformed"]);
+    }
+
+    #[test]
+    fn reports_are_taken_once() {
+        report(Severity::Warning, "no SetupFloor batch");
+
+        assert_eq!(
+            take(),
+            vec![Diagnostic {
+                severity: Severity::Warning,
+                message: "no SetupFloor batch".into(),
+            }]
+        );
+        assert!(take().is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+/// Overrides the discovered log folder.
+pub const LOG_DIR_ENV: &str = "RUSTED_MAPPER_LOG_DIR";
+
+/// Name part of the logs the parser reads, the rest is a timestamp and the player.
+pub const LOG_NAME: &str = "NICKNAME_NETSTATUS";
+
+/// GTFO's Steam app id, the name of its Proton prefix.
+const GTFO_APP_ID: &str = "493520";
+
+/// Log folder inside a Windows user profile.
+const PROFILE_LOG_DIR: &str = "AppData/LocalLow/10 Chambers Collective/GTFO";
+
+/// Folder GTFO writes its logs to: [`LOG_DIR_ENV`] if set, otherwise the first platform
+/// candidate that exists. Falls back to the most likely candidate, so the parser can wait
+/// for the game to create it.
+pub fn log_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(LOG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    let candidates = candidates();
+    candidates
+        .iter()
+        .find(|dir| dir.is_dir())
+        .or(candidates.first())
+        .cloned()
+}
+
+/// Possible log folders for this platform, most likely first.
+#[cfg(windows)]
+pub fn candidates() -> Vec<PathBuf> {
+    dirs::home_dir()
+        .map(|home| vec![home.join(PROFILE_LOG_DIR)])
+        .unwrap_or_default()
+}
+
+/// Possible log folders for this platform, most likely first. GTFO runs through Proton, so
+/// these are in its prefix in every Steam library.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn candidates() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return vec![];
+    };
+
+    let roots = [
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        // Flatpak
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"),
+    ];
+
+    let mut libraries: Vec<PathBuf> = vec![];
+    for root in roots {
+        for library in std::iter::once(root.clone()).chain(
+            std::fs::read_to_string(root.join("steamapps/libraryfolders.vdf"))
+                .map(|vdf| library_paths(&vdf))
+                .unwrap_or_default(),
+        ) {
+            // `~/.steam/steam` is usually a link to one of the others.
+            let library = library.canonicalize().unwrap_or(library);
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+    }
+
+    libraries.iter().map(|lib| proton_log_dir(lib)).collect()
+}
+
+/// Possible log folders for this platform, most likely first. These are the CrossOver
+/// bottles GTFO might be installed in.
+#[cfg(target_os = "macos")]
+pub fn candidates() -> Vec<PathBuf> {
+    let Some(bottles) = dirs::data_dir().map(|dir| dir.join("CrossOver/Bottles")) else {
+        return vec![];
+    };
+
+    std::fs::read_dir(bottles)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|bottle| {
+                    bottle
+                        .path()
+                        .join("drive_c/users/crossover")
+                        .join(PROFILE_LOG_DIR)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether `path` is one of the logs the parser reads.
+pub fn is_log(path: &Path) -> bool {
+    is_named_log(path, LOG_NAME)
+}
+
+/// Whether the file name of `path` contains `log_name`, for logs not named like GTFO's.
+pub fn is_named_log(path: &Path, log_name: &str) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(log_name))
+}
+
+/// Most recently written log in `dir`. With several game instances logging at once, this is
+/// the one that's being played.
+pub fn latest_log(dir: &Path) -> Option<PathBuf> {
+    latest_named_log(dir, LOG_NAME)
+}
+
+/// Most recently written file in `dir` with `log_name` in its name.
+pub fn latest_named_log(dir: &Path, log_name: &str) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| is_named_log(&entry.path(), log_name))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Library folders listed in Steam's `libraryfolders.vdf`.
+pub fn library_paths(vdf: &str) -> Vec<PathBuf> {
+    let re = Regex::new(r#""path"\s+"(?<path>[^"]+)""#).unwrap();
+    re.captures_iter(vdf)
+        .map(|cap| PathBuf::from(cap["path"].replace(r"\\", r"\")))
+        .collect()
+}
+
+/// Log folder inside GTFO's Proton prefix in `library`.
+pub fn proton_log_dir(library: &Path) -> PathBuf {
+    library
+        .join("steamapps/compatdata")
+        .join(GTFO_APP_ID)
+        .join("pfx/drive_c/users/steamuser")
+        .join(PROFILE_LOG_DIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn libraries_from_vdf() {
+        let vdf = r#"
+"libraryfolders"
+{
+	"0"
+	{
+		"path"		"/home/deck/.local/share/Steam"
+		"apps"
+		{
+			"228980"		"471432033"
+		}
+	}
+	"1"
+	{
+		"path"		"/run/media/mmcblk0p1"
+	}
+}
+"#;
+
+        assert_eq!(
+            library_paths(vdf),
+            vec![
+                PathBuf::from("/home/deck/.local/share/Steam"),
+                PathBuf::from("/run/media/mmcblk0p1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn latest_log_skips_other_files() {
+        let dir = TempDir::new("latest");
+        let old = dir.join("GTFO.2024.01.01 20.00.00_A_NICKNAME_NETSTATUS.txt");
+        let new = dir.join("GTFO.2024.01.02 20.00.00_B_NICKNAME_NETSTATUS.txt");
+        fs::write(&old, "").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&new, "").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(dir.join("Player.log"), "").unwrap();
+
+        assert_eq!(latest_log(dir.path()), Some(new));
+        assert!(!is_log(&dir.join("Player.log")));
+        assert_eq!(
+            latest_named_log(dir.path(), "Player"),
+            Some(dir.join("Player.log"))
+        );
+    }
+
+    #[test]
+    fn log_dir_in_proton_prefix() {
+        assert_eq!(
+            proton_log_dir(Path::new("/run/media/mmcblk0p1")),
+            PathBuf::from(
+                "/run/media/mmcblk0p1/steamapps/compatdata/493520/pfx/drive_c/users/steamuser/AppData/LocalLow/10 Chambers Collective/GTFO"
+            )
+        );
+    }
+}
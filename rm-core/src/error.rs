@@ -0,0 +1,33 @@
+use std::{fmt::Display, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Errors the frontends are expected to handle and show to the user.
+///
+/// Errors while the parser runs are sent as [`crate::parser::ParserMsg::Error`], the parser
+/// keeps going after them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParserError {
+    /// GTFO hasn't created its log folder yet, i.e. the game was never launched.
+    WatchDirMissing(PathBuf),
+    /// The log folder watcher died and couldn't be started again, it's retried later
+    WatcherFailed(String),
+    /// A log couldn't be opened, or reads of it kept failing and are still being retried
+    LogFailed(PathBuf, String),
+}
+
+impl Display for ParserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParserError::WatchDirMissing(path) => {
+                write!(f, "Waiting for GTFO, {} doesn't exist yet", path.display())
+            }
+            ParserError::WatcherFailed(e) => write!(f, "Not watching for new logs: {e}"),
+            ParserError::LogFailed(path, e) => {
+                write!(f, "Failed to read {}: {e}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParserError {}
@@ -0,0 +1,500 @@
+use std::{
+    fmt::Display,
+    io::{ErrorKind, Write},
+    net::{SocketAddr, TcpListener},
+    str::FromStr,
+    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+#[cfg(unix)]
+use std::{os::unix::net::UnixListener, path::PathBuf};
+
+use anyhow::{anyhow, bail};
+use jiff::civil::Time;
+use log::{debug, error, info};
+use might_sleep::cpu_limiter::CpuLimiter;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{GatherItem, Outcome, TimerEntry},
+    parser::ParserMsg,
+};
+
+/// Where the event publisher listens for clients. Kept in the config as its `tcp:` or
+/// `unix:` string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum EventSink {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for EventSink {
+    type Err = anyhow::Error;
+
+    /// Accepts `tcp:<addr>:<port>` or `unix:<path>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("tcp", addr)) => Ok(EventSink::Tcp(addr.parse()?)),
+            #[cfg(unix)]
+            Some(("unix", path)) => Ok(EventSink::Unix(PathBuf::from(path))),
+            _ => Err(anyhow!(
+                "unknown event sink `{s}`, expected `tcp:<addr>:<port>` or `unix:<path>`"
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for EventSink {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<EventSink> for String {
+    fn from(sink: EventSink) -> Self {
+        sink.to_string()
+    }
+}
+
+impl Display for EventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventSink::Tcp(addr) => write!(f, "tcp:{addr}"),
+            #[cfg(unix)]
+            EventSink::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Events published to automation clients, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Event {
+    Seeds {
+        build: u32,
+        host: u32,
+        session: u32,
+    },
+    Expedition {
+        rundown: String,
+        exp: String,
+    },
+    Zone {
+        alias: u32,
+        local: u32,
+        dimension: String,
+        layer: String,
+        /// Log time, if the line had one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        at: Option<Time>,
+    },
+    Connection {
+        from: u32,
+        to: u32,
+    },
+    Key {
+        name: String,
+        dimension: String,
+        zone: u32,
+        ri: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        at: Option<Time>,
+    },
+    Gatherable {
+        item: GatherItem,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        zone: Option<u32>,
+        /// Dimension of `zone`, portal levels reuse aliases in every dimension
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dimension: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        area: Option<char>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        at: Option<Time>,
+    },
+    Collected {
+        item: String,
+        zone: u32,
+        seed: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        at: Option<Time>,
+    },
+    Outcome {
+        outcome: Outcome,
+    },
+    Checkpoint,
+    Reset,
+}
+
+impl Event {
+    /// Maps a parser message onto the event that automation clients care about, if any.
+    pub fn from_msg(msg: &ParserMsg) -> Option<Self> {
+        match msg {
+            ParserMsg::LevelSeeds(seeds, _) => Some(Event::Seeds {
+                build: seeds.build,
+                host: seeds.host,
+                session: seeds.session,
+            }),
+            ParserMsg::LevelInit(level) => Some(Event::Expedition {
+                rundown: format!("{:?}", level.rundown),
+                exp: level.exp_name.clone(),
+            }),
+            ParserMsg::GeneratedZone(TimerEntry::Zone(zone), at) => Some(Event::Zone {
+                alias: zone.alias,
+                local: zone.local,
+                dimension: zone.dimension.to_string(),
+                layer: zone.layer.clone(),
+                at: *at,
+            }),
+            ParserMsg::Connection(from, to) => Some(Event::Connection {
+                from: *from,
+                to: *to,
+            }),
+            ParserMsg::Gatherable(GatherItem::Key(name, dimension, zone, ri), at) => {
+                Some(Event::Key {
+                    name: name.clone(),
+                    dimension: dimension.to_string(),
+                    zone: *zone,
+                    ri: *ri,
+                    at: *at,
+                })
+            }
+            ParserMsg::Gatherable(item, at) => Some(Event::Gatherable {
+                item: item.clone(),
+                zone: None,
+                dimension: None,
+                area: None,
+                at: *at,
+            }),
+            ParserMsg::ZoneGatherable(zone, item, at) => Some(Event::Gatherable {
+                item: item.clone(),
+                zone: Some(zone.alias),
+                dimension: Some(zone.dimension.to_string()),
+                area: None,
+                at: *at,
+            }),
+            ParserMsg::AreaGatherable(zone, area, item, at) => Some(Event::Gatherable {
+                item: item.clone(),
+                zone: Some(zone.alias),
+                dimension: Some(zone.dimension.to_string()),
+                area: Some(*area),
+                at: *at,
+            }),
+            ParserMsg::Collected(pickup, at) => Some(Event::Collected {
+                item: pickup.item.clone(),
+                zone: pickup.zone,
+                seed: pickup.seed,
+                at: *at,
+            }),
+            ParserMsg::Outcome(outcome) => Some(Event::Outcome { outcome: *outcome }),
+            ParserMsg::Checkpoint => Some(Event::Checkpoint),
+            ParserMsg::Reset | ParserMsg::NewFile => Some(Event::Reset),
+            _ => None,
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(sink: &EventSink) -> anyhow::Result<Self> {
+        let listener = match sink {
+            EventSink::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)?;
+                listener.set_nonblocking(true)?;
+                Listener::Tcp(listener)
+            }
+            #[cfg(unix)]
+            EventSink::Unix(path) => {
+                use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+
+                // A socket nothing answers on is stale, from a previous run that didn't
+                // clean up. Anything else there isn't ours to remove.
+                if let Ok(meta) = std::fs::symlink_metadata(path) {
+                    if !meta.file_type().is_socket() {
+                        bail!("{} exists and isn't a socket", path.display());
+                    }
+                    if UnixStream::connect(path).is_ok() {
+                        bail!("Another mapper is publishing events on {}", path.display());
+                    }
+                    std::fs::remove_file(path)?;
+                }
+                let listener = UnixListener::bind(path)?;
+                listener.set_nonblocking(true)?;
+                Listener::Unix(listener)
+            }
+        };
+
+        Ok(listener)
+    }
+
+    /// Accepts a single pending client without blocking.
+    fn accept(&self) -> std::io::Result<Option<Box<dyn Write + Send>>> {
+        let stream: std::io::Result<Box<dyn Write + Send>> = match self {
+            Listener::Tcp(listener) => listener.accept().and_then(|(stream, addr)| {
+                info!("Event client connected from {addr}");
+                stream.set_nonblocking(true)?;
+                Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+            #[cfg(unix)]
+            Listener::Unix(listener) => listener.accept().and_then(|(stream, _)| {
+                info!("Event client connected");
+                stream.set_nonblocking(true)?;
+                Ok(Box::new(stream) as Box<dyn Write + Send>)
+            }),
+        };
+
+        match stream {
+            Ok(stream) => Ok(Some(stream)),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Connected client with the bytes of the events it hasn't taken yet.
+struct Client<W> {
+    stream: W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Client<W> {
+    /// Bytes a client can fall behind by before it's dropped
+    const MAX_PENDING: usize = 1 << 20;
+
+    fn new(stream: W) -> Self {
+        Self {
+            stream,
+            pending: vec![],
+        }
+    }
+
+    /// Queues `line` after what's still pending and writes as much as the client takes, so
+    /// a line cut off by a full socket is finished before the next one starts. Returns
+    /// whether the client is still connected.
+    fn send(&mut self, line: &[u8]) -> bool {
+        if self.pending.len() + line.len() > Self::MAX_PENDING {
+            debug!("Dropping event client, it's too far behind");
+            return false;
+        }
+        self.pending.extend_from_slice(line);
+        self.flush()
+    }
+
+    /// Writes what's pending until the client would block. Returns whether the client is
+    /// still connected.
+    fn flush(&mut self) -> bool {
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => {
+                    debug!("Dropping event client, it's closed");
+                    return false;
+                }
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    debug!("Dropping event client: {e}");
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Publishes [`Event`]s as newline-delimited JSON to every connected client.
+///
+/// Events are dropped if nobody is listening, and the publisher thread stops once
+/// the publisher is dropped.
+#[derive(Debug)]
+pub struct EventPublisher {
+    sink: EventSink,
+    tx: Option<Sender<Event>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl EventPublisher {
+    pub fn start(sink: EventSink) -> anyhow::Result<Self> {
+        let listener = Listener::bind(&sink)?;
+        let (tx, rx) = channel::<Event>();
+
+        let handle = thread::Builder::new()
+            .name("event publisher".into())
+            .spawn(move || EventPublisher::publish_loop(listener, rx))?;
+
+        info!("Publishing events on {sink}");
+
+        Ok(Self {
+            sink,
+            tx: Some(tx),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn sink(&self) -> &EventSink {
+        &self.sink
+    }
+
+    /// Forwards a parser message to connected clients if it maps onto an [`Event`].
+    pub fn publish(&self, msg: &ParserMsg) {
+        if let (Some(tx), Some(event)) = (&self.tx, Event::from_msg(msg)) {
+            let _ = tx.send(event);
+        }
+    }
+
+    fn publish_loop(listener: Listener, rx: Receiver<Event>) {
+        let mut limiter = CpuLimiter::new(Duration::from_millis(25));
+        let mut clients: Vec<Client<Box<dyn Write + Send>>> = vec![];
+
+        loop {
+            loop {
+                match listener.accept() {
+                    Ok(Some(client)) => clients.push(Client::new(client)),
+                    Ok(None) => break,
+                    Err(e) => {
+                        error!("Failed to accept event client: {e}");
+                        break;
+                    }
+                }
+            }
+
+            // Finish lines slow clients couldn't take in one go.
+            clients.retain_mut(Client::flush);
+
+            loop {
+                match rx.try_recv() {
+                    Ok(event) => {
+                        if clients.is_empty() {
+                            continue;
+                        }
+
+                        let mut line = match serde_json::to_string(&event) {
+                            Ok(line) => line,
+                            Err(e) => {
+                                error!("Failed to serialize {event:?}: {e}");
+                                continue;
+                            }
+                        };
+                        line.push('\n');
+
+                        // Slow clients get the event later, broken ones get dropped.
+                        clients.retain_mut(|client| client.send(line.as_bytes()));
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        info!("Event channel was disconnected, stopping publisher.");
+                        return;
+                    }
+                }
+            }
+
+            limiter.might_sleep();
+        }
+    }
+}
+
+impl Drop for EventPublisher {
+    fn drop(&mut self) {
+        self.tx.take();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+
+        #[cfg(unix)]
+        if let EventSink::Unix(path) = &self.sink {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    /// Socket taking `room` more bytes before it would block.
+    struct Socket {
+        written: Vec<u8>,
+        room: usize,
+    }
+
+    impl Write for Socket {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.room == 0 {
+                return Err(ErrorKind::WouldBlock.into());
+            }
+            let n = buf.len().min(self.room);
+            self.written.extend_from_slice(&buf[..n]);
+            self.room -= n;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn lines_cut_by_a_full_socket_are_finished_first() {
+        let mut client = Client::new(Socket {
+            written: vec![],
+            room: 4,
+        });
+        assert!(client.send(b"{\"type\":\"checkpoint\"}\n"));
+        assert!(client.send(b"{\"type\":\"reset\"}\n"));
+        assert_eq!(client.stream.written, b"{\"ty");
+
+        client.stream.room = usize::MAX;
+        assert!(client.flush());
+        assert_eq!(
+            client.stream.written,
+            b"{\"type\":\"checkpoint\"}\n{\"type\":\"reset\"}\n"
+        );
+        assert!(client.pending.is_empty());
+    }
+
+    #[test]
+    fn sink_round_trips_as_a_string() {
+        let sink: EventSink = serde_json::from_str("\"tcp:127.0.0.1:9870\"").unwrap();
+        assert_eq!(sink, EventSink::Tcp("127.0.0.1:9870".parse().unwrap()));
+        assert_eq!(
+            serde_json::to_string(&sink).unwrap(),
+            "\"tcp:127.0.0.1:9870\""
+        );
+        assert!(serde_json::from_str::<EventSink>("\"udp:127.0.0.1:9870\"").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_stale_sockets_are_replaced() {
+        let tmp = TempDir::new("events");
+        let file = tmp.join("events.json");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(Listener::bind(&EventSink::Unix(file.clone())).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+
+        let path = tmp.join("events.sock");
+        let running = Listener::bind(&EventSink::Unix(path.clone())).unwrap();
+        assert!(Listener::bind(&EventSink::Unix(path.clone())).is_err());
+
+        // Left behind by a mapper that didn't clean up.
+        drop(running);
+        assert!(Listener::bind(&EventSink::Unix(path)).is_ok());
+    }
+}
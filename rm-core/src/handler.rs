@@ -0,0 +1,139 @@
+//! Parsing lines rm-core doesn't know about, for forks and tools embedding the parser that
+//! want more out of the log without patching the parser.
+//!
+//! ```no_run
+//! use rm_core::{handler::RegexHandler, prelude::*};
+//!
+//! let mut parser = Parser::new(None);
+//! parser.add_handler(Box::new(RegexHandler::new(
+//!     "Reactor",
+//!     r"ReactorStartup wave: (?<wave>\d+)",
+//! )?));
+//! parser.start_watcher()?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::{fmt::Debug, sync::mpsc::Sender};
+
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+
+use crate::parser::ParserMsg;
+
+/// Turns the lines its pattern matches into [`ParserMsg::Custom`] messages with its name.
+pub trait LineHandler: Debug + Send {
+    /// Name the messages are sent with.
+    fn name(&self) -> &str;
+
+    /// Pattern matched against the log, once per complete line.
+    fn regex(&self) -> &Regex;
+
+    /// Data sent for a match, nothing is sent for `None`.
+    fn handle(&mut self, cap: &Captures) -> Option<Value>;
+}
+
+/// Handler that sends the named groups of its pattern as an object of strings, i.e.
+/// `{"wave": "3"}`. Groups that didn't take part in the match are left out.
+#[derive(Debug, Clone)]
+pub struct RegexHandler {
+    name: String,
+    regex: Regex,
+}
+
+impl RegexHandler {
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+
+impl LineHandler for RegexHandler {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    fn handle(&mut self, cap: &Captures) -> Option<Value> {
+        let groups: Map<_, _> = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| Some((name.to_string(), cap.name(name)?.as_str().into())))
+            .collect();
+
+        Some(Value::Object(groups))
+    }
+}
+
+/// Runs every handler over `lines`, which end on a complete line. Each handler's matches are
+/// sent in the order they're logged, one handler after the other.
+pub(crate) fn run(
+    handlers: &mut [Box<dyn LineHandler>],
+    lines: &str,
+    parser_tx: &Sender<ParserMsg>,
+) -> anyhow::Result<()> {
+    for handler in handlers {
+        let caps: Vec<_> = handler.regex().clone().captures_iter(lines).collect();
+        for cap in caps {
+            if let Some(value) = handler.handle(&cap) {
+                parser_tx.send(ParserMsg::Custom(handler.name().into(), value))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::parser::ParserManager;
+
+    #[test]
+    fn handlers_see_complete_lines() {
+        let (parser_tx, parser_rx) = channel();
+        let mut manager = ParserManager {
+            buffer: "20:03:18.001 - ReactorStartup wave: 1\n20:03:18.002 - ReactorStartup wave: 2"
+                .into(),
+            handlers: vec![Box::new(
+                RegexHandler::new(
+                    "Reactor",
+                    r"ReactorStartup wave: (?<wave>\d+)(?<done> done)?",
+                )
+                .unwrap(),
+            )],
+            ..Default::default()
+        };
+
+        manager.drain(&parser_tx).unwrap();
+        let custom = |rx: &std::sync::mpsc::Receiver<ParserMsg>| -> Vec<_> {
+            rx.try_iter()
+                .filter(|msg| matches!(msg, ParserMsg::Custom(..)))
+                .collect()
+        };
+        assert_eq!(
+            custom(&parser_rx),
+            vec![ParserMsg::Custom("Reactor".into(), json!({"wave": "1"}))]
+        );
+
+        // The second line only counts once it's complete.
+        manager.buffer.push_str(" done\n");
+        manager.drain(&parser_tx).unwrap();
+        assert_eq!(
+            custom(&parser_rx),
+            vec![ParserMsg::Custom(
+                "Reactor".into(),
+                json!({"wave": "2", "done": " done"})
+            )]
+        );
+    }
+}
@@ -1,4 +1,27 @@
+pub mod archive;
+pub mod chat;
+pub mod checkpoint;
+pub mod compare;
+pub mod config;
+pub mod control;
 pub mod data;
+pub mod diagnostics;
+pub mod discovery;
+pub mod error;
+pub mod events;
+pub mod handler;
+pub mod livesplit;
+pub mod locale;
 pub mod parser;
+pub mod prelude;
+pub mod profile;
 pub mod re;
+pub mod recording;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod snapshot;
+#[cfg(feature = "async")]
+pub mod stream;
 pub mod tail;
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,164 @@
+use std::{
+    io::Write,
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{channel, Receiver, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+
+use crate::parser::ParserMsg;
+
+/// Default port of the LiveSplit Server component.
+pub const DEFAULT_ADDR: &str = "localhost:16834";
+
+/// LiveSplit Server commands the mapper sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveSplitCmd {
+    StartTimer,
+    Split,
+    Reset,
+}
+
+impl LiveSplitCmd {
+    /// Maps the timer messages onto LiveSplit commands. Extraction is the last split, which
+    /// ends the run in LiveSplit, so only leaving the level resets the timer.
+    pub fn from_msg(msg: &ParserMsg) -> Option<Self> {
+        match msg {
+            ParserMsg::ElevatorDrop(_) => Some(LiveSplitCmd::StartTimer),
+            ParserMsg::ZoneDoorOpened(..) | ParserMsg::LevelFinish(_) | ParserMsg::Split(_) => {
+                Some(LiveSplitCmd::Split)
+            }
+            ParserMsg::Reset | ParserMsg::NewFile => Some(LiveSplitCmd::Reset),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            LiveSplitCmd::StartTimer => "starttimer",
+            LiveSplitCmd::Split => "split",
+            LiveSplitCmd::Reset => "reset",
+        }
+    }
+}
+
+/// Forwards timer messages to a LiveSplit Server over TCP.
+///
+/// The connection is made on the first command and remade after errors, commands sent while
+/// LiveSplit isn't reachable are dropped.
+#[derive(Debug)]
+pub struct LiveSplit {
+    addr: String,
+    tx: Option<Sender<LiveSplitCmd>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LiveSplit {
+    pub fn start(addr: String) -> anyhow::Result<Self> {
+        let (tx, rx) = channel::<LiveSplitCmd>();
+
+        let thread_addr = addr.clone();
+        let handle = thread::Builder::new()
+            .name("livesplit".into())
+            .spawn(move || LiveSplit::send_loop(thread_addr, rx))?;
+
+        info!("Forwarding splits to LiveSplit Server at {addr}");
+
+        Ok(Self {
+            addr,
+            tx: Some(tx),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Sends the matching command if the message is one LiveSplit cares about.
+    pub fn forward(&self, msg: &ParserMsg) {
+        if let (Some(tx), Some(cmd)) = (&self.tx, LiveSplitCmd::from_msg(msg)) {
+            let _ = tx.send(cmd);
+        }
+    }
+
+    fn connect(addr: &str) -> std::io::Result<TcpStream> {
+        let mut last_err = None;
+        for addr in addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| std::io::ErrorKind::AddrNotAvailable.into()))
+    }
+
+    fn send_loop(addr: String, rx: Receiver<LiveSplitCmd>) {
+        let mut stream: Option<TcpStream> = None;
+
+        for cmd in rx {
+            if stream.is_none() {
+                match LiveSplit::connect(&addr) {
+                    Ok(s) => {
+                        info!("Connected to LiveSplit Server at {addr}");
+                        stream = Some(s);
+                    }
+                    Err(e) => {
+                        warn!("Dropping {cmd:?}, LiveSplit Server at {addr} isn't reachable: {e}");
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(s) = stream.as_mut() {
+                if let Err(e) = s.write_all(format!("{}\r\n", cmd.as_str()).as_bytes()) {
+                    warn!("Lost connection to LiveSplit Server, dropping {cmd:?}: {e}");
+                    stream = None;
+                }
+            }
+        }
+
+        debug!("LiveSplit channel was disconnected, stopping thread.");
+    }
+}
+
+impl Drop for LiveSplit {
+    fn drop(&mut self) {
+        self.tx.take();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use super::*;
+
+    #[test]
+    fn commands_reach_the_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let livesplit = LiveSplit::start(addr).unwrap();
+        livesplit.forward(&ParserMsg::ElevatorDrop(None));
+        livesplit.forward(&ParserMsg::Checkpoint);
+        livesplit.forward(&ParserMsg::ZoneDoorOpened(50, None));
+        livesplit.forward(&ParserMsg::LevelFinish(None));
+        livesplit.forward(&ParserMsg::Reset);
+
+        let (mut client, _) = listener.accept().unwrap();
+        // Closes the connection once everything was sent.
+        drop(livesplit);
+
+        let mut received = String::new();
+        client.read_to_string(&mut received).unwrap();
+        assert_eq!(received, "starttimer\r\nsplit\r\nsplit\r\nreset\r\n");
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{info, warn};
+
+use crate::data::GatherItem;
+
+/// Locale every other one falls back to for keys it doesn't translate.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Locales shipped with the mapper, by code.
+const BUNDLED: [(&str, &str); 4] = [
+    ("en", include_str!("locales/en.toml")),
+    ("ru", include_str!("locales/ru.toml")),
+    ("zh", include_str!("locales/zh.toml")),
+    ("ko", include_str!("locales/ko.toml")),
+];
+
+/// GUI strings and item names in one language, from a TOML file of sections of keys like
+/// `[menu] file = "File"`, looked up as `menu.file`.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    pub code: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    /// Folder of user locales, `<code>.toml` files that add languages or replace bundled
+    /// strings.
+    pub fn user_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("locales"))
+    }
+
+    /// Bundled locale with `code` over English, just English for unknown codes.
+    pub fn bundled(code: &str) -> Self {
+        let mut locale = Locale {
+            code: code.into(),
+            strings: HashMap::new(),
+        };
+        for (bundled, toml) in BUNDLED {
+            if bundled == DEFAULT_LOCALE || bundled == code {
+                locale.extend(toml).expect("bundled locales are valid TOML");
+            }
+        }
+
+        locale
+    }
+
+    /// Bundled locale with the user's file of the same code on top. A broken file is logged
+    /// and skipped.
+    pub fn load(code: &str) -> Self {
+        let mut locale = Locale::bundled(code);
+        if let Some(path) = Locale::user_dir()
+            .map(|dir| dir.join(format!("{code}.toml")))
+            .filter(|path| path.is_file())
+        {
+            match locale.load_file(&path) {
+                Ok(()) => info!("Loaded locale {}", path.display()),
+                Err(e) => warn!("Skipping locale {}: {e}", path.display()),
+            }
+        }
+
+        locale
+    }
+
+    /// Replaces strings with the ones in the TOML file at `path`.
+    pub fn load_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.extend(&fs::read_to_string(path)?)
+    }
+
+    fn extend(&mut self, toml: &str) -> anyhow::Result<()> {
+        fn flatten(prefix: &str, table: toml::Table, strings: &mut HashMap<String, String>) {
+            for (key, value) in table {
+                let key = match prefix {
+                    "" => key,
+                    prefix => format!("{prefix}.{key}"),
+                };
+                match value {
+                    toml::Value::String(s) => {
+                        strings.insert(key, s);
+                    }
+                    toml::Value::Table(table) => flatten(&key, table, strings),
+                    _ => warn!("Ignoring locale key {key}, it's not text"),
+                }
+            }
+        }
+
+        flatten("", toml.parse()?, &mut self.strings);
+        Ok(())
+    }
+
+    /// Codes and names of the bundled locales and the ones in [`Locale::user_dir`], sorted
+    /// by code.
+    pub fn available() -> Vec<(String, String)> {
+        let mut codes: Vec<String> = BUNDLED.iter().map(|(code, _)| code.to_string()).collect();
+        if let Some(Ok(entries)) = Locale::user_dir().map(fs::read_dir) {
+            codes.extend(entries.filter_map(|entry| {
+                let path = entry.ok()?.path();
+                path.extension()
+                    .is_some_and(|ext| ext == "toml")
+                    .then(|| path.file_stem()?.to_str().map(String::from))?
+            }));
+        }
+        codes.sort();
+        codes.dedup();
+
+        codes
+            .into_iter()
+            .map(|code| {
+                let name = Locale::load(&code).tr("name").to_string();
+                (code, name)
+            })
+            .collect()
+    }
+
+    /// String for `key`, the key itself if no locale has it.
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map_or(key, String::as_str)
+    }
+
+    /// Name of `item` with its kind translated, the same as its `Display` in English.
+    pub fn item(&self, item: &GatherItem) -> String {
+        let tr = |key| self.tr(key);
+        match item {
+            GatherItem::HSU(id, area) => {
+                format!("{} {} {area} ({id})", tr("item.hsu"), tr("item.area"))
+            }
+            GatherItem::Generator(name, item_idx, idx) => {
+                format!("{name} #{idx} {} {item_idx}", tr("item.generator_item"))
+            }
+            GatherItem::ID(container, seed) => format!("{} {container} {seed}", tr("item.id")),
+            GatherItem::PD(container, seed) => format!("{} {container} {seed}", tr("item.pd")),
+            GatherItem::GLP1(container, seed) => format!("{} {container} {seed}", tr("item.glp1")),
+            GatherItem::OSIP(container, seed) => format!("{} {container} {seed}", tr("item.osip")),
+            GatherItem::Datasphere(zone_idx) => format!("{} {zone_idx}", tr("item.datasphere")),
+            GatherItem::PlantSample(container, seed) => {
+                format!("{} {container} {seed}", tr("item.plant_sample"))
+            }
+            GatherItem::DataCube(container, seed) => {
+                format!("{} {container} {seed}", tr("item.data_cube"))
+            }
+            GatherItem::GLP2(container, seed) => format!("{} {container} {seed}", tr("item.glp2")),
+            // Names the game gives, like keys and cells, read the same in every language.
+            item => item.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locales_fall_back_to_english() {
+        let en = Locale::bundled("en");
+        let ru = Locale::bundled("ru");
+
+        assert_eq!(en.tr("menu.file"), "File");
+        assert_eq!(ru.tr("menu.file"), "Файл");
+        assert_eq!(ru.tr("item.id"), "ID");
+        assert_eq!(ru.tr("missing.key"), "missing.key");
+        assert_eq!(Locale::bundled("xx").tr("menu.file"), "File");
+
+        for item in [
+            GatherItem::HSU(3, 'B'),
+            GatherItem::Generator("GENERATOR_1".into(), 2, 0),
+            GatherItem::ID("Box".into(), 12),
+            GatherItem::Datasphere(4),
+            GatherItem::PlantSample("Locker".into(), 34),
+            GatherItem::Cell(1),
+        ] {
+            assert_eq!(en.item(&item), item.to_string());
+        }
+        assert_eq!(ru.item(&GatherItem::HSU(3, 'B')), "HSU область B (3)");
+    }
+
+    #[test]
+    fn bundled_locales_have_only_english_keys() {
+        let en = Locale::bundled("en");
+        for (code, toml) in BUNDLED {
+            let mut locale = Locale::default();
+            locale.extend(toml).unwrap();
+            for key in locale.strings.keys() {
+                assert!(en.strings.contains_key(key), "{code} has unknown key {key}");
+            }
+        }
+    }
+}
@@ -1,428 +1,3262 @@
 use std::{
-    marker,
+    fmt::Display,
     path::{Path, PathBuf},
-    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{
+            channel, sync_channel, Receiver, RecvTimeoutError, SendError, Sender, SyncSender,
+            TryRecvError,
+        },
+        Arc, Mutex, Weak,
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use log::{error, info};
+use anyhow::bail;
+use jiff::civil::Time;
+use log::{error, info, warn};
 use might_sleep::cpu_limiter::CpuLimiter;
 use notify::{
-    event::CreateKind, recommended_watcher, Error, Event, RecommendedWatcher, RecursiveMode,
-    Watcher,
+    event::{CreateKind, ModifyKind, RenameMode},
+    recommended_watcher, Error, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
-use walkdir::WalkDir;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    data::{GatherItem, ItemIdentifier, Level, Rundown, TimerEntry, Zone},
-    re,
-    tail::{Tail, TailCmd, TailMsg},
+    checkpoint::{Checkpoint, CheckpointFile},
+    config::{PollConfig, ResumeConfig},
+    data::{
+        Dimension, GatherItem, GatherKind, Level, LevelEvent, Objective, Outcome, Pickup, Rundown,
+        SeedCorrelator, Seeds, SessionInfo, TimerEntry, Zone, ZoneKey,
+    },
+    diagnostics::{self, Diagnostic, Severity},
+    discovery,
+    error::ParserError,
+    handler::{self, LineHandler},
+    profile, re,
+    tail::{complete_lines, OpenFrom, PollRate, Tail, TailCmd, TailMsg, ThreadHandle},
 };
 
 #[derive(Debug)]
 pub struct Parser {
     watch_path: PathBuf,
-    dir_watcher: Option<RecommendedWatcher>,
+    /// Replaced by the supervisor thread whenever the watcher dies.
+    dir_watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    /// Set once the current watcher reports an error, cleared on reconnect.
+    watcher_failed: Arc<AtomicBool>,
+    supervisor: Option<ThreadHandle>,
+    /// Dropped to stop the supervisor thread
+    supervisor_stop: Option<Sender<()>>,
+    /// Where the log that already exists when watching starts is read from
+    open_from: OpenFrom,
+    poll: PollConfig,
+    resume: ResumeConfig,
+    /// Part of the name that tells logs apart from other files in the watch path
+    log_name: String,
+    /// Folder profiles are loaded from, the user's by default
+    profiles_dir: Option<PathBuf>,
+    /// Messages `rx` holds before the parser thread waits for them to be read, unbounded if
+    /// `None`
+    capacity: Option<usize>,
+    /// Handed to the parser thread when it starts
+    handlers: Vec<Box<dyn LineHandler>>,
     pub tail_cmd_tx: Option<Sender<TailCmd>>,
     pub rx: Option<Receiver<ParserMsg>>,
+    tail_handle: Option<ThreadHandle>,
+    parser_handle: Option<ThreadHandle>,
 }
 
-#[derive(Debug)]
+/// Configuration of a [`Parser`], for embedding it in other tools.
+///
+/// ```no_run
+/// use rm_core::prelude::*;
+///
+/// let mut parser = Parser::builder()
+///     .watch_path("logs")
+///     .poll_interval(std::time::Duration::from_millis(100))
+///     .channel_capacity(1024)
+///     .build();
+/// parser.start_watcher()?;
+/// for msg in parser.rx.take().unwrap() {
+///     println!("{msg}");
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserBuilder {
+    watch_path: Option<PathBuf>,
+    from_end: bool,
+    poll: PollConfig,
+    resume: ResumeConfig,
+    log_name: Option<String>,
+    profiles_dir: Option<PathBuf>,
+    capacity: Option<usize>,
+}
+
+impl ParserBuilder {
+    /// Folder to watch for logs, GTFO's by default.
+    pub fn watch_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.watch_path = Some(path.into());
+        self
+    }
+
+    /// See [`Parser::set_tail_from_end`].
+    pub fn tail_from_end(mut self, from_end: bool) -> Self {
+        self.from_end = from_end;
+        self
+    }
+
+    /// See [`Parser::set_poll`].
+    pub fn poll(mut self, poll: PollConfig) -> Self {
+        self.poll = poll;
+        self
+    }
+
+    /// See [`Parser::set_resume`].
+    pub fn resume(mut self, resume: ResumeConfig) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll.interval_ms = interval.as_millis() as u64;
+        self
+    }
+
+    /// Only files with `log_name` in their name are read, [`discovery::LOG_NAME`] by
+    /// default.
+    pub fn log_name(mut self, log_name: impl Into<String>) -> Self {
+        self.log_name = Some(log_name.into());
+        self
+    }
+
+    /// Folder the rundown profiles and their pattern overrides are loaded from instead of
+    /// [`profile::profiles_dir`]. Profiles are loaded once per process, by whichever parser
+    /// starts first.
+    pub fn profiles_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.profiles_dir = Some(dir.into());
+        self
+    }
+
+    /// Messages the receiver holds before the parser thread waits for them to be read.
+    /// Unbounded by default.
+    pub fn channel_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Parser with this configuration, started with [`Parser::start_watcher`].
+    pub fn build(self) -> Parser {
+        let mut parser = Parser::new(self.watch_path);
+        parser.set_tail_from_end(self.from_end);
+        parser.set_poll(self.poll);
+        parser.set_resume(self.resume);
+        if let Some(log_name) = self.log_name {
+            parser.log_name = log_name;
+        }
+        parser.profiles_dir = self.profiles_dir;
+        parser.capacity = self.capacity;
+        parser
+    }
+}
+
+/// Sender of the parser thread's messages. Sending on a full bounded channel waits until
+/// there is room.
+#[derive(Debug, Clone)]
+pub enum MsgSender {
+    Unbounded(Sender<ParserMsg>),
+    Bounded(SyncSender<ParserMsg>),
+}
+
+impl MsgSender {
+    pub fn send(&self, msg: ParserMsg) -> Result<(), SendError<ParserMsg>> {
+        match self {
+            MsgSender::Unbounded(tx) => tx.send(msg),
+            MsgSender::Bounded(tx) => tx.send(msg),
+        }
+    }
+}
+
+impl From<Sender<ParserMsg>> for MsgSender {
+    fn from(tx: Sender<ParserMsg>) -> Self {
+        MsgSender::Unbounded(tx)
+    }
+}
+
+/// Messages emitted by the parser thread. Serialized adjacently tagged, i.e.
+/// `{"type": "Gatherable", "data": {...}}`, so consumers never rely on variant order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
 pub enum ParserMsg {
-    LevelSeeds(u32, u32, u32),
-    LevelInit(Level),
-    GeneratedZone(TimerEntry),
-    Gatherable(GatherItem),
-    LevelStart,
-    ZoneDoorOpened,
-    LevelFinish,
+    /// Seeds of the level being built, with the time of the seed line.
+    LevelSeeds(Seeds, Option<Time>),
+    LevelInit(Box<Level>),
+    /// Zone generated during floor setup, with the time of its line. `Start` and `End` have
+    /// the times of the first and last line of the batch.
+    GeneratedZone(TimerEntry, Option<Time>),
+    /// Zone aliases connected during floor setup, `(from, to)`
+    Connection(u32, u32),
+    /// Item found during generation, with the time it was distributed. Items parsed from a
+    /// whole batch, like seeded items and generators, have the time the batch started.
+    Gatherable(GatherItem, Option<Time>),
+    /// Item distributed into the zone of the given key
+    ZoneGatherable(Box<ZoneKey>, GatherItem, Option<Time>),
+    /// Item distributed into an area of the zone of the given key, `(zone, area, item)`
+    AreaGatherable(Box<ZoneKey>, char, GatherItem, Option<Time>),
+    /// Generation finished and players can act, with the time of the `BuildDone` line.
+    /// Sent once per expedition.
+    LevelStart(Option<Time>),
+    /// Elevator drop finished, the run timer starts
+    ElevatorDrop(Option<Time>),
+    /// Security door into the zone with the given alias opened
+    ZoneDoorOpened(u32, Option<Time>),
+    /// Local player entered the zone with the given alias, in the given area if the game
+    /// logged it
+    ZoneEntered(u32, Option<char>, Option<Time>),
+    /// Scan of the bulkhead door controller in the zone with the given alias finished
+    BulkheadScanned(u32, Option<Time>),
+    /// Team reached a checkpoint, the one later [`ParserMsg::Checkpoint`] reloads go back to
+    CheckpointActivated(Option<Time>),
+    /// Extraction, the run timer stops
+    LevelFinish(Option<Time>),
+    /// Manual split, sent by frontends rather than the parser
+    Split(Option<Time>),
+    /// Checkpoint was reloaded, the current level stays as is.
+    Checkpoint,
+    /// Expedition ended or was abandoned, the current level should be cleared.
+    Reset,
+    /// Serial the players see in the zone with the given alias, like `TERMINAL_123` or
+    /// `CELL_4`, with the time of its line
+    Serial(u32, String, Option<Time>),
+    /// Warden objective set up during distribution, with the time of its line
+    Objective(Objective, Option<Time>),
+    /// Item picked up during the run
+    Collected(Pickup, Option<Time>),
+    /// The run ended, sent before the [`ParserMsg::Reset`] that clears it
+    Outcome(Outcome),
+    /// Scan or alarm started during the run, with the time of its line
+    LevelEvent(LevelEvent, Option<Time>),
+    /// What the log header says about the game, sent again whenever more of it is read
+    Session(SessionInfo),
+    /// How far the level's build is, i.e. `Generating... (batch 3/12)`, sent for every
+    /// batch until the [`ParserMsg::LevelStart`] of the level
+    BuildProgress(String),
 
     NewFile,
+    Status(ParserStatus),
+    /// Message parsed from a log opened with [`Parser::compare_with`], tagged with its
+    /// source. Never another `Sourced`.
+    Sourced(String, Box<ParserMsg>),
+    /// Something went wrong that the user should know about, parsing goes on
+    Error(ParserError),
+    /// Note about how parsing went, see [`diagnostics::report`]
+    Diagnostic(Diagnostic),
+    /// Generation of the current level went wrong, like a builder error or a batch that ran
+    /// more than once. The level may have rerolled or be missing items. Has the time of the
+    /// line, if the warning is about one.
+    Warning(String, Option<Time>),
+    /// Sent by a [`LineHandler`] added with [`Parser::add_handler`], with its name
+    Custom(String, serde_json::Value),
 }
 
-#[derive(Debug, Default)]
-enum ParserState {
+/// Health of the tail and parser threads, reported about once a second.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParserStatus {
+    /// Log file currently being tailed
+    pub file: Option<PathBuf>,
+    /// Timestamp of the last line read from the log
+    pub last_line: Option<Time>,
+    pub lines_per_sec: f32,
+    pub tail_alive: bool,
+}
+
+/// Parses the local time prefix of a log line.
+pub fn line_time(line: &str) -> Option<Time> {
+    re::LINE_TIME.captures(line)?["time"].parse().ok()
+}
+
+impl Display for ParserMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParserMsg::LevelSeeds(seeds, _) => write!(f, "Seeds {seeds}"),
+            ParserMsg::LevelInit(level) => write!(f, "Expedition {level}"),
+            ParserMsg::GeneratedZone(entry, _) => write!(f, "Generated {entry}"),
+            ParserMsg::Connection(from, to) => write!(f, "Connection ZONE_{from} -> ZONE_{to}"),
+            ParserMsg::Gatherable(item, _) => write!(f, "Gatherable {item}"),
+            ParserMsg::ZoneGatherable(zone, item, _) => {
+                write!(f, "Gatherable {item} {zone}")
+            }
+            ParserMsg::AreaGatherable(zone, area, item, _) => {
+                write!(f, "Gatherable {item} {zone} Area {area}")
+            }
+            ParserMsg::LevelStart(Some(time)) => {
+                // jiff's strftime has no fractional seconds.
+                write!(
+                    f,
+                    "Level start at {}.{:03}",
+                    time.strftime("%H:%M:%S"),
+                    time.millisecond()
+                )
+            }
+            ParserMsg::LevelStart(None) => write!(f, "Level start"),
+            ParserMsg::ElevatorDrop(_) => write!(f, "Elevator drop"),
+            ParserMsg::ZoneDoorOpened(alias, _) => write!(f, "Zone door opened ZONE_{alias}"),
+            ParserMsg::ZoneEntered(alias, Some(area), _) => {
+                write!(f, "Entered ZONE_{alias} Area {area}")
+            }
+            ParserMsg::ZoneEntered(alias, None, _) => write!(f, "Entered ZONE_{alias}"),
+            ParserMsg::BulkheadScanned(alias, _) => write!(f, "Bulkhead scanned ZONE_{alias}"),
+            ParserMsg::CheckpointActivated(_) => write!(f, "Checkpoint activated"),
+            ParserMsg::LevelFinish(_) => write!(f, "Level finish"),
+            ParserMsg::Split(_) => write!(f, "Split"),
+            ParserMsg::Checkpoint => write!(f, "Checkpoint"),
+            ParserMsg::Reset => write!(f, "Reset"),
+            ParserMsg::Serial(alias, serial, _) => write!(f, "Serial {serial} ZONE_{alias}"),
+            ParserMsg::Objective(objective, _) => write!(f, "Objective {objective}"),
+            ParserMsg::Collected(pickup, _) => write!(f, "Collected {pickup}"),
+            ParserMsg::Outcome(outcome) => write!(f, "Outcome {outcome}"),
+            ParserMsg::LevelEvent(event, _) => write!(f, "{event}"),
+            ParserMsg::Session(info) => write!(f, "Session {info}"),
+            ParserMsg::BuildProgress(progress) => write!(f, "{progress}"),
+            ParserMsg::NewFile => write!(f, "New file"),
+            ParserMsg::Sourced(source, msg) => write!(f, "[{source}] {msg}"),
+            ParserMsg::Status(status) => write!(f, "Status {status:?}"),
+            ParserMsg::Error(e) => write!(f, "Error {e}"),
+            ParserMsg::Diagnostic(diagnostic) => write!(f, "{diagnostic}"),
+            ParserMsg::Warning(warning, _) => write!(f, "Warning: {warning}"),
+            ParserMsg::Custom(name, value) => write!(f, "{name} {value}"),
+        }
+    }
+}
+
+/// Runtime events scanned for while a level is loaded.
+enum SessionEvent {
+    Checkpoint,
+    /// Game state changed `(from, to)`, `None` if the line was malformed
+    State(Option<(String, String)>, Option<Time>),
+    /// `None` if the pickup line was malformed
+    Pickup(Option<Pickup>, Option<Time>),
+    DoorOpened(Option<u32>, Option<Time>),
+    ZoneEntered(Option<u32>, Option<char>, Option<Time>),
+    BulkheadScanned(Option<u32>, Option<Time>),
+    CheckpointActivated(Option<Time>),
+    Level(LevelEvent, Option<Time>),
+    Quit,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum ParserState {
     #[default]
     LevelSeeds,
     LevelSelected,
     LevelGeneration,
     ItemGeneration,
     ElevatorDropFinish,
+    #[allow(dead_code)]
     LevelFinish,
+    #[allow(dead_code)]
     NotInLevel,
 }
 
-#[derive(Debug)]
-struct ParserManager {
+#[derive(Debug, Default)]
+pub(crate) struct ParserManager {
+    /// Complete lines of the log, patterns never see half a line.
     pub buffer: String,
+    /// Line the last push stopped in the middle of, added to `buffer` once it's complete.
+    pub partial: String,
+    /// Where level parsing resumes in `buffer`.
     pub pos: usize,
+    /// Where game state/checkpoint scanning resumes in `buffer`.
+    pub event_pos: usize,
+    /// Where the current level's build started, right after its seeds.
+    pub build_start: usize,
+    /// End of the lines already checked by [`trace_misses`].
+    pub traced: usize,
+    /// Outcome sent for the current run, cleared by checkpoint reloads.
+    pub outcome: Option<Outcome>,
+    /// Latch for [`ParserMsg::LevelStart`], cleared on reset.
+    pub started: bool,
+    /// Latch for [`ParserMsg::ElevatorDrop`], checkpoint reloads go through `InLevel` again.
+    pub dropped: bool,
     pub state: ParserState,
+    /// Run over every complete line, see [`Parser::add_handler`].
+    pub handlers: Vec<Box<dyn LineHandler>>,
+    /// What the log header said so far.
+    pub session: SessionInfo,
+    /// The header is over once the first level's seeds are logged.
+    pub header_done: bool,
+    /// Zones of the current level's SetupFloor batch, for the zones of its items.
+    pub zones: GeneratedZones,
+    /// Where counting the current build's batches resumes in `buffer`.
+    pub progress_pos: usize,
+    /// Batches the current build started so far.
+    pub batches: usize,
+    /// Batches the last finished build ran, the total the next one is shown against.
+    pub last_batches: Option<usize>,
+    /// Start of the seeds line of the level being parsed, cleared on reset.
+    pub level_start: Option<usize>,
 }
 
-impl Default for ParserManager {
-    fn default() -> Self {
-        Self {
-            buffer: "".into(),
-            pos: 0,
-            state: Default::default(),
-        }
+/// Zones generated in a SetupFloor batch. Portal levels reuse aliases in every dimension, so
+/// an alias from a distribution line only picks a zone together with the line's dimension.
+#[derive(Debug, Default)]
+pub(crate) struct GeneratedZones(Vec<ZoneKey>);
+
+impl GeneratedZones {
+    /// Key of the zone with `alias` that `line` is about. The dimension named on the line
+    /// picks between zones sharing the alias, lines without one are taken to be about
+    /// `Reality`'s. Zones that weren't generated get a key of their own.
+    fn key(&self, alias: u32, line: &str) -> ZoneKey {
+        let dimension = re::ZONE_DIMENSION
+            .captures(line)
+            .and_then(|cap| group::<Dimension>("ZONE_DIMENSION", &cap, "dim"));
+        let candidates = || {
+            self.0.iter().filter(|key| {
+                key.alias == alias && dimension.as_ref().is_none_or(|dim| key.dimension == *dim)
+            })
+        };
+
+        candidates()
+            .find(|key| dimension.is_some() || key.dimension == Dimension::Reality)
+            .or_else(|| candidates().next())
+            .cloned()
+            .unwrap_or_else(|| ZoneKey {
+                dimension: dimension.unwrap_or(Dimension::Reality),
+                ..ZoneKey::from(alias)
+            })
     }
 }
 
 impl Parser {
     pub fn new(watch_path: Option<PathBuf>) -> Self {
-        let profile_path = if let Some(path) = watch_path {
-            path
-        } else {
-            Path::new(env!("USERPROFILE")).join("appdata\\locallow\\10 Chambers Collective\\GTFO")
-        };
+        let profile_path = watch_path.or_else(discovery::log_dir).unwrap_or_default();
 
         Parser {
             watch_path: profile_path,
-            dir_watcher: None,
+            dir_watcher: Default::default(),
+            watcher_failed: Default::default(),
+            supervisor: None,
+            supervisor_stop: None,
+            open_from: OpenFrom::Start,
+            poll: PollConfig::default(),
+            resume: ResumeConfig::default(),
+            log_name: discovery::LOG_NAME.into(),
+            profiles_dir: None,
+            capacity: None,
+            handlers: vec![],
+            tail_handle: None,
+            parser_handle: None,
             tail_cmd_tx: None,
             rx: None,
         }
     }
 
+    /// Parser configured before it starts, see [`ParserBuilder`].
+    pub fn builder() -> ParserBuilder {
+        ParserBuilder::default()
+    }
+
     pub fn start_watcher(&mut self) -> anyhow::Result<()> {
-        let (command_tx, data_rx): (Sender<TailCmd>, Receiver<TailMsg>) = Tail::start_listen()?;
+        // Overrides have to be in place before the parser thread compiles any pattern.
+        profile::load_from(self.profiles_dir.clone().or_else(profile::profiles_dir));
+
+        let rate = PollRate::new(self.poll.interval());
+        let (command_tx, data_rx, tail_handle) = Tail::start_listen(rate.clone())?;
 
-        let (parser_tx, parser_rx) = channel::<ParserMsg>();
+        let (parser_tx, parser_rx) = match self.capacity {
+            Some(capacity) => {
+                let (tx, rx) = sync_channel(capacity);
+                (MsgSender::Bounded(tx), rx)
+            }
+            None => {
+                let (tx, rx) = channel();
+                (MsgSender::Unbounded(tx), rx)
+            }
+        };
 
-        self.tail_cmd_tx = Some(command_tx.clone());
+        self.tail_cmd_tx = Some(command_tx);
         self.rx = Some(parser_rx);
+        let poll = self.poll;
+        let checkpoint = CheckpointFile::new(&self.resume);
+        let handlers = std::mem::take(&mut self.handlers);
+        self.tail_handle = Some(tail_handle);
 
-        thread::Builder::new()
-            .name("parser".into())
-            .spawn(|| Parser::parser(data_rx, parser_tx))?;
-
-        // We first look for `NICKNAME_NETSTATUS` file in case
-        // rusted-mapper was opened after the game was open.
-        for entry in WalkDir::new(self.watch_path.clone().as_path())
-            .min_depth(1)
-            .max_depth(1)
-            .sort_by(|a, b| {
-                b.metadata()
-                    .unwrap()
-                    .modified()
-                    .unwrap()
-                    .cmp(&a.metadata().unwrap().modified().unwrap())
-            })
-            .into_iter()
-            .flatten()
-        {
-            info!("{:?}", entry.file_name());
-            if entry
-                .file_name()
-                .to_str()
-                .map_or(false, |v| v.contains("NICKNAME_NETSTATUS"))
-            {
-                command_tx.send(TailCmd::Open(entry.path().to_path_buf()))?;
-                break;
-            }
+        self.parser_handle = Some(
+            thread::Builder::new()
+                .name("parser".into())
+                .spawn(move || {
+                    Parser::parser(data_rx, parser_tx, poll, rate, checkpoint, handlers)
+                })?,
+        );
+
+        self.watch_dir()
+    }
+
+    /// Parses lines the parser doesn't know about with `handler`, which sends what it finds as
+    /// [`ParserMsg::Custom`]. Has to be added before [`Parser::start_watcher`], handlers only
+    /// see the log being tailed and not the ones opened with [`Parser::compare_with`].
+    pub fn add_handler(&mut self, handler: Box<dyn LineHandler>) {
+        if self.parser_handle.is_some() {
+            warn!(
+                "Parser already started, ignoring handler {}",
+                handler.name()
+            );
+            return;
+        }
+        self.handlers.push(handler);
+    }
+
+    /// Only read what's logged from now on when attaching to a running game, instead of
+    /// replaying the whole session. Has to be set before [`Parser::start_watcher`].
+    pub fn set_tail_from_end(&mut self, from_end: bool) {
+        self.open_from = if from_end {
+            OpenFrom::End
+        } else {
+            OpenFrom::Start
+        };
+    }
+
+    /// Keeps a [`Checkpoint`] of how far the log was parsed, which the log is picked up from
+    /// again if the parser is restarted while the game still writes it. Has to be set before
+    /// [`Parser::start_watcher`].
+    pub fn set_resume(&mut self, resume: ResumeConfig) {
+        self.resume = resume;
+    }
+
+    /// How often the threads poll, has to be set before [`Parser::start_watcher`].
+    pub fn set_poll(&mut self, poll: PollConfig) {
+        self.poll = poll;
+    }
+
+    pub fn watch_path(&self) -> &Path {
+        &self.watch_path
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.dir_watcher.lock().unwrap().is_some()
+    }
+
+    /// Opens the latest log in the watch path and starts watching it for new ones.
+    ///
+    /// Fails with [`ParserError::WatchDirMissing`] if GTFO hasn't created the folder yet,
+    /// in which case it can be retried later without restarting the threads.
+    pub fn watch_dir(&mut self) -> anyhow::Result<()> {
+        let Some(command_tx) = self.tail_cmd_tx.clone() else {
+            bail!("Parser threads aren't running");
+        };
+
+        if !self.watch_path.is_dir() {
+            return Err(ParserError::WatchDirMissing(self.watch_path.clone()).into());
+        }
+
+        // The game may already be running, in which case its log is already there.
+        if let Some(path) = discovery::latest_named_log(&self.watch_path, &self.log_name) {
+            info!("Found log {}", path.display());
+            let from = CheckpointFile::new(&self.resume).map_or(self.open_from, |checkpoint| {
+                checkpoint.open_from(&path, self.resume.from, self.open_from)
+            });
+            command_tx.send(TailCmd::Open(path, from))?;
+        }
+
+        self.watcher_failed.store(false, Ordering::Relaxed);
+        let watcher = Parser::create_watcher(
+            &self.watch_path,
+            &self.log_name,
+            command_sender(command_tx.clone()),
+            self.watcher_failed.clone(),
+        )?;
+        *self.dir_watcher.lock().unwrap() = Some(watcher);
+
+        if self.supervisor.is_none() {
+            let dir_watcher = Arc::downgrade(&self.dir_watcher);
+            let watch_path = self.watch_path.clone();
+            let log_name = self.log_name.clone();
+            let failed = self.watcher_failed.clone();
+            let (stop_tx, stop_rx) = channel();
+
+            self.supervisor = Some(
+                thread::Builder::new()
+                    .name("watcher supervisor".into())
+                    .spawn(move || {
+                        Parser::supervise(
+                            dir_watcher,
+                            watch_path,
+                            log_name,
+                            command_tx,
+                            failed,
+                            stop_rx,
+                        );
+                        Ok(())
+                    })?,
+            );
+            self.supervisor_stop = Some(stop_tx);
         }
 
+        Ok(())
+    }
+
+    /// Watches `watch_path` for new logs, `failed` is set once the watcher reports an error
+    /// or the folder itself goes away.
+    ///
+    /// Logs that are created or renamed into the folder are opened. When a log is removed or
+    /// renamed away, the most recent one left is opened instead. The tail ignores opens of
+    /// the log it's already reading, and notices truncated or recreated logs on its own.
+    pub(crate) fn create_watcher(
+        watch_path: &Path,
+        log_name: &str,
+        send: impl Fn(TailCmd) + Send + 'static,
+        failed: Arc<AtomicBool>,
+    ) -> anyhow::Result<RecommendedWatcher> {
+        let root = watch_path.to_path_buf();
+        let log_name = log_name.to_string();
         let mut watcher = recommended_watcher(move |res: Result<Event, Error>| match res {
             Ok(event) => {
                 info!("{:?} {:?} {:?}", event.kind, event.attrs, event.paths);
-                if let notify::EventKind::Create(CreateKind::Any) = event.kind {
-                    if let Some(path) = event.paths.first() {
-                        if let Some(filename) = path.file_name() {
-                            if filename
-                                .to_str()
-                                .map_or(false, |v| v.contains("NICKNAME_NETSTATUS"))
-                            {
-                                command_tx.send(TailCmd::Open(path.to_path_buf())).unwrap();
-                            }
+                // New logs are read whole no matter where existing ones start.
+                let open = |path: PathBuf| send(TailCmd::Open(path, OpenFrom::Start));
+
+                match event.kind {
+                    EventKind::Remove(_) if event.paths.contains(&root) => {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                    // Windows reports `Any`, inotify reports `File`. Renames come with both
+                    // paths, the new one last.
+                    EventKind::Create(CreateKind::Any | CreateKind::File)
+                    | EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Both)) => {
+                        if let Some(path) = event
+                            .paths
+                            .last()
+                            .filter(|path| discovery::is_named_log(path, &log_name))
+                        {
+                            open(path.clone());
+                        }
+                    }
+                    EventKind::Remove(_)
+                    | EventKind::Modify(ModifyKind::Name(RenameMode::From))
+                        if event
+                            .paths
+                            .iter()
+                            .any(|path| discovery::is_named_log(path, &log_name)) =>
+                    {
+                        if let Some(path) = discovery::latest_named_log(&root, &log_name) {
+                            open(path);
                         }
                     }
+                    _ => {}
                 }
             }
-            Err(e) => error!("{e:?}"),
+            Err(e) => {
+                error!("{e:?}");
+                failed.store(true, Ordering::Relaxed);
+            }
         })?;
 
-        watcher.watch(self.watch_path.as_path(), RecursiveMode::NonRecursive)?;
+        watcher.watch(watch_path, RecursiveMode::NonRecursive)?;
+
+        Ok(watcher)
+    }
+
+    /// Re-creates the directory watcher when it dies, with an exponential backoff between
+    /// failed attempts. Stops once `stop` is dropped or the [`Parser`] owning the watcher is.
+    fn supervise(
+        dir_watcher: Weak<Mutex<Option<RecommendedWatcher>>>,
+        watch_path: PathBuf,
+        log_name: String,
+        command_tx: Sender<TailCmd>,
+        failed: Arc<AtomicBool>,
+        stop: Receiver<()>,
+    ) {
+        const MIN_BACKOFF: Duration = Duration::from_secs(1);
+        const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+        let mut backoff = MIN_BACKOFF;
+        let mut retry_at = Instant::now();
+
+        while let Err(RecvTimeoutError::Timeout) = stop.recv_timeout(MIN_BACKOFF) {
+            let Some(dir_watcher) = dir_watcher.upgrade() else {
+                break;
+            };
+
+            if !failed.load(Ordering::Relaxed) && watch_path.is_dir() {
+                continue;
+            }
+            if Instant::now() < retry_at {
+                continue;
+            }
+
+            info!("Watcher for {} died, reconnecting", watch_path.display());
+            failed.store(false, Ordering::Relaxed);
+
+            match Parser::create_watcher(
+                &watch_path,
+                &log_name,
+                command_sender(command_tx.clone()),
+                failed.clone(),
+            ) {
+                Ok(watcher) => {
+                    *dir_watcher.lock().unwrap() = Some(watcher);
+                    info!("Watcher for {} reconnected", watch_path.display());
+                    backoff = MIN_BACKOFF;
+                }
+                Err(e) => {
+                    failed.store(true, Ordering::Relaxed);
+                    error!("Failed to reconnect watcher, retrying in {backoff:?}: {e}");
+                    let _ =
+                        command_tx.send(TailCmd::Report(ParserError::WatcherFailed(e.to_string())));
+                    retry_at = Instant::now() + backoff;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    /// Stops the tail, parser and watcher supervisor threads and waits up to
+    /// `SHUTDOWN_TIMEOUT` for them to finish. Dropping the parser does the same, this just makes the exit point explicit.
+    pub fn shutdown(mut self) {
+        self.stop_threads();
+    }
+
+    fn stop_threads(&mut self) {
+        const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+        // The tail forwards the stop to the parser thread, so both wind down from here.
+        if let Some(tail_cmd_tx) = self.tail_cmd_tx.take() {
+            let _ = tail_cmd_tx.send(TailCmd::Stop);
+        }
+        self.supervisor_stop.take();
+        self.dir_watcher.lock().unwrap().take();
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        for handle in [
+            self.tail_handle.take(),
+            self.parser_handle.take(),
+            self.supervisor.take(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let name = handle.thread().name().unwrap_or_default().to_string();
+            while !handle.is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+
+            if !handle.is_finished() {
+                warn!("{name} thread didn't stop in {SHUTDOWN_TIMEOUT:?}, leaving it");
+                continue;
+            }
+            match handle.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error!("{name} thread failed: {e:?}"),
+                Err(_) => error!("{name} thread panicked"),
+            }
+        }
+    }
+
+    /// Parses a finished log from the start, i.e. to look at a run after the game was closed.
+    /// Messages arrive on `rx` like live ones, the log isn't tailed afterwards.
+    pub fn parse_file(&self, path: PathBuf) -> anyhow::Result<()> {
+        let Some(tail_cmd_tx) = &self.tail_cmd_tx else {
+            bail!("Parser threads aren't running");
+        };
+        tail_cmd_tx.send(TailCmd::Replay(path))?;
+
+        Ok(())
+    }
+
+    /// Switches the tail to the newest log in the watch path, in case it missed the game
+    /// starting a new one.
+    pub fn reopen_latest(&self) -> anyhow::Result<()> {
+        let Some(tail_cmd_tx) = &self.tail_cmd_tx else {
+            bail!("Parser threads aren't running");
+        };
+        let Some(path) = discovery::latest_named_log(&self.watch_path, &self.log_name) else {
+            bail!("No log in {}", self.watch_path.display());
+        };
+        tail_cmd_tx.send(TailCmd::Open(path, OpenFrom::Start))?;
+
+        Ok(())
+    }
+
+    /// Asks the tail to pass on what it has read right away.
+    pub fn force_update(&self) -> anyhow::Result<()> {
+        if let Some(tail_cmd_tx) = &self.tail_cmd_tx {
+            tail_cmd_tx.send(TailCmd::ForceUpdate)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parses another log alongside the current one, e.g. the host's, with its messages
+    /// arriving as [`ParserMsg::Sourced`]. Returns the source they're tagged with, the log's
+    /// file name.
+    pub fn compare_with(&self, path: PathBuf) -> anyhow::Result<String> {
+        let Some(tail_cmd_tx) = &self.tail_cmd_tx else {
+            bail!("Parser threads aren't running");
+        };
+        let source = source_name(&path);
+        tail_cmd_tx.send(TailCmd::OpenSource(source.clone(), path))?;
+
+        Ok(source)
+    }
 
-        self.dir_watcher = Some(watcher);
+    pub fn stop_comparing(&self, source: &str) -> anyhow::Result<()> {
+        if let Some(tail_cmd_tx) = &self.tail_cmd_tx {
+            tail_cmd_tx.send(TailCmd::CloseSource(source.into()))?;
+        }
 
         Ok(())
     }
 
     pub fn stop_tail(&mut self) -> anyhow::Result<()> {
-        self.tail_cmd_tx.clone().unwrap().send(TailCmd::Stop)?;
+        if let Some(tail_cmd_tx) = &self.tail_cmd_tx {
+            tail_cmd_tx.send(TailCmd::Stop)?;
+        }
 
         Ok(())
     }
 
-    pub fn parser(data_rx: Receiver<TailMsg>, parser_tx: Sender<ParserMsg>) -> anyhow::Result<()> {
-        let mut limiter = CpuLimiter::new(Duration::from_millis(250));
-        let mut parser_manager = ParserManager::default();
+    /// Parser thread. With [`PollConfig::adaptive`] it sets `rate` for both threads as the
+    /// log goes in and out of levels, with a `checkpoint` it writes how far it got every
+    /// second.
+    pub(crate) fn parser(
+        data_rx: Receiver<TailMsg>,
+        parser_tx: MsgSender,
+        poll: PollConfig,
+        rate: PollRate,
+        checkpoint: Option<CheckpointFile>,
+        handlers: Vec<Box<dyn LineHandler>>,
+    ) -> anyhow::Result<()> {
+        let mut limiter = CpuLimiter::new(rate.get());
+        // Steps send on an unbounded channel, passed on to `parser_tx` after each poll.
+        let (step_tx, step_rx) = channel();
+        let mut tail = TailParser::new(checkpoint, handlers);
+        let mut lines = 0;
+        let mut status_at = Instant::now();
 
         loop {
             match data_rx.try_recv() {
-                Ok(val) => {
-                    // For now we get the message and propagate it back
-                    match val {
-                        TailMsg::Content(s) => {
-                            parser_manager.buffer.push_str(s.as_str());
-                        }
-                        TailMsg::NewFile => {
-                            parser_manager.buffer.clear();
-                            parser_manager.state = ParserState::LevelSeeds;
-                            parser_tx.send(ParserMsg::NewFile)?;
-                        }
-                        TailMsg::Stop => break,
+                Ok(msg) => {
+                    if let TailMsg::Content(s) = &msg {
+                        lines += s.lines().count();
+                    }
+                    if !tail.receive(msg, &step_tx)? {
+                        break;
                     }
                 }
                 Err(TryRecvError::Empty) => {}
                 Err(TryRecvError::Disconnected) => {
                     error!("Got disconnect from data channel");
+                    tail.status.tail_alive = false;
+                    parser_tx.send(ParserMsg::Status(tail.status))?;
                     break;
                 }
             }
 
-            // Check for level end trigger/level de-init.
-            if false {
-                todo!();
-                continue;
+            tail.manager.step(&step_tx)?;
+            tail.sources.drain(&step_tx)?;
+            send_diagnostics(&step_tx)?;
+            for msg in step_rx.try_iter() {
+                parser_tx.send(msg)?;
             }
 
-            match parser_manager.state {
-                ParserState::LevelSeeds => {
-                    if let Some(ref cap) = re::BUILDER_LEVEL_SEEDS
-                        .captures_iter(&parser_manager.buffer)
-                        .last()
-                    {
-                        let (_, [build_seed, host_seed, session_seed]) = cap.extract();
-                        parser_manager.pos = cap.get(0).unwrap().end();
+            if status_at.elapsed() >= Duration::from_secs(1) {
+                tail.status.lines_per_sec = lines as f32 / status_at.elapsed().as_secs_f32();
+                lines = 0;
+                status_at = Instant::now();
+                parser_tx.send(ParserMsg::Status(tail.status.clone()))?;
+                tail.write_checkpoint();
+            }
 
-                        let build_seed = build_seed.parse::<u32>()?;
-                        let host_seed = host_seed.parse::<u32>()?;
-                        let session_seed = session_seed.parse::<u32>()?;
+            if poll.adaptive {
+                rate.set(tail.manager.poll_interval(&poll));
+            }
+            limiter.duration = rate.get();
+            limiter.might_sleep();
+        }
 
-                        parser_tx.send(ParserMsg::LevelSeeds(
-                            build_seed,
-                            host_seed,
-                            session_seed,
-                        ))?;
+        Ok(())
+    }
+}
 
-                        parser_manager.state = ParserState::LevelSelected;
-                    }
+/// Parsers of what a tail reads, with where the log being parsed was opened. Driven by the
+/// parser thread and by the async parser, which only differ in how they wait for the tail.
+#[derive(Debug)]
+pub(crate) struct TailParser {
+    pub(crate) manager: ParserManager,
+    pub(crate) sources: Sources,
+    pub(crate) status: ParserStatus,
+    checkpoint: Option<CheckpointFile>,
+    /// Log being parsed, at the byte it was opened from
+    opened: Option<Checkpoint>,
+}
+
+impl TailParser {
+    pub(crate) fn new(
+        checkpoint: Option<CheckpointFile>,
+        handlers: Vec<Box<dyn LineHandler>>,
+    ) -> Self {
+        Self {
+            manager: ParserManager {
+                handlers,
+                ..Default::default()
+            },
+            sources: Sources::default(),
+            status: ParserStatus {
+                tail_alive: true,
+                ..Default::default()
+            },
+            checkpoint,
+            opened: None,
+        }
+    }
+
+    /// Takes in what the tail sent, what comes of it right away is sent on `parser_tx`.
+    /// False once the tail stopped.
+    pub(crate) fn receive(
+        &mut self,
+        msg: TailMsg,
+        parser_tx: &Sender<ParserMsg>,
+    ) -> anyhow::Result<bool> {
+        match msg {
+            TailMsg::Content(s) => {
+                if let Some(time) = s.lines().rev().find_map(line_time) {
+                    self.status.last_line = Some(time);
                 }
-                ParserState::LevelSelected => {
-                    if let Some(cap) = re::DROP_SERVER_MANAGER_NEW_SESSION
-                        .captures_iter(&parser_manager.buffer)
-                        .last()
-                    {
-                        let (_, [rundown_idx, rundown_exp]) = cap.extract();
-                        parser_manager.pos = cap.get(0).unwrap().end();
+                self.manager.push(&s);
+            }
+            TailMsg::NewFile(path, offset) => {
+                // Replayed archives send their logs back to back, the last one has to be
+                // parsed to the end before it's dropped.
+                self.manager.finish();
+                self.manager.drain(parser_tx)?;
+                self.manager = ParserManager {
+                    handlers: std::mem::take(&mut self.manager.handlers),
+                    ..Default::default()
+                };
+                parser_tx.send(ParserMsg::NewFile)?;
 
-                        let rundown_idx = rundown_idx.parse::<u16>()?;
-                        let rundown_exp = rundown_exp.to_string();
+                // The header of a resumed log was read before the restart.
+                let resumed = self
+                    .checkpoint
+                    .as_ref()
+                    .and_then(|checkpoint| checkpoint.resumed(&path, offset));
+                if let Some(session) = resumed {
+                    self.manager.session = session.clone();
+                    self.manager.header_done = true;
+                    parser_tx.send(ParserMsg::Session(session.clone()))?;
+                }
+                self.opened = Some(Checkpoint::new(&path, offset));
 
-                        let level = Level {
-                            rundown: Rundown::from_repr(rundown_idx).unwrap_or(Rundown::Modded),
-                            exp_name: rundown_exp.clone(),
-                            ..Default::default()
-                        };
+                self.status.file = Some(path);
+                self.status.last_line = None;
+                parser_tx.send(ParserMsg::Status(self.status.clone()))?;
+            }
+            TailMsg::NewSource(source, _) => self.sources.open(source, parser_tx)?,
+            TailMsg::SourceContent(source, s) => self.sources.push(&source, &s),
+            // Same log read on from where it stopped, the parser keeps its state.
+            TailMsg::Reopened(path) => info!("Reopened {}", path.display()),
+            TailMsg::Error(e) => parser_tx.send(ParserMsg::Error(e))?,
+            TailMsg::Diagnostic(diagnostic) => parser_tx.send(ParserMsg::Diagnostic(diagnostic))?,
+            TailMsg::Stop => {
+                self.write_checkpoint();
+                return Ok(false);
+            }
+        }
 
-                        parser_tx.send(ParserMsg::LevelInit(level))?;
+        Ok(true)
+    }
 
-                        parser_manager.state = ParserState::LevelGeneration;
-                    }
-                }
-                ParserState::LevelGeneration => {
-                    // TODO: add check if level already exists as file and load zones from file
+    /// Writes how far the log was parsed, if resuming is on.
+    pub(crate) fn write_checkpoint(&mut self) {
+        if let (Some(checkpoint), Some(opened)) = (&mut self.checkpoint, &self.opened) {
+            checkpoint.write(self.manager.checkpoint(opened));
+        }
+    }
+}
 
-                    let (batch_start, batch_end) = (
-                        re::SETUP_FLOOR_BATCH_START
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.start()),
-                        re::SETUP_FLOOR_BATCH_END
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.end()),
-                    );
+/// Source a log is compared under, its file name.
+pub(crate) fn source_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
 
-                    if let (Some(start), Some(end)) = (batch_start, batch_end) {
-                        parser_tx.send(ParserMsg::GeneratedZone(TimerEntry::Start))?;
-                        for cap in
-                            re::ZONE_CREATED.captures_iter(&parser_manager.buffer[start..end])
-                        {
-                            let (_, [alias, local, dim, layer]) = cap.extract();
-                            parser_tx.send(ParserMsg::GeneratedZone(TimerEntry::Zone(Zone {
-                                alias: alias.parse::<u32>()?,
-                                local: local.parse::<u32>()?,
-                                dimension: dim.to_string(),
-                                layer: layer.to_string(),
-                                area: None,
-                            })))?;
-                        }
+/// Parsers of the logs opened with [`TailCmd::OpenSource`], each with its own buffer.
+#[derive(Debug)]
+pub(crate) struct Sources {
+    parsers: Vec<(String, ParserManager)>,
+    source_tx: Sender<ParserMsg>,
+    source_rx: Receiver<ParserMsg>,
+}
 
-                        parser_tx.send(ParserMsg::GeneratedZone(TimerEntry::End))?;
+impl Default for Sources {
+    fn default() -> Self {
+        let (source_tx, source_rx) = channel();
+        Self {
+            parsers: vec![],
+            source_tx,
+            source_rx,
+        }
+    }
+}
 
-                        parser_manager.state = ParserState::ItemGeneration;
-                    }
-                }
-                ParserState::ItemGeneration => {
-                    // TODO: Biggest state yet
-                    // General work that we need to do here:
-                    // - Parse for gatherable items (any _other_ gatherable that we can encounter) and record their zones and count
-                    // - Parse the information for mappable items like keys - 1st Variant
-                    // - Parse the information for mappable items that have item seed - 2nd Variant
-                    // - Parse the information for generators if we have generator objective - 3rd Variant
-
-                    let (
-                        distribution_batch_start,
-                        distribution_batch_end,
-                        marker_batch_start,
-                        marker_batch_end,
-                    ) = (
-                        re::DISTRIBUTION_BATCH_START
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.start()),
-                        re::DISTRIBUTION_BATCH_END
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.end()),
-                        re::FUNCTION_MARKERS_BATCH_START
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.start()),
-                        re::FUNCTION_MARKERS_BATCH_END
-                            .captures_iter(&parser_manager.buffer)
-                            .last()
-                            .and_then(|c| c.get(0))
-                            .map(|m| m.end()),
-                    );
+impl Sources {
+    /// Starts `source` over, its log was (re)opened.
+    pub(crate) fn open(
+        &mut self,
+        source: String,
+        parser_tx: &Sender<ParserMsg>,
+    ) -> anyhow::Result<()> {
+        self.parsers.retain(|(open, _)| *open != source);
+        parser_tx.send(ParserMsg::Sourced(
+            source.clone(),
+            Box::new(ParserMsg::NewFile),
+        ))?;
+        self.parsers.push((source, ParserManager::default()));
 
-                    if let (
-                        Some(distribution_start),
-                        Some(distribution_end),
-                        Some(marker_start),
-                        Some(marker_end),
-                    ) = (
-                        distribution_batch_start,
-                        distribution_batch_end,
-                        marker_batch_start,
-                        marker_batch_end,
-                    ) {
-                        let distribution_segment =
-                            &parser_manager.buffer[distribution_start..distribution_end];
-                        let marker_segment = &parser_manager.buffer[marker_start..marker_end];
+        Ok(())
+    }
 
-                        // Keys
-                        for cap in
-                            re::CREATE_KEY_ITEM_DISTRIBUTION.captures_iter(distribution_segment)
-                        {
-                            let (_, [key, dim, _, alias, ri]) = cap.extract();
-                            parser_tx.send(ParserMsg::Gatherable(GatherItem::Key(
-                                key.into(),
-                                dim.into(),
-                                alias.parse()?,
-                                ri.parse()?,
-                            )))?;
-                        }
+    pub(crate) fn push(&mut self, source: &str, content: &str) {
+        if let Some((_, parser)) = self.parsers.iter_mut().find(|(open, _)| open == source) {
+            parser.push(content);
+        }
+    }
 
-                        let mut collectibles: Vec<ItemIdentifier> = vec![];
+    /// Parses what every source has buffered, tagging the messages with their source.
+    /// Diagnostics and errors are passed on as they are.
+    pub(crate) fn drain(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        for (source, parser) in &mut self.parsers {
+            parser.drain(&self.source_tx)?;
+            for msg in self.source_rx.try_iter() {
+                let msg = match msg {
+                    ParserMsg::Diagnostic(_) | ParserMsg::Error(_) => msg,
+                    msg => ParserMsg::Sourced(source.clone(), Box::new(msg)),
+                };
+                parser_tx.send(msg)?;
+            }
+        }
 
-                        for cap in
-                            re::DISTRIBUTE_WARDEN_OBJECTIVE.captures_iter(distribution_segment)
-                        {
-                            let (_, [alias, idx, item]) = cap.extract();
-
-                            collectibles.push(
-                                match ItemIdentifier::from_repr(item.parse()?).unwrap() {
-                                    ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => {
-                                        ItemIdentifier::DataCube
-                                    }
-                                    other => other,
-                                },
-                            );
-                        }
+        Ok(())
+    }
+}
 
-                        let mut seeded_collectibles = collectibles.iter().filter(|x| {
-                            matches!(
-                                x,
-                                ItemIdentifier::ID
-                                    | ItemIdentifier::PD
-                                    | ItemIdentifier::GLP1
-                                    | ItemIdentifier::OSIP
-                                    | ItemIdentifier::PlantSample
-                                    | ItemIdentifier::DataCube
-                                    | ItemIdentifier::DataCubeR8
-                                    | ItemIdentifier::GLP2
-                            )
-                        });
-
-                        for cap in re::GENERIC_SMALL_PICKUP_ITEM.captures_iter(marker_segment) {
-                            let (_, [container, seed]) = cap.extract();
-                            let seed = seed.parse::<u32>()?;
-
-                            let item = seeded_collectibles.next();
-
-                            let collectible = match item {
-                                Some(item) => match item {
-                                    ItemIdentifier::ID => GatherItem::ID(container.into(), seed),
-                                    ItemIdentifier::PD => GatherItem::PD(container.into(), seed),
-                                    ItemIdentifier::GLP1 => {
-                                        GatherItem::GLP1(container.into(), seed)
-                                    }
-                                    ItemIdentifier::OSIP => {
-                                        GatherItem::OSIP(container.into(), seed)
-                                    }
-                                    ItemIdentifier::PlantSample => {
-                                        GatherItem::PlantSample(container.into(), seed)
-                                    }
-                                    ItemIdentifier::DataCube | ItemIdentifier::DataCubeR8 => {
-                                        GatherItem::DataCube(container.into(), seed)
-                                    }
-                                    ItemIdentifier::GLP2 => {
-                                        GatherItem::GLP2(container.into(), seed)
-                                    }
-                                    _ => GatherItem::Seeded(container.into(), seed),
-                                },
-                                None => GatherItem::Seeded(container.into(), seed),
-                            };
-
-                            parser_tx.send(ParserMsg::Gatherable(collectible))?;
-                        }
+/// Passes the watcher's commands on to the tail thread. The tail is only gone when shutting
+/// down.
+fn command_sender(command_tx: Sender<TailCmd>) -> impl Fn(TailCmd) + Send + 'static {
+    move |cmd| {
+        let _ = command_tx.send(cmd);
+    }
+}
 
-                        for (i, cap) in re::WARDEN_OBJECTIVE_MANAGER
-                            .captures_iter(marker_segment)
-                            .enumerate()
-                        {
-                            dbg!(cap);
-                        }
+/// Looks up and parses a named group, logging and returning `None` instead of panicking
+/// when a changed log line or a user pattern didn't provide it.
+fn group<T: FromStr>(pattern: &str, cap: &Captures, name: &str) -> Option<T> {
+    let Some(value) = cap.name(name) else {
+        diagnostics::report(
+            Severity::Warning,
+            format!("{pattern} matched without `{name}`: {}", &cap[0]),
+        );
+        return None;
+    };
 
-                        parser_manager.state = ParserState::ElevatorDropFinish;
-                    }
-                }
-                ParserState::ElevatorDropFinish => {
-                    // TODO: ;_;
-                }
-                ParserState::LevelFinish => {}
-                ParserState::NotInLevel => {}
-            }
+    let value = value.as_str().parse().ok();
+    if value.is_none() {
+        diagnostics::report(
+            Severity::Warning,
+            format!("{pattern} has malformed `{name}`: {}", &cap[0]),
+        );
+    }
 
-            limiter.might_sleep();
+    value
+}
+
+/// Reports every line containing `guard` that isn't part of a match of `re`, so a pattern
+/// broken by a game update or a modded rundown shows up in the parser log.
+fn trace_misses(pattern: &str, guard: &str, re: &Regex, haystack: &str) {
+    let matches: Vec<_> = re.find_iter(haystack).map(|m| m.range()).collect();
+
+    let mut start = 0;
+    for line in haystack.split_inclusive('\n') {
+        if line.contains(guard) && !matches.iter().any(|m| m.contains(&start)) {
+            diagnostics::report(
+                Severity::Info,
+                format!("{pattern} missed line: {}", line.trim_end()),
+            );
         }
+        start += line.len();
+    }
+}
 
-        Ok(())
+/// Passes on what was reported on this thread since the last call.
+pub(crate) fn send_diagnostics(parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+    for diagnostic in diagnostics::take() {
+        parser_tx.send(ParserMsg::Diagnostic(diagnostic))?;
     }
+
+    Ok(())
 }
 
-impl Drop for Parser {
-    fn drop(&mut self) {
-        self.stop_tail().unwrap();
+/// Runs the parser over a complete log, i.e. a saved one or a test fixture, and returns
+/// every message it produced in order.
+pub fn parse_str(log: &str) -> Vec<ParserMsg> {
+    let mut parser = LogParser::new();
+    let mut msgs = parser.push(log);
+    msgs.extend(parser.finish());
+    msgs
+}
+
+/// Parser that's handed the log piece by piece instead of tailing it, i.e. lines from a game
+/// mod. What a piece leads to depends on what came before, so one parser is kept per log.
+#[derive(Debug, Default)]
+pub struct LogParser {
+    manager: ParserManager,
+}
+
+impl LogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [`Parser::add_handler`].
+    pub fn add_handler(&mut self, handler: Box<dyn LineHandler>) {
+        self.manager.handlers.push(handler);
+    }
+
+    /// Adds a line to the log, with or without its line break, and returns the messages
+    /// it led to.
+    pub fn push_line(&mut self, line: &str) -> Vec<ParserMsg> {
+        self.push(&format!("{}\n", line.trim_end_matches(['\r', '\n'])))
+    }
+
+    /// Adds text to the log, which may stop in the middle of a line, and returns the
+    /// messages it led to. A line that's cut off is only parsed once the rest of it is
+    /// pushed.
+    pub fn push(&mut self, text: &str) -> Vec<ParserMsg> {
+        self.manager.push(text);
+        self.drain()
+    }
+
+    /// Ends the log, its last line is parsed even without a line break.
+    pub fn finish(&mut self) -> Vec<ParserMsg> {
+        self.manager.finish();
+        self.drain()
+    }
+
+    fn drain(&mut self) -> Vec<ParserMsg> {
+        let (parser_tx, parser_rx) = channel();
+        // Only fails once the receiver is gone, which it isn't.
+        self.manager.drain(&parser_tx).unwrap();
+
+        parser_rx.try_iter().collect()
+    }
+}
+
+/// Signs that the build in `build` went wrong: builder errors, and batches that ran more
+/// than once because the game retried them.
+fn generation_anomalies(build: &str) -> Vec<(String, Option<Time>)> {
+    let mut warnings = vec![];
+    for cap in re::GENERATION_ANOMALY.captures_iter(build) {
+        if let Some(anomaly) = group::<String>("GENERATION_ANOMALY", &cap, "anomaly") {
+            warnings.push((anomaly, line_time(&cap[0])));
+        }
+    }
+
+    for (batch, start) in [
+        ("SetupFloor", &re::SETUP_FLOOR_BATCH_START),
+        ("Distribution", &re::DISTRIBUTION_BATCH_START),
+        ("FunctionMarkers", &re::FUNCTION_MARKERS_BATCH_START),
+    ] {
+        let runs: Vec<_> = start.find_iter(build).collect();
+        if runs.len() > 1 {
+            warnings.push((
+                format!(
+                    "{batch} batch ran {} times, the level may have rerolled",
+                    runs.len()
+                ),
+                runs.last().and_then(|m| line_time(m.as_str())),
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Adds what the log header says about the game to `info`, whether it said anything new.
+fn read_header(header: &str, info: &mut SessionInfo) -> bool {
+    let before = info.clone();
+
+    if info.build.is_none() {
+        if let Some(cap) = re::GAME_BUILD.captures(header) {
+            info.build = group("GAME_BUILD", &cap, "build");
+            info.revision = cap
+                .name("revision")
+                .and_then(|revision| revision.as_str().parse().ok());
+        }
+    }
+    if info.player.is_none() {
+        info.player = re::PLAYER_NAME
+            .captures(header)
+            .and_then(|cap| group("PLAYER_NAME", &cap, "player"));
+    }
+    if info.platform.is_none() {
+        info.platform = re::PLATFORM
+            .captures(header)
+            .and_then(|cap| group("PLATFORM", &cap, "platform"));
+    }
+
+    *info != before
+}
+
+/// Objective items from the Distribution batch that are only known by name or index, i.e.
+/// power cells, cargo crates, fog turbines, cryo cases, the R2E1 neonate and HiSec cargo,
+/// with the zone they spawn in.
+fn named_objectives(distribution: &str, zones: &GeneratedZones) -> Vec<(ZoneKey, GatherItem)> {
+    let name = "DISTRIBUTE_NAMED_OBJECTIVE";
+    re::DISTRIBUTE_NAMED_OBJECTIVE
+        .captures_iter(distribution)
+        .filter_map(|cap| {
+            let (Some(alias), Some(idx), Some(item)) = (
+                group::<u32>(name, &cap, "alias"),
+                group::<u32>(name, &cap, "idx"),
+                group::<u8>(name, &cap, "item"),
+            ) else {
+                return None;
+            };
+
+            let item = profile::active().item(item)?.named(alias, idx)?;
+            Some((zones.key(alias, &cap[0]), item))
+        })
+        .collect()
+}
+
+/// Terminals the uplink objectives are placed on, from the Distribution batch, with their
+/// zone. Terminals without a logged serial are numbered in the order of their zone.
+fn uplink_terminals(distribution: &str, zones: &GeneratedZones) -> Vec<(ZoneKey, GatherItem)> {
+    let name = "UPLINK_TERMINAL";
+    let mut terminals: Vec<(ZoneKey, GatherItem)> = vec![];
+
+    for cap in re::UPLINK_TERMINAL.captures_iter(distribution) {
+        let Some(alias) = group::<u32>(name, &cap, "alias") else {
+            continue;
+        };
+        let key = zones.key(alias, &cap[0]);
+        let terminal = match cap.name("serial") {
+            Some(serial) => serial.as_str().to_string(),
+            None => {
+                let idx = terminals.iter().filter(|(zone, _)| *zone == key).count();
+                format!("ZONE_{alias} #{idx}")
+            }
+        };
+
+        // Every step of the objective may log its terminal again.
+        let item = GatherItem::Uplink(terminal);
+        if !terminals.iter().any(|(_, uplink)| *uplink == item) {
+            terminals.push((key, item));
+        }
+    }
+
+    terminals
+}
+
+/// Special carry items from their own spawn lines in the FunctionMarkers batch, with the
+/// zone and area of where they are. A spawn line takes over an item of the same kind
+/// distributed into its zone, keeping its name, so items logged both ways are mapped once.
+fn carry_items(
+    markers: &str,
+    zones: &GeneratedZones,
+    named: &mut Vec<(ZoneKey, GatherItem)>,
+) -> Vec<(ZoneKey, Option<char>, GatherItem)> {
+    let name = "CARRY_ITEM_SPAWN";
+    let mut items: Vec<(ZoneKey, Option<char>, GatherItem)> = vec![];
+    for cap in re::CARRY_ITEM_SPAWN.captures_iter(markers) {
+        let (Some(spawn_name), Some(alias)) = (
+            group::<String>(name, &cap, "item"),
+            group::<u32>(name, &cap, "alias"),
+        ) else {
+            continue;
+        };
+        let area = cap
+            .name("area")
+            .and_then(|area| area.as_str().chars().next());
+        let Some(kind) =
+            GatherItem::carried(&spawn_name, alias, 0).map(|item| GatherKind::from(&item))
+        else {
+            continue;
+        };
+
+        let key = zones.key(alias, &cap[0]);
+        let same =
+            |zone: &ZoneKey, item: &GatherItem| *zone == key && GatherKind::from(item) == kind;
+        let item = match named.iter().position(|(zone, item)| same(zone, item)) {
+            Some(i) => named.remove(i).1,
+            None => {
+                let idx = items
+                    .iter()
+                    .filter(|(zone, _, item)| same(zone, item))
+                    .count();
+                let Some(item) = GatherItem::carried(&spawn_name, alias, idx as u32) else {
+                    continue;
+                };
+                item
+            }
+        };
+        items.push((key, area, item));
+    }
+
+    items
+}
+
+/// Gather objectives from the Distribution batch, with the time of their line.
+fn objectives(distribution: &str) -> Vec<(Objective, Option<Time>)> {
+    let name = "WARDEN_OBJECTIVE_ITEMS";
+    re::WARDEN_OBJECTIVE_ITEMS
+        .captures_iter(distribution)
+        .filter_map(|cap| {
+            let (Some(count), Some(item)) = (
+                group::<u32>(name, &cap, "count"),
+                group::<u8>(name, &cap, "item"),
+            ) else {
+                return None;
+            };
+
+            let Some(item) = profile::active().item(item) else {
+                diagnostics::report(
+                    Severity::Warning,
+                    format!("Skipping objective for unknown item id {item}: {}", &cap[0]),
+                );
+                return None;
+            };
+
+            Some((Objective { item, count }, line_time(&cap[0])))
+        })
+        .collect()
+}
+
+/// Generators of the level's objective collections, from the FunctionMarkers batch. Every
+/// status change is logged, so only the first line of each generator counts. Generators are
+/// numbered in the order they show up in their collection.
+fn generators(markers: &str, zones: &GeneratedZones) -> Vec<(Option<ZoneKey>, GatherItem)> {
+    let name = "WARDEN_OBJECTIVE_MANAGER";
+    let mut generators: Vec<(Option<ZoneKey>, GatherItem)> = vec![];
+
+    for cap in re::WARDEN_OBJECTIVE_MANAGER.captures_iter(markers) {
+        let (Some(collection), Some(generator)) = (
+            group::<u8>(name, &cap, "id"),
+            group::<String>(name, &cap, "name"),
+        ) else {
+            continue;
+        };
+        let zone = cap
+            .name("alias")
+            .and_then(|alias| alias.as_str().parse().ok())
+            .map(|alias| zones.key(alias, &cap[0]));
+
+        if generators
+            .iter()
+            .any(|(_, item)| matches!(item, GatherItem::Generator(n, ..) if *n == generator))
+        {
+            continue;
+        }
+
+        let idx = generators
+            .iter()
+            .filter(|(_, item)| matches!(item, GatherItem::Generator(_, c, _) if *c == collection))
+            .count() as u8;
+        generators.push((zone, GatherItem::Generator(generator, collection, idx)));
+    }
+
+    generators
+}
+
+/// Pairs the objective items of the Distribution batch with the container seeds rolled in the
+/// FunctionMarkers batch through a [`SeedCorrelator`], so this only runs once both batches
+/// are complete.
+fn merge_seeded(
+    distribution: &str,
+    markers: &str,
+    zones: &GeneratedZones,
+) -> Vec<(Option<ZoneKey>, GatherItem)> {
+    let mut correlator = SeedCorrelator::default();
+    let mut unknown = vec![];
+
+    for cap in re::DISTRIBUTE_WARDEN_OBJECTIVE.captures_iter(distribution) {
+        let name = "DISTRIBUTE_WARDEN_OBJECTIVE";
+        let (Some(alias), Some(idx), Some(id)) = (
+            group::<u32>(name, &cap, "alias"),
+            group::<u32>(name, &cap, "idx"),
+            group::<u8>(name, &cap, "item"),
+        ) else {
+            continue;
+        };
+
+        let Some(item) = profile::active().item(id) else {
+            let label = profile::active()
+                .name(id)
+                .map_or_else(|| format!("Item {id}"), String::from);
+            diagnostics::report(
+                Severity::Warning,
+                format!("Unknown item id {id}, kept as {label}: {}", &cap[0]),
+            );
+            unknown.push((
+                Some(zones.key(alias, &cap[0])),
+                GatherItem::Unknown(id, format!("{label} ZONE_{alias} #{idx}")),
+            ));
+            continue;
+        };
+
+        correlator.add_item(zones.key(alias, &cap[0]), item);
+    }
+
+    for cap in re::GENERIC_SMALL_PICKUP_ITEM.captures_iter(markers) {
+        let name = "GENERIC_SMALL_PICKUP_ITEM";
+        let (Some(container), Some(seed)) = (
+            group::<String>(name, &cap, "container"),
+            group::<u32>(name, &cap, "seed"),
+        ) else {
+            continue;
+        };
+
+        correlator.add_seed(cap.name("kind").map(|kind| kind.as_str()), &container, seed);
+    }
+
+    let mut items = correlator.resolve();
+    items.extend(unknown);
+    items
+}
+
+impl ParserManager {
+    /// Absolute bounds of the first `start..end` batch after the current position. Only the
+    /// current build is searched, so batches of a later level are never read as this one's.
+    fn batch(&self, start: &Regex, end: &Regex) -> Option<(usize, usize)> {
+        let rest = &self.buffer[self.pos..self.build_end().unwrap_or(self.buffer.len())];
+        let start = start.find(rest)?.start();
+        let end = start + end.find(&rest[start..])?.end();
+
+        Some((self.pos + start, self.pos + end))
+    }
+
+    /// Start of the `BuildDone` line that ends the current build, once it's been logged.
+    fn build_end(&self) -> Option<usize> {
+        re::BUILDER_END
+            .find(&self.buffer[self.pos..])
+            .map(|m| self.pos + m.start())
+    }
+
+    /// Poll interval for where the log is at: slow outside of levels, fast while one
+    /// generates so the mapper is ready by the drop.
+    pub(crate) fn poll_interval(&self, poll: &PollConfig) -> Duration {
+        const IDLE: Duration = Duration::from_secs(2);
+        const GENERATING: Duration = Duration::from_millis(25);
+
+        match self.state {
+            ParserState::LevelSeeds => IDLE,
+            ParserState::LevelSelected
+            | ParserState::LevelGeneration
+            | ParserState::ItemGeneration => GENERATING,
+            _ => poll.interval(),
+        }
+    }
+
+    fn end_run(&mut self, outcome: Outcome, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        self.outcome = Some(outcome);
+        parser_tx.send(ParserMsg::Outcome(outcome))?;
+        Ok(())
+    }
+
+    /// Keeps the current level after the wipe before a checkpoint reload, which isn't the
+    /// end of the run. Reloads without a wipe, i.e. both a reload line and the state leaving
+    /// `ExpeditionFail`, are only sent once.
+    fn reload_checkpoint(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        if self.outcome == Some(Outcome::Failed) {
+            info!("Checkpoint reload, keeping current level");
+            self.outcome = None;
+            parser_tx.send(ParserMsg::Checkpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Clears the current level, a run that didn't end yet counts as aborted.
+    fn reset(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        if self.outcome.take().is_none() {
+            parser_tx.send(ParserMsg::Outcome(Outcome::Aborted))?;
+        }
+        parser_tx.send(ParserMsg::Reset)?;
+        self.started = false;
+        self.dropped = false;
+        self.pos = self.event_pos;
+        self.level_start = None;
+        self.state = ParserState::LevelSeeds;
+        Ok(())
+    }
+
+    /// Adds the complete lines of what was read to `buffer`, the rest waits for its line
+    /// break so multi-line blocks aren't matched with a line cut short.
+    pub(crate) fn push(&mut self, read: &str) {
+        let lines = complete_lines(&mut self.partial, read);
+        self.buffer.push_str(&lines);
+    }
+
+    /// Adds the last line of a log that ended without a line break.
+    pub(crate) fn finish(&mut self) {
+        if !self.partial.is_empty() {
+            let line = std::mem::take(&mut self.partial);
+            self.buffer.push_str(&line);
+            self.buffer.push('\n');
+        }
+    }
+
+    /// Checkpoint of what's been parsed of the log `opened` has the start of.
+    pub(crate) fn checkpoint(&self, opened: &Checkpoint) -> Checkpoint {
+        Checkpoint {
+            offset: opened.offset + self.traced as u64,
+            level: self.level_start.map(|start| opened.offset + start as u64),
+            session: self.session.clone(),
+            ..opened.clone()
+        }
+    }
+
+    /// Sends a [`ParserMsg::BuildProgress`] for every batch the current build started on a
+    /// complete line, up to its `BuildDone`. The game doesn't log how many batches a build
+    /// runs, so the count of the log's last build stands in for it.
+    ///
+    /// Counting stops at the end of the batches the current state is about to read, so their
+    /// messages follow the progress the same way whether the log came whole or line by line.
+    fn build_progress(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        let complete = self.buffer.rfind('\n').map_or(0, |i| i + 1);
+        let reading = match self.state {
+            ParserState::LevelGeneration => self
+                .batch(&re::SETUP_FLOOR_BATCH_START, &re::SETUP_FLOOR_BATCH_END)
+                .map(|(_, end)| end),
+            ParserState::ItemGeneration => self
+                .batch(&re::DISTRIBUTION_BATCH_START, &re::DISTRIBUTION_BATCH_END)
+                .zip(self.batch(
+                    &re::FUNCTION_MARKERS_BATCH_START,
+                    &re::FUNCTION_MARKERS_BATCH_END,
+                ))
+                .map(|((_, distribution), (_, markers))| distribution.max(markers)),
+            _ => None,
+        };
+        let end = [Some(complete), reading, self.build_end()]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(complete);
+        if end <= self.progress_pos {
+            return Ok(());
+        }
+
+        for _ in re::BUILD_BATCH.find_iter(&self.buffer[self.progress_pos..end]) {
+            self.batches += 1;
+            let progress = match self.last_batches {
+                Some(total) => format!("batch {}/{}", self.batches, total.max(self.batches)),
+                None => format!("batch {}", self.batches),
+            };
+            parser_tx.send(ParserMsg::BuildProgress(format!(
+                "Generating... ({progress})"
+            )))?;
+        }
+        self.progress_pos = end;
+
+        Ok(())
+    }
+
+    /// Scans for game state changes, checkpoint reloads and pickups since the last seen event.
+    ///
+    /// A checkpoint reload re-runs parts of generation and goes through `ExpeditionFail`,
+    /// so only a return to the lobby, or the game quitting, counts as the end of the
+    /// expedition. Leaving `ExpeditionFail` for any other state is the reload, but only once
+    /// [`re::GAME_STATE_CHANGE`] is verified: until then the level is kept and the run stays
+    /// failed.
+    fn session_events(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        // Until the level has started only what's logged before the build is done is looked
+        // at, so a whole log doesn't report the run before the level it's in.
+        let end = match self.started {
+            true => None,
+            false => self.build_end(),
+        }
+        .unwrap_or(self.buffer.len());
+
+        loop {
+            let rest = &self.buffer[self.event_pos..end.max(self.event_pos)];
+
+            let checkpoint = re::CHECKPOINT_RELOAD
+                .find(rest)
+                .map(|m| (m.start(), m.end(), SessionEvent::Checkpoint));
+            let state_change = re::GAME_STATE_CHANGE.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::State(
+                        group("GAME_STATE_CHANGE", &cap, "from").zip(group(
+                            "GAME_STATE_CHANGE",
+                            &cap,
+                            "to",
+                        )),
+                        line_time(m.as_str()),
+                    ),
+                )
+            });
+            // Carried items have no seed, they're matched on name alone.
+            let pickup = |name: &str, regex: &Regex, seeded: bool| {
+                regex.captures(rest).map(|cap| {
+                    let m = cap.get(0).unwrap();
+                    let seed = if seeded {
+                        group(name, &cap, "seed")
+                    } else {
+                        Some(0)
+                    };
+                    let pickup = match (group(name, &cap, "item"), group(name, &cap, "zone"), seed)
+                    {
+                        (Some(item), Some(zone), Some(seed)) => Some(Pickup { item, zone, seed }),
+                        _ => None,
+                    };
+                    (
+                        m.start(),
+                        m.end(),
+                        SessionEvent::Pickup(pickup, line_time(m.as_str())),
+                    )
+                })
+            };
+            let item_pickup = pickup("ITEM_PICKUP", &re::ITEM_PICKUP, true);
+            let carry_pickup = pickup("CARRY_ITEM_PICKUP", &re::CARRY_ITEM_PICKUP, false);
+            let small_pickup = pickup("SMALL_PICKUP_INTERACT", &re::SMALL_PICKUP_INTERACT, true);
+
+            let door = re::SECURITY_DOOR_OPENED.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::DoorOpened(
+                        group("SECURITY_DOOR_OPENED", &cap, "alias"),
+                        line_time(m.as_str()),
+                    ),
+                )
+            });
+
+            let entered = re::PLAYER_ZONE_ENTERED.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::ZoneEntered(
+                        group("PLAYER_ZONE_ENTERED", &cap, "alias"),
+                        cap.name("area")
+                            .and_then(|area| area.as_str().chars().next()),
+                        line_time(m.as_str()),
+                    ),
+                )
+            });
+
+            let bulkhead = re::BULKHEAD_SCAN_DONE.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::BulkheadScanned(
+                        group("BULKHEAD_SCAN_DONE", &cap, "alias"),
+                        line_time(m.as_str()),
+                    ),
+                )
+            });
+
+            let checkpoint_activated = re::CHECKPOINT_ACTIVATED.find(rest).map(|m| {
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::CheckpointActivated(line_time(m.as_str())),
+                )
+            });
+
+            let scan = re::SECURITY_SCAN_START.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                let event = LevelEvent::Scan(
+                    cap.name("alias")
+                        .and_then(|_| group("SECURITY_SCAN_START", &cap, "alias")),
+                    cap.name("class").map(|class| class.as_str().to_string()),
+                );
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::Level(event, line_time(m.as_str())),
+                )
+            });
+
+            let error_alarm = re::ERROR_ALARM.captures(rest).map(|cap| {
+                let m = cap.get(0).unwrap();
+                let event = LevelEvent::ErrorAlarm(
+                    cap.name("alias")
+                        .and_then(|_| group("ERROR_ALARM", &cap, "alias")),
+                );
+                (
+                    m.start(),
+                    m.end(),
+                    SessionEvent::Level(event, line_time(m.as_str())),
+                )
+            });
+
+            let quit = re::APPLICATION_QUIT
+                .find(rest)
+                .map(|m| (m.start(), m.end(), SessionEvent::Quit));
+
+            // Earliest event first, so a reset never swallows the pickups logged before it.
+            let Some((_, end, event)) = [
+                checkpoint,
+                state_change,
+                item_pickup,
+                carry_pickup,
+                small_pickup,
+                door,
+                entered,
+                bulkhead,
+                checkpoint_activated,
+                scan,
+                error_alarm,
+                quit,
+            ]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(start, _, _)| *start) else {
+                break;
+            };
+            self.event_pos += end;
+
+            match event {
+                SessionEvent::Checkpoint => self.reload_checkpoint(parser_tx)?,
+                SessionEvent::State(Some((from, to)), time) => {
+                    match to.as_str() {
+                        "InLevel" if !self.dropped => {
+                            self.dropped = true;
+                            parser_tx.send(ParserMsg::ElevatorDrop(time))?;
+                        }
+                        "ExpeditionSuccess" => {
+                            parser_tx.send(ParserMsg::LevelFinish(time))?;
+                            self.end_run(Outcome::Success, parser_tx)?;
+                        }
+                        "ExpeditionFail" => self.end_run(Outcome::Failed, parser_tx)?,
+                        "Lobby" | "NoLobby" => {
+                            self.reset(parser_tx)?;
+                            break;
+                        }
+                        _ if from == "ExpeditionFail" && re::GAME_STATE_CHANGE.is_verified() => {
+                            self.reload_checkpoint(parser_tx)?
+                        }
+                        _ if from == "ExpeditionFail" => {
+                            info!("Left ExpeditionFail for {to}, not taken as a reload while unverified")
+                        }
+                        _ => {}
+                    }
+                }
+                SessionEvent::State(None, _) => {}
+                SessionEvent::Quit => {
+                    self.reset(parser_tx)?;
+                    break;
+                }
+                SessionEvent::Pickup(Some(pickup), time) => {
+                    parser_tx.send(ParserMsg::Collected(pickup, time))?;
+                }
+                SessionEvent::Pickup(None, _) => {}
+                SessionEvent::DoorOpened(Some(alias), time) => {
+                    parser_tx.send(ParserMsg::ZoneDoorOpened(alias, time))?;
+                }
+                SessionEvent::DoorOpened(None, _) => {}
+                SessionEvent::ZoneEntered(Some(alias), area, time) => {
+                    parser_tx.send(ParserMsg::ZoneEntered(alias, area, time))?;
+                }
+                SessionEvent::ZoneEntered(None, ..) => {}
+                SessionEvent::BulkheadScanned(Some(alias), time) => {
+                    parser_tx.send(ParserMsg::BulkheadScanned(alias, time))?;
+                }
+                SessionEvent::BulkheadScanned(None, _) => {}
+                SessionEvent::CheckpointActivated(time) => {
+                    parser_tx.send(ParserMsg::CheckpointActivated(time))?;
+                }
+                SessionEvent::Level(event, time) => {
+                    parser_tx.send(ParserMsg::LevelEvent(event, time))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Steps until the buffer has nothing more to give, i.e. for content that arrives all at
+    /// once rather than every poll.
+    pub(crate) fn drain(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        loop {
+            let before = (self.pos, self.event_pos, self.state);
+            self.step(parser_tx)?;
+            send_diagnostics(parser_tx)?;
+
+            if (self.pos, self.event_pos, self.state) == before {
+                return Ok(());
+            }
+        }
+    }
+
+    fn step(&mut self, parser_tx: &Sender<ParserMsg>) -> anyhow::Result<()> {
+        // Single line patterns are checked once per complete line, batch patterns once per batch.
+        let complete = self.buffer.rfind('\n').map_or(0, |i| i + 1);
+        if complete > self.traced {
+            let lines = &self.buffer[self.traced..complete];
+            if !self.header_done {
+                let header = match re::BUILDER_LEVEL_SEEDS.find(lines) {
+                    Some(m) => {
+                        self.header_done = true;
+                        &lines[..m.start()]
+                    }
+                    None => lines,
+                };
+                if read_header(header, &mut self.session) {
+                    info!("Log of {}", self.session);
+                    parser_tx.send(ParserMsg::Session(self.session.clone()))?;
+                }
+            }
+            trace_misses(
+                "BUILDER_LEVEL_SEEDS",
+                "buildSeed",
+                &re::BUILDER_LEVEL_SEEDS,
+                lines,
+            );
+            trace_misses(
+                "DROP_SERVER_MANAGER_NEW_SESSION",
+                "'new session'",
+                &re::DROP_SERVER_MANAGER_NEW_SESSION,
+                lines,
+            );
+            trace_misses(
+                "GAME_STATE_CHANGE",
+                "GAMESTATEMANAGER",
+                &re::GAME_STATE_CHANGE,
+                lines,
+            );
+            trace_misses("ITEM_PICKUP", "OnPickedUp", &re::ITEM_PICKUP, lines);
+            trace_misses(
+                "SECURITY_DOOR_OPENED",
+                "OnDoorIsOpened",
+                &re::SECURITY_DOOR_OPENED,
+                lines,
+            );
+            trace_misses(
+                "PLAYER_ZONE_ENTERED",
+                "OnCourseNodeChanged",
+                &re::PLAYER_ZONE_ENTERED,
+                lines,
+            );
+            handler::run(&mut self.handlers, lines, parser_tx)?;
+            self.traced = complete;
+        }
+
+        if !matches!(self.state, ParserState::LevelSeeds) {
+            self.session_events(parser_tx)?;
+        }
+
+        let generating = matches!(
+            self.state,
+            ParserState::LevelGeneration
+                | ParserState::ItemGeneration
+                | ParserState::ElevatorDropFinish
+        );
+        if generating && !self.started {
+            self.build_progress(parser_tx)?;
+        }
+
+        match self.state {
+            ParserState::LevelSeeds => {
+                if let Some(ref cap) = re::BUILDER_LEVEL_SEEDS
+                    .captures_iter(&self.buffer[self.pos..])
+                    .last()
+                {
+                    let name = "BUILDER_LEVEL_SEEDS";
+                    if let (Some(build), Some(host), Some(session)) = (
+                        group(name, cap, "build"),
+                        group(name, cap, "hostId"),
+                        group(name, cap, "session"),
+                    ) {
+                        parser_tx.send(ParserMsg::LevelSeeds(
+                            Seeds {
+                                build,
+                                host,
+                                session,
+                            },
+                            line_time(&cap[0]),
+                        ))?;
+                    }
+
+                    let line = self.pos + cap.get(0).unwrap().start();
+                    self.level_start = Some(self.buffer[..line].rfind('\n').map_or(0, |i| i + 1));
+                    self.pos += cap.get(0).unwrap().end();
+                    self.event_pos = self.pos;
+                    self.build_start = self.pos;
+                    self.progress_pos = self.pos;
+                    self.batches = 0;
+                    self.state = ParserState::LevelSelected;
+                }
+            }
+            ParserState::LevelSelected => {
+                if let Some(cap) =
+                    re::DROP_SERVER_MANAGER_NEW_SESSION.captures(&self.buffer[self.pos..])
+                {
+                    let name = "DROP_SERVER_MANAGER_NEW_SESSION";
+                    if let (Some(rundown_idx), Some(rundown_exp)) = (
+                        group::<u16>(name, &cap, "rundown_idx"),
+                        group::<String>(name, &cap, "rundown_exp"),
+                    ) {
+                        let rundown = Rundown::from_repr(rundown_idx).unwrap_or(Rundown::Modded);
+                        let modded = match rundown {
+                            Rundown::Modded => profile::active().rundown(rundown_idx).cloned(),
+                            _ => None,
+                        };
+                        let level = Level {
+                            rundown,
+                            modded,
+                            exp_name: rundown_exp,
+                            ..Default::default()
+                        };
+
+                        parser_tx.send(ParserMsg::LevelInit(Box::new(level)))?;
+                    }
+
+                    self.pos += cap.get(0).unwrap().end();
+                    self.zones = GeneratedZones::default();
+                    self.state = ParserState::LevelGeneration;
+                }
+            }
+            ParserState::LevelGeneration => {
+                // TODO: add check if level already exists as file and load zones from file
+
+                let setup_floor =
+                    self.batch(&re::SETUP_FLOOR_BATCH_START, &re::SETUP_FLOOR_BATCH_END);
+                if setup_floor.is_none() && self.build_end().is_some() {
+                    diagnostics::report(
+                        Severity::Warning,
+                        "Level was built without a SetupFloor batch, no zones to map",
+                    );
+                    self.state = ParserState::ItemGeneration;
+                } else if let Some((start, end)) = setup_floor {
+                    let segment = &self.buffer[start..end];
+                    trace_misses("ZONE_CREATED", "Zone Created", &re::ZONE_CREATED, segment);
+                    trace_misses(
+                        "ZONE_BUILD_FROM",
+                        "CreateZone",
+                        &re::ZONE_BUILD_FROM,
+                        segment,
+                    );
+
+                    parser_tx.send(ParserMsg::GeneratedZone(
+                        TimerEntry::Start,
+                        segment.lines().find_map(line_time),
+                    ))?;
+                    for cap in re::ZONE_CREATED.captures_iter(segment) {
+                        let name = "ZONE_CREATED";
+                        let (Some(alias), Some(local), Some(dimension), Some(layer)) = (
+                            group(name, &cap, "alias"),
+                            group(name, &cap, "local"),
+                            group(name, &cap, "dim"),
+                            group(name, &cap, "layer"),
+                        ) else {
+                            continue;
+                        };
+
+                        let zone = Zone {
+                            alias,
+                            local,
+                            dimension,
+                            layer,
+                            area: None,
+                        };
+                        self.zones.0.push(zone.key());
+                        parser_tx.send(ParserMsg::GeneratedZone(
+                            TimerEntry::Zone(zone),
+                            line_time(&cap[0]),
+                        ))?;
+                    }
+
+                    for cap in re::ZONE_BUILD_FROM.captures_iter(segment) {
+                        let name = "ZONE_BUILD_FROM";
+                        let (Some(alias), Some(from)) =
+                            (group::<u32>(name, &cap, "alias"), group(name, &cap, "from"))
+                        else {
+                            continue;
+                        };
+
+                        if alias != from {
+                            parser_tx.send(ParserMsg::Connection(from, alias))?;
+                        }
+                    }
+
+                    parser_tx.send(ParserMsg::GeneratedZone(
+                        TimerEntry::End,
+                        segment.lines().rev().find_map(line_time),
+                    ))?;
+
+                    self.pos = end;
+                    self.state = ParserState::ItemGeneration;
+                }
+            }
+            ParserState::ItemGeneration => {
+                // TODO: Biggest state yet
+                // General work that we need to do here:
+                // - Parse for gatherable items (any _other_ gatherable that we can encounter) and record their zones and count
+                // - Parse the information for mappable items like keys - 1st Variant
+                // - Parse the information for mappable items that have item seed - 2nd Variant
+                // - Parse the information for generators if we have generator objective - 3rd Variant
+
+                let distribution =
+                    self.batch(&re::DISTRIBUTION_BATCH_START, &re::DISTRIBUTION_BATCH_END);
+                let markers = self.batch(
+                    &re::FUNCTION_MARKERS_BATCH_START,
+                    &re::FUNCTION_MARKERS_BATCH_END,
+                );
+
+                // Both batches are needed to merge seeds, unless the build is done without one.
+                if (distribution.is_some() && markers.is_some()) || self.build_end().is_some() {
+                    if distribution.is_none() || markers.is_none() {
+                        diagnostics::report(
+                            Severity::Warning,
+                            "Level was built without a Distribution or FunctionMarkers batch",
+                        );
+                    }
+                    let (distribution_start, distribution_end) =
+                        distribution.unwrap_or((self.pos, self.pos));
+                    let (marker_start, marker_end) = markers.unwrap_or((self.pos, self.pos));
+
+                    let distribution_segment = &self.buffer[distribution_start..distribution_end];
+                    let marker_segment = &self.buffer[marker_start..marker_end];
+                    let distribution_time = distribution_segment.lines().find_map(line_time);
+
+                    trace_misses(
+                        "CREATE_KEY_ITEM_DISTRIBUTION",
+                        "CreateKeyItemDistribution",
+                        &re::CREATE_KEY_ITEM_DISTRIBUTION,
+                        distribution_segment,
+                    );
+                    trace_misses(
+                        "DISTRIBUTE_WARDEN_OBJECTIVE",
+                        "in zone ZONE",
+                        &re::DISTRIBUTE_WARDEN_OBJECTIVE,
+                        distribution_segment,
+                    );
+                    trace_misses(
+                        "GENERIC_SMALL_PICKUP_ITEM",
+                        "Spawning Personnel",
+                        &re::GENERIC_SMALL_PICKUP_ITEM,
+                        marker_segment,
+                    );
+                    trace_misses(
+                        "WARDEN_OBJECTIVE_MANAGER",
+                        "OnSyncStatusChanged",
+                        &re::WARDEN_OBJECTIVE_MANAGER,
+                        marker_segment,
+                    );
+                    trace_misses(
+                        "CARRY_ITEM_SPAWN",
+                        "CarryItemPickup",
+                        &re::CARRY_ITEM_SPAWN,
+                        marker_segment,
+                    );
+
+                    trace_misses(
+                        "DISTRIBUTE_HSU",
+                        "LG_Distribute_HSU",
+                        &re::DISTRIBUTE_HSU,
+                        distribution_segment,
+                    );
+                    trace_misses(
+                        "ITEM_SERIAL",
+                        "SerialGenerator",
+                        &re::ITEM_SERIAL,
+                        distribution_segment,
+                    );
+
+                    // Keys
+                    for cap in re::CREATE_KEY_ITEM_DISTRIBUTION.captures_iter(distribution_segment)
+                    {
+                        let name = "CREATE_KEY_ITEM_DISTRIBUTION";
+                        let (Some(key), Some(dim), Some(alias), Some(ri)) = (
+                            group(name, &cap, "key"),
+                            group(name, &cap, "dim"),
+                            group(name, &cap, "alias"),
+                            group(name, &cap, "ri"),
+                        ) else {
+                            continue;
+                        };
+
+                        let zone = self.zones.key(alias, &cap[0]);
+                        let item = GatherItem::Key(key, dim, alias, ri);
+                        let area = cap
+                            .name("area")
+                            .and_then(|area| area.as_str().chars().next());
+                        let time = line_time(&cap[0]);
+                        parser_tx.send(match area {
+                            Some(area) => ParserMsg::AreaGatherable(zone.into(), area, item, time),
+                            None => ParserMsg::Gatherable(item, time),
+                        })?;
+                    }
+
+                    for cap in re::DISTRIBUTE_HSU.captures_iter(distribution_segment) {
+                        let name = "DISTRIBUTE_HSU";
+                        let (Some(alias), Some(id), Some(area)) = (
+                            group(name, &cap, "alias"),
+                            group(name, &cap, "id"),
+                            group(name, &cap, "area"),
+                        ) else {
+                            continue;
+                        };
+
+                        parser_tx.send(ParserMsg::AreaGatherable(
+                            self.zones.key(alias, &cap[0]).into(),
+                            area,
+                            GatherItem::HSU(id, area),
+                            line_time(&cap[0]),
+                        ))?;
+                    }
+
+                    for cap in re::ITEM_SERIAL.captures_iter(distribution_segment) {
+                        let name = "ITEM_SERIAL";
+                        let (Some(alias), Some(serial)) =
+                            (group(name, &cap, "alias"), group(name, &cap, "serial"))
+                        else {
+                            continue;
+                        };
+
+                        parser_tx.send(ParserMsg::Serial(alias, serial, line_time(&cap[0])))?;
+                    }
+
+                    for (objective, time) in objectives(distribution_segment) {
+                        parser_tx.send(ParserMsg::Objective(objective, time))?;
+                    }
+
+                    let marker_time = marker_segment.lines().find_map(line_time);
+                    let mut named = named_objectives(distribution_segment, &self.zones);
+                    named.extend(uplink_terminals(distribution_segment, &self.zones));
+                    let carried = carry_items(marker_segment, &self.zones, &mut named);
+                    for (zone, item) in named {
+                        parser_tx.send(ParserMsg::ZoneGatherable(
+                            zone.into(),
+                            item,
+                            distribution_time,
+                        ))?;
+                    }
+                    for (zone, area, item) in carried {
+                        parser_tx.send(match area {
+                            Some(area) => {
+                                ParserMsg::AreaGatherable(zone.into(), area, item, marker_time)
+                            }
+                            None => ParserMsg::ZoneGatherable(zone.into(), item, marker_time),
+                        })?;
+                    }
+
+                    for (zone, item) in
+                        merge_seeded(distribution_segment, marker_segment, &self.zones)
+                    {
+                        parser_tx.send(match zone {
+                            Some(zone) => {
+                                ParserMsg::ZoneGatherable(zone.into(), item, distribution_time)
+                            }
+                            None => ParserMsg::Gatherable(item, distribution_time),
+                        })?;
+                    }
+
+                    for (zone, item) in generators(marker_segment, &self.zones) {
+                        parser_tx.send(match zone {
+                            Some(zone) => ParserMsg::ZoneGatherable(zone.into(), item, marker_time),
+                            None => ParserMsg::Gatherable(item, marker_time),
+                        })?;
+                    }
+
+                    self.pos = distribution_end.max(marker_end);
+                    self.state = ParserState::ElevatorDropFinish;
+                }
+            }
+            ParserState::ElevatorDropFinish => {
+                if !self.started {
+                    if let Some(m) = re::BUILDER_END.find(&self.buffer[self.pos..]) {
+                        self.started = true;
+                        self.last_batches = Some(self.batches);
+                        let build = &self.buffer[self.build_start..self.pos + m.start()];
+                        for (warning, time) in generation_anomalies(build) {
+                            parser_tx.send(ParserMsg::Warning(warning, time))?;
+                        }
+                        parser_tx.send(ParserMsg::LevelStart(line_time(m.as_str())))?;
+                        self.pos += m.end();
+                    }
+                }
+            }
+            ParserState::LevelFinish => {}
+            ParserState::NotInLevel => {}
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Parser {
+    fn drop(&mut self) {
+        self.stop_threads();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::{data::ItemIdentifier, testing::TempDir};
+
+    const EXPEDITION: &str = concat!(
+        "20:03:18.001 - Builder.Build buildSeed: 12 hostIDSeed: 34 sessionSeed: 56\n",
+        "20:03:18.002 - ServerManager: 'new session' rundown: Local_32, expedition: A1\n",
+        "Next Batch: SetupFloor\n",
+        "20:03:19.031 - LG_Floor.CreateZone, Alias: 49 with BuildFromZoneAlias49 zoneAliasStart: 49 aliasOffset: Zone_0\n",
+        "20:03:19.033 - <b>Zone Created</b> (New Game Object) in Reality MainLayer with\n",
+        "20:03:19.035 - LG_Floor.CreateZone, Alias: 50 with BuildFromZoneAlias49 zoneAliasStart: 49 aliasOffset: Zone_1\n",
+        "20:03:19.036 - <b>Zone Created</b> (New Game Object) in Reality MainLayer with\n",
+        "20:03:19.040 - Last Batch: SetupFloor\n",
+        "20:03:19.050 - Next Batch: Distribution\n",
+        "20:03:19.060 - Last Batch: Distribution\n",
+        "20:03:19.070 - Next Batch: FunctionMarkers\n",
+        "20:03:19.080 - Last Batch: FunctionMarkers\n",
+        "20:03:29.500 - BUILDER : BuildDone\n",
+        "20:03:30.000 - GAMESTATEMANAGER CHANGE STATE FROM : ReadyToStartLevel TO: InLevel\n",
+    );
+
+    /// Key of a zone [`EXPEDITION`] generates.
+    fn main_zone(alias: u32) -> ZoneKey {
+        ZoneKey {
+            alias,
+            dimension: Dimension::Reality,
+            layer: "MainLayer".into(),
+        }
+    }
+
+    #[test]
+    fn zone_connections() {
+        let msgs = parse_str(EXPEDITION);
+
+        assert!(msgs.contains(&ParserMsg::Connection(49, 50)));
+        assert_eq!(
+            msgs.iter()
+                .filter(|msg| matches!(msg, ParserMsg::Connection(..)))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn lines_fed_one_by_one() {
+        let mut parser = LogParser::new();
+        let msgs: Vec<_> = EXPEDITION
+            .lines()
+            .flat_map(|line| parser.push_line(line))
+            .collect();
+
+        assert_eq!(msgs, parse_str(EXPEDITION));
+    }
+
+    #[test]
+    fn header_is_read() {
+        re::use_sample(&[&re::GAME_BUILD, &re::PLAYER_NAME, &re::PLATFORM]);
+        let log = concat!(
+            "20:03:00.001 - GTFO build: 31000 revision 31002\n",
+            "20:03:00.002 - SNet_Core_STEAM: Setup\n",
+            "20:03:00.003 - SNet: Player name: Rusty Maid \n",
+            "20:03:18.001 - Builder.Build buildSeed: 12 hostIDSeed: 34 sessionSeed: 56\n",
+            "20:03:18.002 - ServerManager: 'new session' rundown: Local_32, expedition: A1\n",
+            "20:03:18.003 - Platform: EGS\n",
+        );
+
+        let msgs = parse_str(log);
+        let info = SessionInfo {
+            build: Some(31000),
+            revision: Some(31002),
+            player: Some("Rusty Maid".into()),
+            platform: Some("STEAM".into()),
+        };
+        assert_eq!(
+            msgs.iter()
+                .rfind(|msg| matches!(msg, ParserMsg::Session(..))),
+            Some(&ParserMsg::Session(info))
+        );
+        assert!(msgs
+            .iter()
+            .any(|msg| matches!(msg, ParserMsg::LevelInit(..))));
+
+        // Without the header there's no session to send.
+        assert!(!parse_str(&log[log.find("20:03:18.001").unwrap()..])
+            .iter()
+            .any(|msg| matches!(msg, ParserMsg::Session(..))));
+    }
+
+    #[test]
+    fn build_finishes_without_markers_batch() {
+        let log = EXPEDITION
+            .replace("20:03:19.070 - Next Batch: FunctionMarkers\n", "")
+            .replace("20:03:19.080 - Last Batch: FunctionMarkers\n", "");
+        // A later batch isn't part of this build.
+        let log = format!(
+            "{log}{}{}",
+            "20:40:00.070 - Next Batch: FunctionMarkers\n",
+            "20:40:00.080 - Last Batch: FunctionMarkers\n",
+        );
+        let msgs = parse_str(&log);
+
+        assert!(msgs.contains(&ParserMsg::Connection(49, 50)));
+        assert_eq!(
+            msgs.iter()
+                .filter(|msg| matches!(msg, ParserMsg::LevelStart(_)))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn generation_anomalies_are_warned_about() {
+        re::use_sample(&[&re::GENERATION_ANOMALY]);
+        let log = EXPEDITION.replace(
+            "20:03:19.060 - Last Batch: Distribution\n",
+            concat!(
+                "20:03:19.055 - LG_Factory: Error in Distribution, LinkedToZoneData is NULL \n",
+                "20:03:19.060 - Last Batch: Distribution\n",
+                "20:03:19.061 - Next Batch: Distribution\n",
+                "20:03:19.065 - Last Batch: Distribution\n",
+            ),
+        );
+        let warnings = |log: &str| {
+            parse_str(log)
+                .into_iter()
+                .filter(|msg| matches!(msg, ParserMsg::Warning(..)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            warnings(&log),
+            vec![
+                ParserMsg::Warning(
+                    "LG_Factory: Error in Distribution, LinkedToZoneData is NULL".into(),
+                    "20:03:19.055".parse().ok()
+                ),
+                ParserMsg::Warning(
+                    "Distribution batch ran 2 times, the level may have rerolled".into(),
+                    "20:03:19.061".parse().ok()
+                ),
+            ]
+        );
+        assert_eq!(warnings(EXPEDITION), vec![]);
+    }
+
+    #[test]
+    fn seeds_are_merged_with_distributed_items() {
+        let distribution = concat!(
+            "20:03:19.055 - creating dist in zone ZONE50 Index: 0\n",
+            "20:03:19.056 - objective item itemID: 129\n",
+        );
+        let markers = concat!(
+            "20:03:19.071 - Spawning Personnel ID Key: Locker12\n",
+            "20:03:19.072 - item seed: 1234\n",
+            "20:03:19.073 - PersonnelPickup_Core.Setup\n",
+            "20:03:19.074 - Spawning Personnel ID Key: Box3\n",
+            "20:03:19.075 - item seed: 99\n",
+            "20:03:19.076 - PersonnelPickup_Core.Setup\n",
+        );
+
+        assert_eq!(
+            merge_seeded(distribution, markers, &GeneratedZones::default()),
+            vec![
+                (Some(50.into()), GatherItem::PD("Locker12".into(), 1234)),
+                (None, GatherItem::Seeded("Box3".into(), 99)),
+            ]
+        );
+    }
+
+    #[test]
+    fn gather_objectives_are_parsed() {
+        let distribution = concat!(
+            "20:03:19.050 - LG_Distribute_WardenObjective, itemsToSpawn: [Count: 6] 149\n",
+            "20:03:19.051 - LG_Distribute_WardenObjective, itemsToSpawn: [Count: 2] 250\n",
+        );
+
+        assert_eq!(
+            objectives(distribution),
+            vec![(
+                Objective {
+                    item: ItemIdentifier::GLP1,
+                    count: 6,
+                },
+                Some(Time::constant(20, 3, 19, 50_000_000)),
+            )]
+        );
+    }
+
+    #[test]
+    fn named_objectives_are_distributed() {
+        let distribution = concat!(
+            "20:03:19.055 - creating dist in zone ZONE50 Index: 0\n",
+            "20:03:19.056 - objective item itemID: 176\n",
+            "20:03:19.057 - creating dist in zone ZONE50 Index: 1\n",
+            "20:03:19.058 - objective item itemID: 129\n",
+            "20:03:19.059 - creating dist in zone ZONE49 Index: 0\n",
+            "20:03:19.060 - objective item itemID: 133\n",
+            "20:03:19.061 - creating dist in zone ZONE52 Index: 3\n",
+            "20:03:19.062 - objective item itemID: 131\n",
+        );
+
+        assert_eq!(
+            named_objectives(distribution, &GeneratedZones::default()),
+            vec![
+                (50.into(), GatherItem::Cargo("Cargo ZONE_50 #0".into())),
+                (
+                    49.into(),
+                    GatherItem::FogTurbine("Fog Turbine ZONE_49 #0".into())
+                ),
+                (52.into(), GatherItem::Cell(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dataspheres_and_uplinks_are_distributed() {
+        re::use_sample(&[&re::UPLINK_TERMINAL]);
+        let distribution = concat!(
+            "20:03:19.055 - creating dist in zone ZONE51 Index: 1\n",
+            "20:03:19.056 - objective item itemID: 151\n",
+            "20:03:19.057 - WardenObjective TerminalUplink placed in ZONE_52 on TERMINAL_343\n",
+            "20:03:19.058 - WardenObjective TerminalUplink placed in ZONE_53\n",
+            "20:03:19.059 - WardenObjective TerminalUplink placed in ZONE_52 on TERMINAL_343\n",
+        );
+
+        assert_eq!(
+            named_objectives(distribution, &GeneratedZones::default()),
+            vec![(51.into(), GatherItem::Datasphere(1))]
+        );
+        assert_eq!(
+            uplink_terminals(distribution, &GeneratedZones::default()),
+            vec![
+                (52.into(), GatherItem::Uplink("TERMINAL_343".into())),
+                (53.into(), GatherItem::Uplink("ZONE_53 #0".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn serials_are_read_once_overridden() {
+        let log = EXPEDITION.replace(
+            "20:03:19.060 - Last Batch: Distribution\n",
+            concat!(
+                "20:03:19.058 - LG_SerialGenerator.GetUniqueSerialNo zone ZONE50 serial: TERMINAL_123\n",
+                "20:03:19.060 - Last Batch: Distribution\n",
+            ),
+        );
+        let serials = |log: &str| {
+            parse_str(log)
+                .into_iter()
+                .filter(|msg| matches!(msg, ParserMsg::Serial(..)))
+                .collect::<Vec<_>>()
+        };
+        assert!(serials(&log).is_empty());
+
+        re::use_sample(&[&re::ITEM_SERIAL]);
+        assert_eq!(
+            serials(&log),
+            [ParserMsg::Serial(
+                50,
+                "TERMINAL_123".into(),
+                Some(Time::constant(20, 3, 19, 58_000_000))
+            )]
+        );
+    }
+
+    #[test]
+    fn dimensions_sharing_an_alias_keep_their_items() {
+        let log = EXPEDITION
+            .replace(
+                "20:03:19.040 - Last Batch: SetupFloor\n",
+                concat!(
+                    "20:03:19.037 - LG_Floor.CreateZone, Alias: 50 with BuildFromZoneAlias50 zoneAliasStart: 50 aliasOffset: Zone_0\n",
+                    "20:03:19.038 - <b>Zone Created</b> (New Game Object) in Dimension_1 MainLayer with\n",
+                    "20:03:19.040 - Last Batch: SetupFloor\n",
+                ),
+            )
+            .replace(
+                "20:03:19.060 - Last Batch: Distribution\n",
+                concat!(
+                    "20:03:19.055 - creating dist in zone ZONE50 Index: 0 Dimension_1\n",
+                    "20:03:19.056 - objective item itemID: 131\n",
+                    "20:03:19.057 - creating dist in zone ZONE50 Index: 1\n",
+                    "20:03:19.058 - objective item itemID: 131\n",
+                    "20:03:19.060 - Last Batch: Distribution\n",
+                ),
+            );
+        let msgs = parse_str(&log);
+        let time = line_time("20:03:19.050 - ");
+
+        assert!(msgs.contains(&ParserMsg::ZoneGatherable(
+            Box::new(ZoneKey {
+                dimension: Dimension::Numbered(1),
+                ..main_zone(50)
+            }),
+            GatherItem::Cell(0),
+            time
+        )));
+        assert!(msgs.contains(&ParserMsg::ZoneGatherable(
+            main_zone(50).into(),
+            GatherItem::Cell(1),
+            time
+        )));
+    }
+
+    #[test]
+    fn carry_spawns_take_over_distributed_items() {
+        re::use_sample(&[&re::CARRY_ITEM_SPAWN]);
+        let distribution = concat!(
+            "20:03:19.055 - creating dist in zone ZONE50 Index: 2\n",
+            "20:03:19.056 - objective item itemID: 176\n",
+            "20:03:19.057 - creating dist in zone ZONE51 Index: 0\n",
+            "20:03:19.058 - objective item itemID: 148\n",
+        );
+        let markers = concat!(
+            "20:03:19.070 - CarryItemPickup_Core.Setup Item: CARGO_CRATE Zone: 50 Area: B\n",
+            "20:03:19.071 - CarryItemPickup_Core.Setup Item: MWP Zone: 52\n",
+            "20:03:19.072 - CarryItemPickup_Core.Setup Item: Generator Zone: 52\n",
+        );
+
+        let zones = GeneratedZones::default();
+        let mut named = named_objectives(distribution, &zones);
+        assert_eq!(
+            carry_items(markers, &zones, &mut named),
+            vec![
+                (
+                    50.into(),
+                    Some('B'),
+                    GatherItem::Cargo("Cargo ZONE_50 #2".into())
+                ),
+                (52.into(), None, GatherItem::MWP("MWP ZONE_52 #0".into())),
+            ]
+        );
+        assert_eq!(
+            named,
+            vec![(51.into(), GatherItem::Cryo("Cryo ZONE_51 #0".into()))]
+        );
+    }
+
+    #[test]
+    fn neonate_in_r2e1() {
+        let log = EXPEDITION
+            .replace("Local_32, expedition: A1", "Local_33, expedition: E1")
+            .replace(
+                "20:03:19.060 - Last Batch: Distribution\n",
+                concat!(
+                    "20:03:19.055 - creating dist in zone ZONE50 Index: 0\n",
+                    "20:03:19.056 - objective item itemID: 137\n",
+                    "20:03:19.060 - Last Batch: Distribution\n",
+                ),
+            );
+        let msgs = parse_str(&log);
+
+        assert!(msgs.contains(&ParserMsg::LevelInit(Box::new(Level {
+            rundown: Rundown::R2,
+            exp_name: "E1".into(),
+            ..Default::default()
+        }))));
+        assert!(msgs.contains(&ParserMsg::ZoneGatherable(
+            main_zone(50).into(),
+            GatherItem::Neonate("Neonate ZONE_50 #0".into()),
+            line_time("20:03:19.050 - ")
+        )));
+    }
+
+    #[test]
+    fn seeded_items_keep_their_zone() {
+        let log = EXPEDITION
+            .replace(
+                "20:03:19.060 - Last Batch: Distribution\n",
+                concat!(
+                    "20:03:19.055 - creating dist in zone ZONE50 Index: 0\n",
+                    "20:03:19.056 - objective item itemID: 149\n",
+                    "20:03:19.060 - Last Batch: Distribution\n",
+                ),
+            )
+            .replace(
+                "20:03:19.080 - Last Batch: FunctionMarkers\n",
+                concat!(
+                    "20:03:19.071 - Spawning Personnel GLP Key: Locker4\n",
+                    "20:03:19.072 - item seed: 777\n",
+                    "20:03:19.073 - PersonnelPickup_Core.Setup\n",
+                    "20:03:19.080 - Last Batch: FunctionMarkers\n",
+                ),
+            );
+
+        assert!(parse_str(&log).contains(&ParserMsg::ZoneGatherable(
+            main_zone(50).into(),
+            GatherItem::GLP1("Locker4".into(), 777),
+            line_time("20:03:19.050 - ")
+        )));
+    }
+
+    #[test]
+    fn unknown_item_is_kept() {
+        let log = EXPEDITION.replace(
+            "20:03:19.060 - Last Batch: Distribution\n",
+            concat!(
+                "20:03:19.055 - creating dist in zone ZONE50 Index: 0\n",
+                "20:03:19.056 - objective item itemID: 250\n",
+                "20:03:19.060 - Last Batch: Distribution\n",
+            ),
+        );
+        let msgs = parse_str(&log);
+
+        assert!(msgs.iter().any(|msg| matches!(
+            msg,
+            ParserMsg::ZoneGatherable(zone, GatherItem::Unknown(250, name), _)
+                if **zone == main_zone(50) && name == "Item 250 ZONE_50 #0"
+        )));
+        assert!(msgs.contains(&ParserMsg::Connection(49, 50)));
+        assert!(msgs.iter().any(|msg| matches!(
+            msg,
+            ParserMsg::Diagnostic(Diagnostic { severity: Severity::Warning, message })
+                if message.starts_with("Unknown item id 250, kept as Item 250")
+        )));
+    }
+
+    #[test]
+    fn pickups_are_collected() {
+        re::use_sample(&[&re::ITEM_PICKUP]);
+        let log = format!(
+            "{EXPEDITION}{}",
+            "20:05:00.000 - PlayerInventory.OnPickedUp Item: KEY_RED_1 Zone: 50 Seed: 0\n",
+        );
+        let msgs = parse_str(&log);
+
+        assert!(msgs.contains(&ParserMsg::Collected(
+            Pickup {
+                item: "KEY_RED_1".into(),
+                zone: 50,
+                seed: 0,
+            },
+            Some(Time::constant(20, 5, 0, 0))
+        )));
+    }
+
+    #[test]
+    fn carried_and_small_pickups_are_collected() {
+        re::use_sample(&[&re::CARRY_ITEM_PICKUP, &re::SMALL_PICKUP_INTERACT]);
+        let log = format!(
+            "{EXPEDITION}{}{}",
+            "20:05:00.000 - CarryItemPickup_Core.OnPickedUp Item: CELL Zone: 50\n",
+            "20:06:00.000 - GenericSmallPickupItem_Core.OnInteract Item: ID Zone: 51 Seed: 99\n",
+        );
+        let collected: Vec<_> = parse_str(&log)
+            .into_iter()
+            .filter_map(|msg| match msg {
+                ParserMsg::Collected(pickup, _) => Some(pickup),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            collected,
+            vec![
+                Pickup {
+                    item: "CELL".into(),
+                    zone: 50,
+                    seed: 0,
+                },
+                Pickup {
+                    item: "ID".into(),
+                    zone: 51,
+                    seed: 99,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn adaptive_poll_follows_the_level() {
+        let poll = PollConfig {
+            interval_ms: 100,
+            adaptive: true,
+        };
+        let mut manager = ParserManager::default();
+        assert_eq!(manager.poll_interval(&poll), Duration::from_secs(2));
+
+        manager.state = ParserState::LevelGeneration;
+        assert_eq!(manager.poll_interval(&poll), Duration::from_millis(25));
+
+        manager.state = ParserState::ElevatorDropFinish;
+        assert_eq!(manager.poll_interval(&poll), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn checkpoint_reload_does_not_reset() {
+        let log = format!(
+            "{EXPEDITION}{}{}",
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : InLevel TO: ExpeditionFail\n",
+            "20:10:09.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionFail TO: InLevel\n",
+        );
+
+        // Guessed states keep the level, but don't take the wipe back.
+        let msgs = parse_str(&log);
+        assert!(!msgs.contains(&ParserMsg::Checkpoint));
+        assert!(!msgs.contains(&ParserMsg::Reset));
+        assert_eq!(msgs.last(), Some(&ParserMsg::Outcome(Outcome::Failed)));
+
+        re::use_sample(&[&re::GAME_STATE_CHANGE]);
+        let msgs = parse_str(&log);
+        assert_eq!(
+            msgs.iter()
+                .filter(|msg| **msg == ParserMsg::Checkpoint)
+                .count(),
+            1
+        );
+        assert!(!msgs.contains(&ParserMsg::Reset));
+
+        // The reload line, when it's overridden in, doesn't send it again.
+        re::use_sample(&[&re::CHECKPOINT_RELOAD]);
+        let with_line = log.replace(
+            "20:10:09.000",
+            "20:10:05.000 - CheckpointManager.ReloadCheckpoint\n20:10:09.000",
+        );
+        assert_eq!(parse_str(&with_line), msgs);
+    }
+
+    #[test]
+    fn scans_and_alarms_are_events() {
+        re::use_sample(&[&re::SECURITY_SCAN_START, &re::ERROR_ALARM]);
+        let log = format!(
+            "{EXPEDITION}{}{}{}",
+            "20:04:00.000 - ChainedPuzzleInstance.AttemptInteract on LG_SecurityDoor ZONE_50, Class III Alarm\n",
+            "20:05:00.000 - ChainedPuzzleInstance.AttemptInteract Team scan\n",
+            "20:06:00.000 - WardenObjectiveManager ErrorAlarm triggered in ZONE_51\n",
+        );
+        let events: Vec<_> = parse_str(&log)
+            .into_iter()
+            .filter_map(|msg| match msg {
+                ParserMsg::LevelEvent(event, time) => Some((event, time.unwrap())),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            events,
+            [
+                (
+                    LevelEvent::Scan(Some(50), Some("Class III Alarm".into())),
+                    Time::constant(20, 4, 0, 0)
+                ),
+                (LevelEvent::Scan(None, None), Time::constant(20, 5, 0, 0)),
+                (
+                    LevelEvent::ErrorAlarm(Some(51)),
+                    Time::constant(20, 6, 0, 0)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn lobby_resets() {
+        let log = format!(
+            "{EXPEDITION}{}",
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionSuccess TO: Lobby\n",
+        );
+        let msgs = parse_str(&log);
+
+        assert_eq!(msgs.last(), Some(&ParserMsg::Reset));
+        assert!(msgs.contains(&ParserMsg::Outcome(Outcome::Aborted)));
+        assert_eq!(
+            msgs.iter()
+                .filter(|msg| matches!(msg, ParserMsg::LevelSeeds(..)))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn state_without_both_states_is_skipped() {
+        // An override that lets either group go missing still passes the group check.
+        re::override_for_test(
+            &re::GAME_STATE_CHANGE,
+            r"(?m)^.*STATE\sFROM\s(?<from>\w+)$|^.*STATE\sTO\s(?<to>\w+)$",
+        );
+        let log = format!("{EXPEDITION}{}", "20:10:00.000 - STATE TO Lobby\n");
+        let msgs = parse_str(&log);
+
+        assert!(!msgs.contains(&ParserMsg::Reset));
+        assert!(msgs.iter().any(|msg| matches!(
+            msg,
+            ParserMsg::Diagnostic(Diagnostic { severity: Severity::Warning, message })
+                if message.starts_with("GAME_STATE_CHANGE matched without `from`")
+        )));
+    }
+
+    #[test]
+    fn build_progress_counts_batches() {
+        let lobby =
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionSuccess TO: Lobby\n";
+        // Fed as it's written, a whole log would skip to its last level.
+        let mut parser = LogParser::new();
+        let mut msgs = parser.push(&format!("{EXPEDITION}{lobby}"));
+        msgs.extend(parser.push(EXPEDITION));
+        let progress: Vec<_> = msgs
+            .into_iter()
+            .filter_map(|msg| match msg {
+                ParserMsg::BuildProgress(progress) => Some(progress),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            progress,
+            [
+                "Generating... (batch 1)",
+                "Generating... (batch 2)",
+                "Generating... (batch 3)",
+                "Generating... (batch 1/3)",
+                "Generating... (batch 2/3)",
+                "Generating... (batch 3/3)",
+            ]
+        );
+    }
+
+    #[test]
+    fn split_lines_parse_like_whole_ones() {
+        let chars: Vec<char> = EXPEDITION.chars().collect();
+        let mut parser = LogParser::new();
+        let mut msgs = vec![];
+        for chunk in chars.chunks(7) {
+            msgs.extend(parser.push(&String::from_iter(chunk)));
+        }
+
+        assert_eq!(msgs, parse_str(EXPEDITION));
+    }
+
+    #[test]
+    fn last_line_waits_for_the_log_to_end() {
+        let lobby =
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionSuccess TO: Lobby";
+        let mut parser = LogParser::new();
+        parser.push(EXPEDITION);
+
+        assert!(!parser.push(lobby).contains(&ParserMsg::Reset));
+        assert!(parser.finish().contains(&ParserMsg::Reset));
+    }
+
+    #[test]
+    fn checkpoint_keeps_the_level_start() {
+        let header = "20:03:00.000 - Mono path[0] = 'GTFO_Data/Managed'\n";
+        let lobby =
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionSuccess TO: Lobby\n";
+        let opened = Checkpoint::new(Path::new("GTFO.txt"), 100);
+        let (parser_tx, _parser_rx) = channel();
+        let mut manager = ParserManager {
+            buffer: format!("{header}{EXPEDITION}"),
+            ..Default::default()
+        };
+        manager.drain(&parser_tx).unwrap();
+
+        let checkpoint = manager.checkpoint(&opened);
+        assert_eq!(checkpoint.offset, 100 + manager.buffer.len() as u64);
+        assert_eq!(checkpoint.level, Some(100 + header.len() as u64));
+
+        manager.buffer.push_str(lobby);
+        manager.drain(&parser_tx).unwrap();
+        assert_eq!(manager.checkpoint(&opened).level, None);
+    }
+
+    #[test]
+    fn level_starts_once() {
+        let log = format!(
+            "{EXPEDITION}{}{}{}",
+            "20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : InLevel TO: ExpeditionFail\n",
+            "20:10:05.000 - GAMESTATEMANAGER CHANGE STATE FROM : ExpeditionFail TO: InLevel\n",
+            "20:10:08.000 - BUILDER : BuildDone\n",
+        );
+        let starts: Vec<_> = parse_str(&log)
+            .into_iter()
+            .filter(|msg| matches!(msg, ParserMsg::LevelStart(_)))
+            .collect();
+
+        assert_eq!(
+            starts,
+            vec![ParserMsg::LevelStart(Some(Time::constant(
+                20,
+                3,
+                29,
+                500_000_000
+            )))]
+        );
+    }
+
+    #[test]
+    fn timer_messages() {
+        re::use_sample(&[
+            &re::PLAYER_ZONE_ENTERED,
+            &re::BULKHEAD_SCAN_DONE,
+            &re::CHECKPOINT_ACTIVATED,
+            &re::CHECKPOINT_RELOAD,
+        ]);
+        let log = format!(
+            "{EXPEDITION}{}{}{}{}{}{}{}",
+            "20:05:00.000 - LG_SecurityDoor.OnDoorIsOpened ZONE_50\n",
+            "20:05:10.000 - LocalPlayerAgent.OnCourseNodeChanged ZONE_50 Area_B\n",
+            "20:07:00.000 - LG_BulkheadDoorController_Core.OnScanDone ZONE_51\n",
+            "20:08:00.000 - CheckpointManager.StoreCheckpoint\n",
+            "20:10:05.000 - CheckpointManager.ReloadCheckpoint\n",
+            "20:10:06.000 - GAMESTATEMANAGER CHANGE STATE FROM : ReadyToStartLevel TO: InLevel\n",
+            "20:20:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : InLevel TO: ExpeditionSuccess\n",
+        );
+        let timer: Vec<_> = parse_str(&log)
+            .into_iter()
+            .filter(|msg| {
+                matches!(
+                    msg,
+                    ParserMsg::ElevatorDrop(_)
+                        | ParserMsg::ZoneDoorOpened(..)
+                        | ParserMsg::ZoneEntered(..)
+                        | ParserMsg::BulkheadScanned(..)
+                        | ParserMsg::CheckpointActivated(_)
+                        | ParserMsg::LevelFinish(_)
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            timer,
+            vec![
+                ParserMsg::ElevatorDrop(Some(Time::constant(20, 3, 30, 0))),
+                ParserMsg::ZoneDoorOpened(50, Some(Time::constant(20, 5, 0, 0))),
+                ParserMsg::ZoneEntered(50, Some('B'), Some(Time::constant(20, 5, 10, 0))),
+                ParserMsg::BulkheadScanned(51, Some(Time::constant(20, 7, 0, 0))),
+                ParserMsg::CheckpointActivated(Some(Time::constant(20, 8, 0, 0))),
+                ParserMsg::LevelFinish(Some(Time::constant(20, 20, 0, 0))),
+            ]
+        );
+    }
+
+    #[test]
+    fn outcomes() {
+        let outcome = |lines: &[&str]| {
+            parse_str(&format!("{EXPEDITION}{}", lines.concat()))
+                .into_iter()
+                .filter_map(|msg| match msg {
+                    ParserMsg::Outcome(outcome) => Some(outcome),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let state = |from: &str, to: &str| {
+            format!("20:10:00.000 - GAMESTATEMANAGER CHANGE STATE FROM : {from} TO: {to}\n")
+        };
+
+        assert_eq!(
+            outcome(&[
+                &state("InLevel", "ExpeditionSuccess"),
+                &state("ExpeditionSuccess", "Lobby"),
+            ]),
+            vec![Outcome::Success]
+        );
+        assert_eq!(
+            outcome(&[
+                &state("InLevel", "ExpeditionFail"),
+                &state("ExpeditionFail", "Lobby"),
+            ]),
+            vec![Outcome::Failed]
+        );
+        assert_eq!(
+            outcome(&["20:10:00.000 - GameDataInit.OnApplicationQuit\n"]),
+            vec![Outcome::Aborted]
+        );
+    }
+
+    fn round_trip(msg: ParserMsg) {
+        let json = serde_json::to_string(&msg).unwrap();
+        let back: ParserMsg = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(msg, back, "{json}");
+    }
+
+    fn zone() -> Zone {
+        Zone {
+            alias: 49,
+            local: 2,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: Some('B'),
+        }
+    }
+
+    #[test]
+    fn level_seeds_round_trip() {
+        round_trip(ParserMsg::LevelSeeds(
+            Seeds {
+                build: 1,
+                host: 2,
+                session: 3,
+            },
+            line_time("20:03:18.001 - Builder.Build"),
+        ));
+    }
+
+    #[test]
+    fn level_init_round_trip() {
+        round_trip(ParserMsg::LevelInit(Box::new(Level {
+            rundown: Rundown::R8,
+            exp_name: "A1".into(),
+            zones: vec![zone()],
+            ..Default::default()
+        })));
+    }
+
+    #[test]
+    fn generated_zone_round_trip() {
+        round_trip(ParserMsg::GeneratedZone(TimerEntry::Start, None));
+        round_trip(ParserMsg::GeneratedZone(
+            TimerEntry::Zone(zone()),
+            Some(Time::constant(20, 3, 19, 31_000_000)),
+        ));
+        round_trip(ParserMsg::GeneratedZone(TimerEntry::End, None));
+    }
+
+    #[test]
+    fn connection_round_trip() {
+        round_trip(ParserMsg::Connection(49, 50));
+    }
+
+    #[test]
+    fn gatherable_round_trip() {
+        round_trip(ParserMsg::Gatherable(
+            GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 49, 3),
+            Some(Time::constant(20, 3, 19, 51_000_000)),
+        ));
+        round_trip(ParserMsg::Gatherable(
+            GatherItem::ID("Locker".into(), 1234),
+            None,
+        ));
+    }
+
+    #[test]
+    fn gatherable_is_tagged() {
+        let json = serde_json::to_value(ParserMsg::Gatherable(GatherItem::Cell(3), None)).unwrap();
+
+        assert_eq!(json["type"], "Gatherable");
+        assert_eq!(json["data"][0]["Cell"]["zone_idx"], 3);
+    }
+
+    #[test]
+    fn gatherable_fields_are_named() {
+        let items = [
+            (
+                GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 49, 3),
+                json!({"Key": {"name": "KEY_GREEN_584", "dimension": "Reality", "zone": 49, "ri": 3}}),
+            ),
+            (
+                GatherItem::BulkheadKey("BULKHEAD_KEY_123".into()),
+                json!({"BulkheadKey": {"name": "BULKHEAD_KEY_123"}}),
+            ),
+            (
+                GatherItem::HSU(2, 'B'),
+                json!({"HSU": {"area_id": 2, "area": "B"}}),
+            ),
+            (
+                GatherItem::Generator("GENERATOR_1".into(), 0, 1),
+                json!({"Generator": {"name": "GENERATOR_1", "collection": 0, "idx": 1}}),
+            ),
+            (
+                GatherItem::ID("Locker".into(), 1234),
+                json!({"ID": {"container": "Locker", "seed": 1234}}),
+            ),
+            (
+                GatherItem::PD("Box".into(), 5),
+                json!({"PD": {"container": "Box", "seed": 5}}),
+            ),
+            (GatherItem::Cell(3), json!({"Cell": {"zone_idx": 3}})),
+            (
+                GatherItem::FogTurbine("Fog Turbine ZONE_50 #0".into()),
+                json!({"FogTurbine": {"name": "Fog Turbine ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::Neonate("Neonate ZONE_50 #0".into()),
+                json!({"Neonate": {"name": "Neonate ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::Cryo("Cryo ZONE_50 #0".into()),
+                json!({"Cryo": {"name": "Cryo ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::GLP1("Locker".into(), 6),
+                json!({"GLP1": {"container": "Locker", "seed": 6}}),
+            ),
+            (
+                GatherItem::OSIP("Locker".into(), 7),
+                json!({"OSIP": {"container": "Locker", "seed": 7}}),
+            ),
+            (
+                GatherItem::Datasphere(1),
+                json!({"Datasphere": {"zone_idx": 1}}),
+            ),
+            (
+                GatherItem::PlantSample("Box".into(), 8),
+                json!({"PlantSample": {"container": "Box", "seed": 8}}),
+            ),
+            (
+                GatherItem::HiSec("HiSec Cargo ZONE_50 #0".into()),
+                json!({"HiSec": {"name": "HiSec Cargo ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::DataCube("Locker".into(), 9),
+                json!({"DataCube": {"container": "Locker", "seed": 9}}),
+            ),
+            (
+                GatherItem::GLP2("Box".into(), 10),
+                json!({"GLP2": {"container": "Box", "seed": 10}}),
+            ),
+            (
+                GatherItem::Cargo("Cargo ZONE_50 #0".into()),
+                json!({"Cargo": {"name": "Cargo ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::MWP("MWP ZONE_50 #0".into()),
+                json!({"MWP": {"name": "MWP ZONE_50 #0"}}),
+            ),
+            (
+                GatherItem::Uplink("TERMINAL_123".into()),
+                json!({"Uplink": {"name": "TERMINAL_123"}}),
+            ),
+            (
+                GatherItem::Seeded("Locker".into(), 11),
+                json!({"Seeded": {"container": "Locker", "seed": 11}}),
+            ),
+            (
+                GatherItem::Unknown(200, "Modded".into()),
+                json!({"Unknown": {"id": 200, "name": "Modded"}}),
+            ),
+        ];
+
+        for (item, named) in items {
+            assert_eq!(serde_json::to_value(&item).unwrap(), named);
+            round_trip(ParserMsg::Gatherable(item, None));
+        }
+    }
+
+    #[test]
+    fn positional_gatherables_still_load() {
+        let key: GatherItem =
+            serde_json::from_str(r#"{"Key": ["KEY_GREEN_584", "Reality", 49, 3]}"#).unwrap();
+        assert_eq!(
+            key,
+            GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 49, 3)
+        );
+        let cell: GatherItem = serde_json::from_str(r#"{"Cell": 3}"#).unwrap();
+        assert_eq!(cell, GatherItem::Cell(3));
+    }
+
+    #[test]
+    fn collected_round_trip() {
+        round_trip(ParserMsg::Collected(
+            Pickup {
+                item: "KEY_RED_1".into(),
+                zone: 50,
+                seed: 0,
+            },
+            None,
+        ));
+    }
+
+    #[test]
+    fn unit_variants_round_trip() {
+        round_trip(ParserMsg::LevelStart(None));
+        round_trip(ParserMsg::LevelStart(Some(Time::constant(20, 3, 30, 0))));
+        round_trip(ParserMsg::ElevatorDrop(Some(Time::constant(20, 3, 30, 0))));
+        round_trip(ParserMsg::ZoneDoorOpened(50, None));
+        round_trip(ParserMsg::BulkheadScanned(51, None));
+        round_trip(ParserMsg::CheckpointActivated(None));
+        round_trip(ParserMsg::Diagnostic(Diagnostic {
+            severity: Severity::Info,
+            message: "ZONE_CREATED missed line".into(),
+        }));
+        round_trip(ParserMsg::Error(ParserError::LogFailed(
+            "NICKNAME_NETSTATUS.txt".into(),
+            "denied".into(),
+        )));
+        round_trip(ParserMsg::LevelFinish(None));
+        round_trip(ParserMsg::Checkpoint);
+        round_trip(ParserMsg::Reset);
+        round_trip(ParserMsg::NewFile);
+    }
+
+    #[test]
+    fn session_round_trip() {
+        round_trip(ParserMsg::Session(SessionInfo {
+            build: Some(34853),
+            revision: None,
+            player: Some("Rusty Maid".into()),
+            platform: Some("STEAM".into()),
+        }));
+    }
+
+    #[test]
+    fn custom_round_trip() {
+        round_trip(ParserMsg::Custom(
+            "Reactor".into(),
+            serde_json::json!({"wave": "3"}),
+        ));
+    }
+
+    #[test]
+    fn status_round_trip() {
+        round_trip(ParserMsg::Status(ParserStatus {
+            file: Some("NICKNAME_NETSTATUS.txt".into()),
+            last_line: line_time("20:03:19.031 - Builder.Build"),
+            lines_per_sec: 12.5,
+            tail_alive: true,
+        }));
+    }
+
+    #[test]
+    fn builder_reads_named_logs() {
+        let dir = TempDir::new("builder");
+        std::fs::write(dir.join("custom_run.txt"), EXPEDITION).unwrap();
+        std::fs::write(dir.join("NICKNAME_NETSTATUS.txt"), "").unwrap();
+
+        let mut parser = Parser::builder()
+            .watch_path(dir.path())
+            .log_name("custom_run")
+            .poll_interval(Duration::from_millis(10))
+            .channel_capacity(1)
+            .build();
+        parser.start_watcher().unwrap();
+
+        let rx = parser.rx.take().unwrap();
+        let seeds = rx
+            .iter()
+            .find_map(|msg| match msg {
+                ParserMsg::LevelSeeds(seeds, _) => Some(seeds),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(seeds.host, 34);
+
+        parser.shutdown();
+    }
+
+    #[test]
+    fn stopping_joins_the_supervisor() {
+        let dir = TempDir::new("supervisor");
+        let mut parser = Parser::builder()
+            .watch_path(dir.path())
+            .poll_interval(Duration::from_millis(10))
+            .build();
+        parser.start_watcher().unwrap();
+        assert!(parser.supervisor.is_some());
+
+        // It's woken up instead of waiting out its sleep, or the whole timeout.
+        let started = Instant::now();
+        parser.stop_threads();
+        assert!(parser.supervisor.is_none());
+        assert!(started.elapsed() < Duration::from_millis(500));
     }
 }
@@ -0,0 +1,14 @@
+//! What tools embedding the parser usually need, `use rm_core::prelude::*;` brings it in.
+//! These names are kept stable, the rest of the crate may move around between releases.
+
+#[cfg(feature = "async")]
+pub use crate::stream::{AsyncParser, ParserStream};
+pub use crate::{
+    compare::{compare, Discrepancy, LevelBuild},
+    config::{PollConfig, ResumeConfig, ResumeFrom},
+    data::{GatherItem, Level, Objective, Outcome, Pickup, Rundown, Seeds, TimerEntry, Zone},
+    diagnostics::{Diagnostic, Severity},
+    error::ParserError,
+    handler::{LineHandler, RegexHandler},
+    parser::{parse_str, LogParser, Parser, ParserBuilder, ParserMsg, ParserStatus},
+};
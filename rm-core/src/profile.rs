@@ -0,0 +1,266 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::anyhow;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{data::ItemIdentifier, re};
+
+/// Rundown of a modded game, e.g. from MTFO, that the stock ids don't cover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RundownProfile {
+    /// Rundown index the game logs, the `rundown_idx` of the drop line
+    pub id: u16,
+    /// Shown in place of `Modded`, like `MOD1`
+    pub name: String,
+    /// Names for tier letters, like `A = "Alpha"`
+    #[serde(default)]
+    pub tiers: BTreeMap<String, String>,
+}
+
+/// What a user knows about a modded rundown, loaded from a TOML or JSON file in
+/// [`profiles_dir`].
+///
+/// `items` maps modded item ids onto the stock item they behave like, `names` gives the
+/// ones that don't behave like any a name. `patterns` overrides patterns like
+/// `patterns.toml` does.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub rundowns: Vec<RundownProfile>,
+    pub items: BTreeMap<String, ItemIdentifier>,
+    pub names: BTreeMap<String, String>,
+    pub patterns: HashMap<String, String>,
+}
+
+/// Item datablock as dumped from the game, only what's needed to name items.
+#[derive(Debug, Deserialize)]
+struct ItemDataBlock {
+    #[serde(rename = "Blocks")]
+    blocks: Vec<ItemBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemBlock {
+    #[serde(rename = "persistentID")]
+    id: u32,
+    #[serde(rename = "publicName")]
+    name: String,
+}
+
+impl Profile {
+    /// Reads a profile, `.json` files as JSON and anything else as TOML. JSON files with
+    /// `Blocks`, i.e. an `ItemDataBlock` dump, only name the items in them.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let profile: Profile = if path.extension().is_some_and(|ext| ext == "json") {
+            let json: serde_json::Value = serde_json::from_str(&text)?;
+            if json.get("Blocks").is_some() {
+                Profile::from_datablock(serde_json::from_value(json)?)
+            } else {
+                serde_json::from_value(json)?
+            }
+        } else {
+            toml::from_str(&text)?
+        };
+
+        if let Some(id) = profile
+            .items
+            .keys()
+            .chain(profile.names.keys())
+            .find(|id| id.parse::<u8>().is_err())
+        {
+            return Err(anyhow!("item id `{id}` isn't a number from 0 to 255"));
+        }
+
+        Ok(profile)
+    }
+
+    /// Names of the items in a datablock dump, blocks with ids past 255 can't be logged and
+    /// are left out.
+    fn from_datablock(datablock: ItemDataBlock) -> Self {
+        let names = datablock
+            .blocks
+            .into_iter()
+            .filter(|block| block.id <= u8::MAX as u32 && !block.name.is_empty())
+            .map(|block| (block.id.to_string(), block.name))
+            .collect();
+
+        Profile {
+            names,
+            ..Default::default()
+        }
+    }
+}
+
+/// Every loaded profile. Later profiles win when two describe the same id or pattern.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profiles {
+    profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// Loads every profile in `dir` in file name order, broken ones are logged and skipped.
+    pub fn load_dir(dir: &Path) -> anyhow::Result<Self> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "toml" || ext == "json")
+            })
+            .collect();
+        paths.sort();
+
+        let mut profiles = vec![];
+        for path in paths {
+            match Profile::load(&path) {
+                Ok(profile) => {
+                    info!("Loaded profile {}", path.display());
+                    profiles.push(profile);
+                }
+                Err(e) => warn!("Skipping profile {}: {e}", path.display()),
+            }
+        }
+
+        Ok(Self { profiles })
+    }
+
+    pub fn rundown(&self, id: u16) -> Option<&RundownProfile> {
+        self.profiles
+            .iter()
+            .rev()
+            .flat_map(|profile| &profile.rundowns)
+            .find(|rundown| rundown.id == id)
+    }
+
+    /// Stock item with `id`, or the one a profile maps it onto.
+    pub fn item(&self, id: u8) -> Option<ItemIdentifier> {
+        ItemIdentifier::try_from(id).ok().or_else(|| {
+            self.profiles
+                .iter()
+                .rev()
+                .find_map(|profile| profile.items.get(&id.to_string()))
+                .cloned()
+        })
+    }
+
+    /// Name a profile gives the item with `id`.
+    pub fn name(&self, id: u8) -> Option<&str> {
+        self.profiles
+            .iter()
+            .rev()
+            .find_map(|profile| profile.names.get(&id.to_string()))
+            .map(String::as_str)
+    }
+
+    pub fn patterns(&self) -> HashMap<String, String> {
+        self.profiles
+            .iter()
+            .flat_map(|profile| profile.patterns.clone())
+            .collect()
+    }
+}
+
+static PROFILES: OnceLock<Profiles> = OnceLock::new();
+
+/// Default folder of the user's profiles.
+pub fn profiles_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("profiles"))
+}
+
+/// Profiles the parser uses, none until [`load_user`] ran.
+pub fn active() -> &'static Profiles {
+    PROFILES.get_or_init(Profiles::default)
+}
+
+/// Loads the profiles in [`profiles_dir`] and the pattern overrides in
+/// [`re::overrides_path`], which win over the profiles' patterns.
+///
+/// Has to be called before the parser touches any pattern or profile. Loading again does
+/// nothing.
+pub fn load_user() {
+    load_from(profiles_dir());
+}
+
+/// Like [`load_user`], with the profiles read from `dir` instead of the user's folder.
+pub fn load_from(dir: Option<PathBuf>) {
+    let profiles = dir
+        .filter(|dir| dir.is_dir())
+        .map(|dir| {
+            Profiles::load_dir(&dir).unwrap_or_else(|e| {
+                error!("Failed to read profiles in {}: {e}", dir.display());
+                Profiles::default()
+            })
+        })
+        .unwrap_or_default();
+
+    let mut patterns = profiles.patterns();
+    if let Some(path) = re::overrides_path().filter(|path| path.is_file()) {
+        match re::read_overrides(&path) {
+            Ok(overrides) => patterns.extend(overrides),
+            Err(e) => error!(
+                "Failed to load pattern overrides from {}: {e}",
+                path.display()
+            ),
+        }
+    }
+
+    if !patterns.is_empty() {
+        if let Err(e) = re::set_overrides(patterns) {
+            error!("{e}");
+        }
+    }
+    let _ = PROFILES.set(profiles);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn later_profiles_win() {
+        let dir = TempDir::new("profiles");
+        fs::write(
+            dir.join("a.toml"),
+            r#"
+[[rundowns]]
+id = 70
+name = "MOD1"
+tiers = { A = "Alpha" }
+
+[items]
+201 = "ID"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.json"),
+            r#"{"rundowns": [{"id": 70, "name": "MOD2"}], "patterns": {"BUILDER_END": "Done"}}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "[items]\nnope = \"ID\"\n").unwrap();
+        fs::write(
+            dir.join("c.json"),
+            r#"{"Blocks": [{"persistentID": 202, "publicName": "Relic"}, {"persistentID": 900, "publicName": "Big"}]}"#,
+        )
+        .unwrap();
+
+        let profiles = Profiles::load_dir(dir.path()).unwrap();
+        assert_eq!(profiles.rundown(70).unwrap().name, "MOD2");
+        assert_eq!(profiles.rundown(71), None);
+        assert_eq!(profiles.item(201), Some(ItemIdentifier::ID));
+        assert_eq!(profiles.item(128), Some(ItemIdentifier::ID));
+        assert_eq!(profiles.item(202), None);
+        assert_eq!(profiles.name(202), Some("Relic"));
+        assert_eq!(profiles.name(201), None);
+        assert_eq!(profiles.patterns()["BUILDER_END"], "Done");
+    }
+}
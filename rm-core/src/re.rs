@@ -1,79 +1,620 @@
+use std::{
+    collections::HashMap,
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{LazyLock, OnceLock},
+};
+
+use anyhow::anyhow;
+use log::{info, warn};
 use regex::Regex;
-use std::sync::LazyLock;
+
+/// Named capture groups every pattern provides, in the order the parser extracts them.
+///
+/// User overrides have to define exactly these groups, otherwise they are rejected and
+/// the compiled default is used instead.
+pub const CAPTURE_GROUPS: &[(&str, &[&str])] = &[
+    ("GAME_BUILD", &["build", "revision"]),
+    ("PLAYER_NAME", &["player"]),
+    ("PLATFORM", &["platform"]),
+    ("BUILDER_LEVEL_SEEDS", &["build", "hostId", "session"]),
+    (
+        "DROP_SERVER_MANAGER_NEW_SESSION",
+        &["rundown_idx", "rundown_exp"],
+    ),
+    ("SETUP_FLOOR_BATCH_START", &[]),
+    ("SETUP_FLOOR_BATCH_END", &[]),
+    ("ZONE_CREATED", &["alias", "local", "dim", "layer"]),
+    ("ZONE_BUILD_FROM", &["alias", "from"]),
+    ("DISTRIBUTION_BATCH_START", &[]),
+    ("DISTRIBUTION_BATCH_END", &[]),
+    (
+        "CREATE_KEY_ITEM_DISTRIBUTION",
+        &["key", "dim", "local", "alias", "ri", "area"],
+    ),
+    ("DISTRIBUTE_WARDEN_OBJECTIVE", &["alias", "idx", "item"]),
+    ("DISTRIBUTE_NAMED_OBJECTIVE", &["alias", "idx", "item"]),
+    ("WARDEN_OBJECTIVE_ITEMS", &["count", "item"]),
+    ("ITEM_SERIAL", &["alias", "serial"]),
+    ("FUNCTION_MARKERS_BATCH_START", &[]),
+    ("FUNCTION_MARKERS_BATCH_END", &[]),
+    ("WARDEN_OBJECTIVE_MANAGER", &["alias", "id", "name"]),
+    ("GENERIC_SMALL_PICKUP_ITEM", &["kind", "container", "seed"]),
+    ("BUILDER_END", &[]),
+    ("GAME_STATE_CHANGE", &["from", "to"]),
+    ("CHECKPOINT_RELOAD", &[]),
+    ("DISTRIBUTE_HSU", &["alias", "id", "area"]),
+    ("ITEM_PICKUP", &["item", "zone", "seed"]),
+    ("APPLICATION_QUIT", &[]),
+    ("SECURITY_DOOR_OPENED", &["alias"]),
+    ("BULKHEAD_SCAN_DONE", &["alias"]),
+    ("CHECKPOINT_ACTIVATED", &[]),
+    ("CARRY_ITEM_PICKUP", &["item", "zone"]),
+    ("CARRY_ITEM_SPAWN", &["item", "alias", "area"]),
+    ("SMALL_PICKUP_INTERACT", &["item", "zone", "seed"]),
+    ("GENERATION_ANOMALY", &["anomaly"]),
+    ("PLAYER_ZONE_ENTERED", &["alias", "area"]),
+    ("SECURITY_SCAN_START", &["alias", "class"]),
+    ("ERROR_ALARM", &["alias"]),
+    ("UPLINK_TERMINAL", &["alias", "serial"]),
+    ("ZONE_DIMENSION", &["dim"]),
+    ("BUILD_BATCH", &["batch"]),
+];
+
+static OVERRIDES: OnceLock<HashMap<String, Regex>> = OnceLock::new();
+
+/// Default location of the user pattern overrides.
+pub fn overrides_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rusted-mapper").join("patterns.toml"))
+}
+
+/// Loads user overrides for the patterns below from a TOML table of `NAME = 'regex'`.
+///
+/// Has to be called before the parser touches any pattern, patterns that were already
+/// compiled keep their value. Invalid overrides are logged and skipped.
+pub fn load_overrides(path: &Path) -> anyhow::Result<()> {
+    set_overrides(read_overrides(path)?)
+}
+
+/// Reads a TOML table of `NAME = 'regex'` without checking the patterns.
+pub fn read_overrides(path: &Path) -> anyhow::Result<HashMap<String, String>> {
+    Ok(toml::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Overrides patterns by name, like [`load_overrides`] but from patterns already read.
+pub fn set_overrides(patterns: HashMap<String, String>) -> anyhow::Result<()> {
+    let mut overrides = HashMap::new();
+    for (name, src) in patterns {
+        match validate(&name, &src) {
+            Ok(re) => {
+                info!("Overriding pattern {name}");
+                overrides.insert(name, re);
+            }
+            Err(e) => warn!("Ignoring override for {name}: {e}"),
+        }
+    }
+
+    OVERRIDES
+        .set(overrides)
+        .map_err(|_| anyhow!("Pattern overrides were already loaded"))
+}
+
+/// Compiles a user pattern and checks it against [`CAPTURE_GROUPS`].
+pub fn validate(name: &str, src: &str) -> anyhow::Result<Regex> {
+    let (_, groups) = CAPTURE_GROUPS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .ok_or_else(|| anyhow!("unknown pattern"))?;
+
+    let re = Regex::new(src)?;
+
+    let names: Vec<&str> = re.capture_names().flatten().collect();
+    if re.captures_len() - 1 != groups.len() || groups.iter().any(|g| !names.contains(g)) {
+        return Err(anyhow!("it has to define exactly the groups {groups:?}"));
+    }
+
+    Ok(re)
+}
+
+/// Pattern compiled on first use, from its override if `patterns.toml` has one.
+#[derive(Debug)]
+pub struct Pattern {
+    name: &'static str,
+    default: &'static str,
+    /// Whether `default` was written against real log lines, see [`Pattern::guessed`]
+    verified: bool,
+    /// Whether `default` is used without an override, see [`Pattern::unverified`]
+    live: bool,
+    compiled: OnceLock<Regex>,
+}
+
+impl Pattern {
+    const fn new(name: &'static str, default: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            verified: true,
+            live: true,
+            compiled: OnceLock::new(),
+        }
+    }
+
+    /// Pattern for a line that was only described, not seen in a real log. It's used like a
+    /// verified one, but what it drives shouldn't throw away what was mapped on its word
+    /// alone, see [`Pattern::is_verified`].
+    const fn guessed(name: &'static str, default: &'static str) -> Self {
+        Self {
+            name,
+            default,
+            verified: false,
+            live: true,
+            compiled: OnceLock::new(),
+        }
+    }
+
+    /// Pattern for a line that hasn't been seen in a real log yet. It matches nothing until
+    /// `patterns.toml` overrides it, `sample` is only the wording the unit tests of what it
+    /// drives override it with, see [`use_sample`].
+    const fn unverified(name: &'static str, sample: &'static str) -> Self {
+        Self {
+            name,
+            default: sample,
+            verified: false,
+            live: false,
+            compiled: OnceLock::new(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Whether the pattern was written against real log lines, or overridden in
+    /// `patterns.toml` by someone who has them.
+    pub fn is_verified(&self) -> bool {
+        self.verified || self.is_overridden()
+    }
+
+    /// Whether the pattern matches anything, without an override or with one.
+    pub fn is_active(&self) -> bool {
+        self.live || self.is_overridden()
+    }
+
+    fn is_overridden(&self) -> bool {
+        #[cfg(test)]
+        if TEST_OVERRIDES.with_borrow(|overrides| overrides.contains_key(self.name)) {
+            return true;
+        }
+
+        OVERRIDES
+            .get()
+            .is_some_and(|overrides| overrides.contains_key(self.name))
+    }
+}
+
+/// Whether pickups are read from the log at all, without an active pickup pattern the
+/// items are only ticked off by hand.
+pub fn reads_pickups() -> bool {
+    [&ITEM_PICKUP, &CARRY_ITEM_PICKUP, &SMALL_PICKUP_INTERACT]
+        .iter()
+        .any(|pattern| pattern.is_active())
+}
+
+/// Regex with the capture groups of `name` that never matches, what unverified patterns
+/// compile to.
+fn never(name: &str) -> Regex {
+    let groups = CAPTURE_GROUPS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map_or(&[][..], |(_, groups)| *groups);
+    let src: String = groups
+        .iter()
+        .map(|group| format!(r"(?<{group}>[^\s\S])"))
+        .collect();
+
+    Regex::new(if src.is_empty() { r"[^\s\S]" } else { &src }).unwrap()
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Overrides of the unit test running on this thread, kept out of the compiled patterns
+    /// so they don't leak into other tests.
+    static TEST_OVERRIDES: std::cell::RefCell<HashMap<&'static str, &'static Regex>> =
+        Default::default();
+}
+
+/// Overrides `pattern` with `src` for the rest of the unit test, like `patterns.toml` would.
+#[cfg(test)]
+pub(crate) fn override_for_test(pattern: &Pattern, src: &str) {
+    let re = validate(pattern.name, src).unwrap();
+    TEST_OVERRIDES.with_borrow_mut(|overrides| {
+        overrides.insert(pattern.name, Box::leak(Box::new(re)));
+    });
+}
+
+/// Overrides unverified `patterns` with their samples for the rest of the unit test.
+#[cfg(test)]
+pub(crate) fn use_sample(patterns: &[&Pattern]) {
+    for pattern in patterns {
+        override_for_test(pattern, pattern.default);
+    }
+}
+
+impl Deref for Pattern {
+    type Target = Regex;
+
+    fn deref(&self) -> &Regex {
+        #[cfg(test)]
+        if let Some(re) = TEST_OVERRIDES.with_borrow(|overrides| overrides.get(self.name).copied())
+        {
+            return re;
+        }
+
+        self.compiled.get_or_init(|| {
+            if let Some(re) = OVERRIDES
+                .get()
+                .and_then(|overrides| overrides.get(self.name))
+            {
+                return re.clone();
+            }
+
+            match self.live {
+                true => Regex::new(self.default).unwrap(),
+                false => never(self.name),
+            }
+        })
+    }
+}
+
+/// Build of the game that wrote the log, from its header. `revision` is only there when the
+/// line has one. Unverified, no real header line is known yet
+pub static GAME_BUILD: Pattern = Pattern::unverified(
+    "GAME_BUILD",
+    r"(?m)^.*?\b(?:GTFO|Game)\b.*?\b[Bb]uild(?:\s?[Nn]umber)?[:=]?\s*(?<build>\d+)\b(?:.*?\b[Rr]ev(?:ision)?[:=]?\s*(?<revision>\d+)\b)?.*$",
+);
+
+/// Nickname of the local player, from the log header. Unverified, no real header line is
+/// known yet
+pub static PLAYER_NAME: Pattern = Pattern::unverified(
+    "PLAYER_NAME",
+    r"(?m)^.*?\b(?:SNet|Steam\w*|PlayerManager|LocalPlayer)\b.*?\b(?:[Pp]layer|[Uu]ser|[Pp]ersona)\s?[Nn]ame[:=]?\s*(?<player>\S(?:.*?\S)?)\s*$",
+);
+
+/// Store the game runs from, i.e. `STEAM` from `SNet_Core_STEAM`, from the log header.
+/// Unverified, no real header line is known yet
+pub static PLATFORM: Pattern = Pattern::unverified(
+    "PLATFORM",
+    r"(?m)^.*?\b(?:SNet_Core_|[Pp]latform[:=]?\s*)(?<platform>[A-Za-z]+)\b.*$",
+);
+
+/// Local time prefix of every log line, i.e. `20:03:19.031 - `
+pub static LINE_TIME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?<time>\d{2}:\d{2}:\d{2}\.\d+)\s-\s").unwrap());
 
 /// At the start of level gen - get the seed info
-pub static BUILDER_LEVEL_SEEDS: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?m)^.*Builder\.Build.*buildSeed:\s(?<build>\d+)\shostIDSeed:\s(?<hostId>\d+)\ssessionSeed:\s(?<session>\d+).*$").unwrap()
-});
+pub static BUILDER_LEVEL_SEEDS: Pattern = Pattern::new(
+    "BUILDER_LEVEL_SEEDS",
+    r"(?m)^.*Builder\.Build.*buildSeed:\s(?<build>\d+)\shostIDSeed:\s(?<hostId>\d+)\ssessionSeed:\s(?<session>\d+).*$",
+);
 
 /// At the start of level gen - get the level info
-pub static DROP_SERVER_MANAGER_NEW_SESSION: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?m)^.*ServerManager:\s'new\ssession.*?rundown:\sLocal_(?<rundown_idx>\d+),\sexpedition:\s(?<rundown_exp>\w\d).*$").unwrap()
-});
+pub static DROP_SERVER_MANAGER_NEW_SESSION: Pattern = Pattern::new(
+    "DROP_SERVER_MANAGER_NEW_SESSION",
+    r"(?m)^.*ServerManager:\s'new\ssession.*?rundown:\sLocal_(?<rundown_idx>\d+),\sexpedition:\s(?<rundown_exp>\w\d).*$",
+);
 
 /// SetupFloor batch start
-pub static SETUP_FLOOR_BATCH_START: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^Next\sBatch:\sSetupFloor.*$").unwrap());
+pub static SETUP_FLOOR_BATCH_START: Pattern = Pattern::new(
+    "SETUP_FLOOR_BATCH_START",
+    r"(?m)^Next\sBatch:\sSetupFloor.*$",
+);
 
 /// SetupFloor batch end
-pub static SETUP_FLOOR_BATCH_END: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^.*Last\sBatch:\sSetupFloor.*$").unwrap());
+pub static SETUP_FLOOR_BATCH_END: Pattern = Pattern::new(
+    "SETUP_FLOOR_BATCH_END",
+    r"(?m)^.*Last\sBatch:\sSetupFloor.*$",
+);
 
 /// Zone info inside SetupFloor batch
-pub static ZONE_CREATED: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"(?m)^.*?Alias: (?<alias>\d+).*aliasOffset: \w+_(?<local>\d+).*\s.*?Zone\sCreated.*?in\s(?<dim>\w+)\s(?<layer>\w+).*$"
-    )
-    .unwrap()
-});
+pub static ZONE_CREATED: Pattern = Pattern::new(
+    "ZONE_CREATED",
+    r"(?m)^.*?Alias: (?<alias>\d+).*aliasOffset: \w+_(?<local>\d+).*\s.*?Zone\sCreated.*?in\s(?<dim>\w+)\s(?<layer>\w+).*$",
+);
+
+/// Zone build-from relationship inside SetupFloor batch, the first zone is built from itself
+pub static ZONE_BUILD_FROM: Pattern = Pattern::new(
+    "ZONE_BUILD_FROM",
+    r"(?m)^.*CreateZone,\sAlias:\s(?<alias>\d+)\swith\sBuildFromZoneAlias(?<from>\d+).*$",
+);
 
 /// Distribution batch items
-pub static DISTRIBUTION_BATCH_START: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^.*Next\sBatch:\sDistribution.*$").unwrap());
+pub static DISTRIBUTION_BATCH_START: Pattern = Pattern::new(
+    "DISTRIBUTION_BATCH_START",
+    r"(?m)^.*Next\sBatch:\sDistribution.*$",
+);
 
-pub static DISTRIBUTION_BATCH_END: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^.*Last\sBatch:\sDistribution.*$").unwrap());
+pub static DISTRIBUTION_BATCH_END: Pattern = Pattern::new(
+    "DISTRIBUTION_BATCH_END",
+    r"(?m)^.*Last\sBatch:\sDistribution.*$",
+);
 
-pub static CREATE_KEY_ITEM_DISTRIBUTION: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(concat!(
+/// Key distribution, `area` is only there when the game logs which area the key is in
+pub static CREATE_KEY_ITEM_DISTRIBUTION: Pattern = Pattern::new(
+    "CREATE_KEY_ITEM_DISTRIBUTION",
+    concat!(
         r"(?m)^.*?PublicName:\s(?<key>[A-Za-z0-9_]+).*?DimensionIndex:\s(?<dim>\w+)\sLocalIndex:\s\w+_(?<local>\d+).*?", // CreateKeyItemDistribution
-        r"(?:\s|.*?)*?",                                            // Discard
-        r"TryGetExisting.*?ZONE(?<alias>\d+).*?ri:\s(?<ri>\d+).*$", // TryGetExistingGenericFunctionDistributionForSession
-    ))
-    .unwrap()
-});
-
-pub static DISTRIBUTE_WARDEN_OBJECTIVE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"(?m)^.*?zone\sZONE(?<alias>\d+).*?Index:\s(?<idx>\d+).*\n.*?itemID:\s(?<item>\d+).*$",
-    )
-    .unwrap()
-});
+        r"(?:\s|.*?)*?",                                         // Discard
+        r"TryGetExisting.*?ZONE(?<alias>\d+).*?ri:\s(?<ri>\d+)", // TryGetExistingGenericFunctionDistributionForSession
+        r"(?:.*?\bArea[_\s]?(?<area>[A-Z])\b)?.*$",
+    ),
+);
+
+pub static DISTRIBUTE_WARDEN_OBJECTIVE: Pattern = Pattern::new(
+    "DISTRIBUTE_WARDEN_OBJECTIVE",
+    r"(?m)^.*?zone\sZONE(?<alias>\d+).*?Index:\s(?<idx>\d+).*\n.*?itemID:\s(?<item>\d+).*$",
+);
+
+/// Distributed objective items that have no seed, keyed off their `ItemIdentifier`:
+/// Cell (131), FogTurbine (133), Neonate (137), Cryo (148), Datasphere (151), HiSec (154)
+/// and Cargo (176)
+pub static DISTRIBUTE_NAMED_OBJECTIVE: Pattern = Pattern::new(
+    "DISTRIBUTE_NAMED_OBJECTIVE",
+    r"(?m)^.*?zone\sZONE(?<alias>\d+).*?Index:\s(?<idx>\d+).*\n.*?itemID:\s(?<item>131|133|137|148|151|154|176)\b.*$",
+);
+
+/// Items a gather objective asks for, i.e. `itemsToSpawn: [Count: 6] 149` for 6 GLP-1
+pub static WARDEN_OBJECTIVE_ITEMS: Pattern = Pattern::new(
+    "WARDEN_OBJECTIVE_ITEMS",
+    r"(?m)^.*LG_Distribute_WardenObjective.*?itemsToSpawn:\s\[Count:\s(?<count>\d+)\]\s(?<item>\d+).*$",
+);
+
+/// Serial the players see on a terminal or objective item, i.e. `CELL_4` or `TERMINAL_123`.
+/// Unverified, no real serial line is known yet
+pub static ITEM_SERIAL: Pattern = Pattern::unverified(
+    "ITEM_SERIAL",
+    r"(?m)^.*SerialGenerator.*?zone\sZONE_?(?<alias>\d+).*?serial:\s(?<serial>[A-Z][A-Z0-9_]*_\d+).*$",
+);
 
 /// FunctionMarkers batch items
-pub static FUNCTION_MARKERS_BATCH_START: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^.*Next\sBatch:\sFunctionMarkers.*$").unwrap());
-
-pub static FUNCTION_MARKERS_BATCH_END: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?m)^.*Last\sBatch:\sFunctionMarkers.*$").unwrap());
-
-pub static WARDEN_OBJECTIVE_MANAGER: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"^(?<gen>.*LG_PowerGenerator_Graphics.OnSyncStatusChanged.*)\s?(?:.*?Collection\s(?<id>\d+)\s.*?\s(?<name>\w+_\d+))?$"
-    )
-    .unwrap()
-});
-
-pub static GENERIC_SMALL_PICKUP_ITEM: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"(?m)^.*?Spawning\sPersonnel.*?Key:\s(?<container>[\w\d]+).*\n.*seed:\s(?<seed>\d+).*?\n.*PersonnelPickup_Core\..*$",
-    ).unwrap()
-});
-
-pub static BUILDER_END: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^.*BUILDER\s:\sBuildDone$").unwrap());
-
-/// Uncategorized
-pub static DISTRIBUTE_HSU: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^.*zone:\s(?<alias>\d+),\sArea:\s(?<id>\d+)_\w+\s(?<area>\w+).*$").unwrap()
-});
+pub static FUNCTION_MARKERS_BATCH_START: Pattern = Pattern::new(
+    "FUNCTION_MARKERS_BATCH_START",
+    r"(?m)^.*Next\sBatch:\sFunctionMarkers.*$",
+);
+
+pub static FUNCTION_MARKERS_BATCH_END: Pattern = Pattern::new(
+    "FUNCTION_MARKERS_BATCH_END",
+    r"(?m)^.*Last\sBatch:\sFunctionMarkers.*$",
+);
+
+/// Generator registered with its objective collection, `alias` is the zone if it's logged
+pub static WARDEN_OBJECTIVE_MANAGER: Pattern = Pattern::new(
+    "WARDEN_OBJECTIVE_MANAGER",
+    r"(?m)^.*LG_PowerGenerator_Graphics\.OnSyncStatusChanged.*?(?:ZONE_?(?<alias>\d+).*?)?Collection\s(?<id>\d+)\s.*?\s(?<name>\w+_\d+).*$",
+);
+
+/// Seeded pickup spawn, `kind` is the item name if the spawn line has one
+pub static GENERIC_SMALL_PICKUP_ITEM: Pattern = Pattern::new(
+    "GENERIC_SMALL_PICKUP_ITEM",
+    r"(?m)^.*?Spawning\sPersonnel(?:\s(?<kind>[\w-]+))?.*?Key:\s(?<container>[\w\d]+).*\n.*seed:\s(?<seed>\d+).*?\n.*PersonnelPickup_Core\..*$",
+);
+
+pub static BUILDER_END: Pattern = Pattern::new("BUILDER_END", r"(?m)^.*BUILDER\s:\sBuildDone.*$");
+
+/// Game state transitions, i.e. `GAMESTATEMANAGER CHANGE STATE FROM : InLevel TO: ExpeditionFail`.
+/// Guessed, no real state line is known yet, so checkpoint reloads aren't told from it
+pub static GAME_STATE_CHANGE: Pattern = Pattern::guessed(
+    "GAME_STATE_CHANGE",
+    r"(?m)^.*GAMESTATEMANAGER\sCHANGE\sSTATE\sFROM\s:\s(?<from>\w+)\sTO:\s(?<to>\w+).*$",
+);
+
+/// Checkpoint reload after a team wipe. Unverified, no real reload line is known yet, so
+/// reloads are told from the game state leaving `ExpeditionFail` for anything but the lobby
+pub static CHECKPOINT_RELOAD: Pattern = Pattern::unverified(
+    "CHECKPOINT_RELOAD",
+    r"(?m)^.*CheckpointManager\.\w*Reload\w*.*$",
+);
+
+/// HSU distributed inside the Distribution batch
+pub static DISTRIBUTE_HSU: Pattern = Pattern::new(
+    "DISTRIBUTE_HSU",
+    r"(?m)^.*zone:\s(?<alias>\d+),\sArea:\s(?<id>\d+)_\w+\s(?<area>\w+).*$",
+);
+
+/// Item picked up by a player during the run. Unverified, no real pickup line is known yet
+pub static ITEM_PICKUP: Pattern = Pattern::unverified(
+    "ITEM_PICKUP",
+    r"(?m)^.*?OnPickedUp.*?Item:\s(?<item>[\w\d_]+)\sZone:\s(?<zone>\d+)\sSeed:\s(?<seed>\d+).*$",
+);
+
+/// Special carry item placed during FunctionMarkers, like a neonate, cryo case, cargo crate
+/// or MWP. `area` is only there when the game logs which area it's in. Unverified, no real
+/// spawn line is known yet
+pub static CARRY_ITEM_SPAWN: Pattern = Pattern::unverified(
+    "CARRY_ITEM_SPAWN",
+    r"(?m)^.*?CarryItemPickup\w*\.Setup\w*.*?Item:\s(?<item>[\w\d_]+)\sZone:\s(?<alias>\d+)(?:.*?\bArea[_:\s]*(?<area>[A-Z])\b)?.*$",
+);
+
+/// Big item, like a cell or a turbine, picked up to be carried. These have no seed.
+/// Unverified, no real pickup line is known yet
+pub static CARRY_ITEM_PICKUP: Pattern = Pattern::unverified(
+    "CARRY_ITEM_PICKUP",
+    r"(?m)^.*?CarryItemPickup\w*\.OnPickedUp.*?Item:\s(?<item>[\w\d_]+)\sZone:\s(?<zone>\d+).*$",
+);
+
+/// Small pickup, like an ID or a GLP, taken from its container. Unverified, no real pickup
+/// line is known yet
+pub static SMALL_PICKUP_INTERACT: Pattern = Pattern::unverified(
+    "SMALL_PICKUP_INTERACT",
+    r"(?m)^.*?GenericSmallPickupItem\w*\.OnInteract.*?Item:\s(?<item>[\w\d_]+)\sZone:\s(?<zone>\d+)\sSeed:\s(?<seed>\d+).*$",
+);
+
+/// Game closing, possibly in the middle of a run. Guessed, no real quit line is known yet
+pub static APPLICATION_QUIT: Pattern =
+    Pattern::guessed("APPLICATION_QUIT", r"(?m)^.*OnApplicationQuit.*$");
+
+/// Security door into the zone with `alias` opened
+pub static SECURITY_DOOR_OPENED: Pattern = Pattern::new(
+    "SECURITY_DOOR_OPENED",
+    r"(?m)^.*LG_SecurityDoor.*?OnDoorIsOpened.*?ZONE_?(?<alias>\d+).*$",
+);
+
+/// Scan of the bulkhead door controller in the zone with `alias` finished. Unverified, no
+/// real scan line is known yet
+pub static BULKHEAD_SCAN_DONE: Pattern = Pattern::unverified(
+    "BULKHEAD_SCAN_DONE",
+    r"(?m)^.*LG_BulkheadDoorController.*?(?:OnScanDone|ScanComplete).*?ZONE_?(?<alias>\d+).*$",
+);
+
+/// Checkpoint stored when the team passes one, reloads are [`CHECKPOINT_RELOAD`].
+/// Unverified, no real checkpoint line is known yet
+pub static CHECKPOINT_ACTIVATED: Pattern = Pattern::unverified(
+    "CHECKPOINT_ACTIVATED",
+    r"(?m)^.*CheckpointManager\.(?:StoreCheckpoint|OnCheckpointActivated)\b.*$",
+);
+
+/// Builder or factory error during generation, like a zone linked to missing data. The
+/// level may have rerolled or be missing items, `anomaly` is the line without its time.
+/// Unverified, no real error line is known yet and the sample takes any builder line with
+/// an error word in it
+pub static GENERATION_ANOMALY: Pattern = Pattern::unverified(
+    "GENERATION_ANOMALY",
+    r"(?m)^(?:\d{2}:\d{2}:\d{2}\.\d+\s-\s)?(?<anomaly>.*?(?:LinkedToZoneData\sis\sNULL|(?:LG_Factory|Builder|BUILDER)\b.*?\b(?:[Ee]rror|ERROR|[Ff]ailed|FAILED|[Rr]etry\w*|RETRY\w*)\b).*?)\s*$",
+);
+
+/// Local player walked into the zone with `alias`, `area` is only there when the game logs
+/// which area of it. Unverified, no real line is known yet, so the player's zone is the one
+/// whose security door opened last
+pub static PLAYER_ZONE_ENTERED: Pattern = Pattern::unverified(
+    "PLAYER_ZONE_ENTERED",
+    r"(?m)^.*?(?:LocalPlayerAgent|PlayerAgent)\w*\.(?:OnCourseNodeChanged|OnAreaEntered|EnterArea)\b.*?ZONE_?(?<alias>\d+)(?:.*?\bArea[_:\s]*(?<area>[A-Z])\b)?.*$",
+);
+
+/// Security scan started, `alias` is the zone of the door it's on and `class` the alarm
+/// class, i.e. `Class III Alarm`, when the game logs them in that order. Unverified, no real
+/// scan line is known yet
+pub static SECURITY_SCAN_START: Pattern = Pattern::unverified(
+    "SECURITY_SCAN_START",
+    r"(?m)^.*?ChainedPuzzle\w*\.(?:Activate|AttemptInteract|StartScan)\w*\b(?:.*?ZONE_?(?<alias>\d+))?(?:.*?\b(?<class>Class\s[\w\s]+?Alarm))?.*$",
+);
+
+/// Error alarm started, the kind with waves until it's turned off. `alias` is only there
+/// when the game logs the zone. Unverified, no real alarm line is known yet
+pub static ERROR_ALARM: Pattern = Pattern::unverified(
+    "ERROR_ALARM",
+    r"(?m)^.*?\bError\s?Alarm\w*\b.*?\b(?:[Ss]tart|[Tt]rigger|[Aa]ctivat)\w*\b(?:.*?ZONE_?(?<alias>\d+))?.*$",
+);
+
+/// Terminal an uplink objective is placed on, `serial` is only there when the game logs the
+/// terminal's, i.e. `TERMINAL_343`. Unverified, the real distribution line isn't known yet
+/// and any line naming an uplink and a zone would put a terminal there
+pub static UPLINK_TERMINAL: Pattern = Pattern::unverified(
+    "UPLINK_TERMINAL",
+    r"(?m)^.*?\b\w*Uplink\w*\b.*?\bZONE_?(?<alias>\d+)\b(?:.*?\b(?<serial>TERMINAL_\d+)\b)?.*$",
+);
+
+/// Dimension named on a distribution line, to tell apart zones of portal levels that share
+/// an alias. Guessed, no real line naming a dimension is known yet
+pub static ZONE_DIMENSION: Pattern =
+    Pattern::guessed("ZONE_DIMENSION", r"\b(?<dim>Reality|Dimension_\d+)\b");
+
+/// Start of any builder batch, `batch` is its name, i.e. `SetupFloor`
+pub static BUILD_BATCH: Pattern =
+    Pattern::new("BUILD_BATCH", r"(?m)^.*?Next\sBatch:\s(?<batch>\w+).*$");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_capture_groups() {
+        let defaults: [(&str, &Regex); 39] = [
+            ("GAME_BUILD", &GAME_BUILD),
+            ("PLAYER_NAME", &PLAYER_NAME),
+            ("PLATFORM", &PLATFORM),
+            ("BUILDER_LEVEL_SEEDS", &BUILDER_LEVEL_SEEDS),
+            (
+                "DROP_SERVER_MANAGER_NEW_SESSION",
+                &DROP_SERVER_MANAGER_NEW_SESSION,
+            ),
+            ("SETUP_FLOOR_BATCH_START", &SETUP_FLOOR_BATCH_START),
+            ("SETUP_FLOOR_BATCH_END", &SETUP_FLOOR_BATCH_END),
+            ("ZONE_CREATED", &ZONE_CREATED),
+            ("ZONE_BUILD_FROM", &ZONE_BUILD_FROM),
+            ("DISTRIBUTION_BATCH_START", &DISTRIBUTION_BATCH_START),
+            ("DISTRIBUTION_BATCH_END", &DISTRIBUTION_BATCH_END),
+            (
+                "CREATE_KEY_ITEM_DISTRIBUTION",
+                &CREATE_KEY_ITEM_DISTRIBUTION,
+            ),
+            ("DISTRIBUTE_WARDEN_OBJECTIVE", &DISTRIBUTE_WARDEN_OBJECTIVE),
+            ("DISTRIBUTE_NAMED_OBJECTIVE", &DISTRIBUTE_NAMED_OBJECTIVE),
+            ("WARDEN_OBJECTIVE_ITEMS", &WARDEN_OBJECTIVE_ITEMS),
+            ("ITEM_SERIAL", &ITEM_SERIAL),
+            (
+                "FUNCTION_MARKERS_BATCH_START",
+                &FUNCTION_MARKERS_BATCH_START,
+            ),
+            ("FUNCTION_MARKERS_BATCH_END", &FUNCTION_MARKERS_BATCH_END),
+            ("WARDEN_OBJECTIVE_MANAGER", &WARDEN_OBJECTIVE_MANAGER),
+            ("GENERIC_SMALL_PICKUP_ITEM", &GENERIC_SMALL_PICKUP_ITEM),
+            ("BUILDER_END", &BUILDER_END),
+            ("GAME_STATE_CHANGE", &GAME_STATE_CHANGE),
+            ("CHECKPOINT_RELOAD", &CHECKPOINT_RELOAD),
+            ("DISTRIBUTE_HSU", &DISTRIBUTE_HSU),
+            ("ITEM_PICKUP", &ITEM_PICKUP),
+            ("APPLICATION_QUIT", &APPLICATION_QUIT),
+            ("SECURITY_DOOR_OPENED", &SECURITY_DOOR_OPENED),
+            ("BULKHEAD_SCAN_DONE", &BULKHEAD_SCAN_DONE),
+            ("CHECKPOINT_ACTIVATED", &CHECKPOINT_ACTIVATED),
+            ("CARRY_ITEM_PICKUP", &CARRY_ITEM_PICKUP),
+            ("CARRY_ITEM_SPAWN", &CARRY_ITEM_SPAWN),
+            ("GENERATION_ANOMALY", &GENERATION_ANOMALY),
+            ("PLAYER_ZONE_ENTERED", &PLAYER_ZONE_ENTERED),
+            ("SMALL_PICKUP_INTERACT", &SMALL_PICKUP_INTERACT),
+            ("SECURITY_SCAN_START", &SECURITY_SCAN_START),
+            ("ERROR_ALARM", &ERROR_ALARM),
+            ("UPLINK_TERMINAL", &UPLINK_TERMINAL),
+            ("ZONE_DIMENSION", &ZONE_DIMENSION),
+            ("BUILD_BATCH", &BUILD_BATCH),
+        ];
+
+        assert_eq!(defaults.len(), CAPTURE_GROUPS.len());
+        for (name, re) in defaults {
+            assert!(validate(name, re.as_str()).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn unverified_patterns_match_nothing() {
+        let re = never(ITEM_PICKUP.name());
+        assert!(validate(ITEM_PICKUP.name(), re.as_str()).is_ok());
+        assert!(
+            !re.is_match("20:06:00.000 - PlayerInventory.OnPickedUp Item: ID Zone: 50 Seed: 1234")
+        );
+        assert!(!never("BUILDER_END").is_match("BUILDER : BuildDone"));
+        assert!(!ITEM_PICKUP.is_verified());
+        assert!(!reads_pickups());
+    }
+
+    #[test]
+    fn override_with_same_groups_is_accepted() {
+        assert!(validate(
+            "BUILDER_LEVEL_SEEDS",
+            r"build=(?<build>\d+) host=(?<hostId>\d+) session=(?<session>\d+)"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn override_missing_group_is_rejected() {
+        assert!(validate("BUILDER_LEVEL_SEEDS", r"build=(?<build>\d+) (?<host>\d+)").is_err());
+        assert!(validate("BUILDER_END", r"(Build)Done").is_err());
+    }
+
+    #[test]
+    fn unknown_or_invalid_override_is_rejected() {
+        assert!(validate("NOT_A_PATTERN", r".*").is_err());
+        assert!(validate("BUILDER_END", r"(").is_err());
+    }
+}
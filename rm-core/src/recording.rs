@@ -0,0 +1,109 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::mpsc::{channel, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parser::ParserMsg;
+
+/// Message of a recording with when it arrived, in milliseconds since the first one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recorded {
+    pub offset_ms: u64,
+    pub msg: ParserMsg,
+}
+
+/// Writes parser messages to a file as one [`Recorded`] JSON object per line, so a run
+/// can be replayed with [`play`] without the game.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    started: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> anyhow::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            started: None,
+        })
+    }
+
+    /// Appends `msg`, flushed right away so a crash keeps everything before it.
+    pub fn record(&mut self, msg: &ParserMsg) -> anyhow::Result<()> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let recorded = Recorded {
+            offset_ms: started.elapsed().as_millis() as u64,
+            msg: msg.clone(),
+        };
+        serde_json::to_writer(&mut self.writer, &recorded)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Reads a recording written by [`Recorder`].
+pub fn load(path: &Path) -> anyhow::Result<Vec<Recorded>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+/// Sends the recorded messages again from another thread, with the gaps between them
+/// divided by `speed`. A `speed` of 0 sends them all at once.
+pub fn play(recording: Vec<Recorded>, speed: f32) -> Receiver<ParserMsg> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let started = Instant::now();
+        for Recorded { offset_ms, msg } in recording {
+            if speed > 0.0 {
+                let due = Duration::from_millis(offset_ms).div_f32(speed);
+                thread::sleep(due.saturating_sub(started.elapsed()));
+            }
+            if tx.send(msg).is_err() {
+                return;
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn recording_plays_back_in_order() {
+        let msgs = [
+            ParserMsg::NewFile,
+            ParserMsg::ZoneDoorOpened(50, None),
+            ParserMsg::Reset,
+        ];
+        let tmp = TempDir::new("recording");
+        let path = tmp.join("recording.jsonl");
+
+        let mut recorder = Recorder::create(&path).unwrap();
+        for msg in &msgs {
+            recorder.record(msg).unwrap();
+        }
+        drop(recorder);
+
+        let recording = load(&path).unwrap();
+        assert_eq!(recording[0].offset_ms, 0);
+        assert!(recording
+            .windows(2)
+            .all(|w| w[0].offset_ms <= w[1].offset_ms));
+        assert_eq!(play(recording, 0.0).iter().collect::<Vec<_>>(), msgs);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use log::{debug, error, info};
+use might_sleep::cpu_limiter::CpuLimiter;
+use serde::{Deserialize, Serialize};
+use tungstenite::{Message, WebSocket};
+
+use crate::{data::Level, parser::ParserMsg};
+
+/// Messages sent to overlay clients as JSON text frames, tagged like [`ParserMsg`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ServerMsg {
+    /// Every message the parser produced, as it comes in
+    Token(ParserMsg),
+    /// Current level state, `None` outside of a level. Also sent to new clients.
+    Level(Option<Box<Level>>),
+}
+
+/// Serves parser messages and the current level over WebSocket, for OBS browser sources
+/// and other overlays.
+///
+/// Clients only receive, anything they send is ignored. The server thread stops once the
+/// server is dropped.
+#[derive(Debug)]
+pub struct OverlayServer {
+    addr: SocketAddr,
+    tx: Option<Sender<ServerMsg>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl OverlayServer {
+    pub fn start(addr: SocketAddr) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        // Port 0 picks a free one.
+        let addr = listener.local_addr()?;
+        let (tx, rx) = channel::<ServerMsg>();
+
+        let handle = thread::Builder::new()
+            .name("overlay server".into())
+            .spawn(move || OverlayServer::serve_loop(listener, rx))?;
+
+        info!("Serving overlays on ws://{addr}");
+
+        Ok(Self {
+            addr,
+            tx: Some(tx),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn publish(&self, msg: &ParserMsg) {
+        self.send(ServerMsg::Token(msg.clone()));
+    }
+
+    pub fn set_level(&self, level: Option<&Level>) {
+        self.send(ServerMsg::Level(level.cloned().map(Box::new)));
+    }
+
+    fn send(&self, msg: ServerMsg) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(msg);
+        }
+    }
+
+    fn handshake(stream: TcpStream) -> anyhow::Result<WebSocket<TcpStream>> {
+        // Accepted streams inherit non-blocking, the handshake is easier done blocking.
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+        let ws = tungstenite::accept(stream).map_err(|e| anyhow::anyhow!("{e}"))?;
+        ws.get_ref().set_nonblocking(true)?;
+        Ok(ws)
+    }
+
+    /// Sends `text` and reads whatever the client sent, so pings get answered and closes
+    /// noticed. Returns `false` once the client is gone.
+    fn serve_client(ws: &mut WebSocket<TcpStream>, text: Option<&str>) -> bool {
+        let sent = match text {
+            Some(text) => ws.send(Message::text(text)),
+            None => ws.flush(),
+        };
+        if let Err(e) = sent {
+            if !is_would_block(&e) {
+                debug!("Dropping overlay client: {e}");
+                return false;
+            }
+        }
+
+        loop {
+            match ws.read() {
+                Ok(Message::Close(_)) => return false,
+                Ok(_) => {}
+                Err(e) if is_would_block(&e) => return true,
+                Err(e) => {
+                    debug!("Dropping overlay client: {e}");
+                    return false;
+                }
+            }
+        }
+    }
+
+    fn serve_loop(listener: TcpListener, rx: Receiver<ServerMsg>) {
+        let mut limiter = CpuLimiter::new(Duration::from_millis(25));
+        let mut clients: Vec<WebSocket<TcpStream>> = vec![];
+        // Sent to clients as they connect, so they don't wait for the next level update.
+        let mut level = to_text(&ServerMsg::Level(None));
+
+        loop {
+            let mut sent = false;
+            loop {
+                match rx.try_recv() {
+                    Ok(msg) => {
+                        let Some(text) = to_text(&msg) else {
+                            continue;
+                        };
+                        if matches!(msg, ServerMsg::Level(_)) {
+                            level = Some(text.clone());
+                        }
+
+                        clients.retain_mut(|ws| OverlayServer::serve_client(ws, Some(&text)));
+                        sent = true;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        info!("Overlay channel was disconnected, stopping server.");
+                        for ws in clients.iter_mut() {
+                            let _ = ws.close(None);
+                            let _ = ws.flush();
+                        }
+                        return;
+                    }
+                }
+            }
+
+            if !sent {
+                clients.retain_mut(|ws| OverlayServer::serve_client(ws, None));
+            }
+
+            // After the updates, so new clients start from the latest level.
+            loop {
+                match listener.accept() {
+                    Ok((stream, addr)) => match OverlayServer::handshake(stream) {
+                        Ok(mut ws) => {
+                            info!("Overlay client connected from {addr}");
+                            if OverlayServer::serve_client(&mut ws, level.as_deref()) {
+                                clients.push(ws);
+                            }
+                        }
+                        Err(e) => debug!("Overlay handshake with {addr} failed: {e}"),
+                    },
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(e) => {
+                        error!("Failed to accept overlay client: {e}");
+                        break;
+                    }
+                }
+            }
+
+            limiter.might_sleep();
+        }
+    }
+}
+
+impl Drop for OverlayServer {
+    fn drop(&mut self) {
+        self.tx.take();
+
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn to_text(msg: &ServerMsg) -> Option<String> {
+    serde_json::to_string(msg)
+        .map_err(|e| error!("Failed to serialize {msg:?}: {e}"))
+        .ok()
+}
+
+fn is_would_block(e: &tungstenite::Error) -> bool {
+    matches!(e, tungstenite::Error::Io(e) if e.kind() == ErrorKind::WouldBlock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_msg(ws: &mut WebSocket<tungstenite::stream::MaybeTlsStream<TcpStream>>) -> ServerMsg {
+        loop {
+            if let Message::Text(text) = ws.read().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn clients_get_level_and_tokens() {
+        let server = OverlayServer::start("127.0.0.1:0".parse().unwrap()).unwrap();
+        let level = Level {
+            exp_name: "A1".into(),
+            ..Default::default()
+        };
+        server.set_level(Some(&level));
+
+        let (mut ws, _) = tungstenite::connect(format!("ws://{}", server.addr())).unwrap();
+        assert_eq!(read_msg(&mut ws), ServerMsg::Level(Some(Box::new(level))));
+
+        server.publish(&ParserMsg::Checkpoint);
+        assert_eq!(read_msg(&mut ws), ServerMsg::Token(ParserMsg::Checkpoint));
+    }
+}
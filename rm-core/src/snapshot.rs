@@ -0,0 +1,165 @@
+use std::{fs, path::PathBuf};
+
+use crate::data::{ItemRow, Level};
+
+/// Template used when the config doesn't set one.
+pub const DEFAULT_TEMPLATE: &str = "{level}\n{progress}\n{objectives}\n{items}\n";
+
+/// Keeps a text file with the current level's items up to date, for OBS text sources or
+/// browser sources that reload a local page.
+///
+/// The template's `{level}`, `{seeds}`, `{progress}`, `{objectives}`, `{items}` and
+/// `{remaining}` are replaced with the level's, lists one entry per line. Files ending in
+/// `.html` or `.htm` get escaped values joined with `<br>`.
+#[derive(Debug, Clone)]
+pub struct SnapshotWriter {
+    path: PathBuf,
+    template: String,
+    /// Last text written, so unchanged levels aren't written again
+    last: Option<String>,
+}
+
+impl SnapshotWriter {
+    pub fn new(path: PathBuf, template: impl Into<String>) -> Self {
+        Self {
+            path,
+            template: template.into(),
+            last: None,
+        }
+    }
+
+    /// Writes the snapshot of `level` if it changed since the last write. The file is
+    /// replaced in one go, so readers never see half of it.
+    pub fn write(&mut self, level: Option<&Level>) -> anyhow::Result<()> {
+        let html = self
+            .path
+            .extension()
+            .is_some_and(|ext| ext == "html" || ext == "htm");
+        let text = render(&self.template, level, html);
+        if self.last.as_ref() == Some(&text) {
+            return Ok(());
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, &text)?;
+        fs::rename(&tmp, &self.path)?;
+        self.last = Some(text);
+
+        Ok(())
+    }
+}
+
+/// Fills in `template` for `level`, placeholders are empty without one.
+pub fn render(template: &str, level: Option<&Level>, html: bool) -> String {
+    let escape = |s: String| {
+        if html {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        } else {
+            s
+        }
+    };
+    let join = |lines: Vec<String>| {
+        let lines: Vec<String> = lines.into_iter().map(escape).collect();
+        lines.join(if html { "<br>\n" } else { "\n" })
+    };
+
+    let (name, seeds, progress, objectives, items, remaining) = match level {
+        Some(level) => {
+            let collected: Vec<String> = level.collected.iter().map(ToString::to_string).collect();
+            let rows = level.item_rows();
+            let line = |row: &ItemRow| match row.zone.as_str() {
+                "" => row.name.clone(),
+                // Cells are carried away from where they spawn, so they lead.
+                zone if row.kind == "Cell" => format!("{} → {zone}", row.name),
+                zone => format!("{zone}: {}", row.name),
+            };
+            let (collected_count, total) = level.progress();
+
+            (
+                level.to_string(),
+                level
+                    .seeds
+                    .map(|seeds| seeds.to_string())
+                    .unwrap_or_default(),
+                format!("{collected_count}/{total}"),
+                level
+                    .objectives
+                    .iter()
+                    .map(|objective| level.objective_summary(objective))
+                    .collect(),
+                rows.iter()
+                    .map(|row| {
+                        if collected.contains(&row.name) {
+                            format!("{} ✓", line(row))
+                        } else {
+                            line(row)
+                        }
+                    })
+                    .collect(),
+                rows.iter()
+                    .filter(|row| !collected.contains(&row.name))
+                    .map(line)
+                    .collect(),
+            )
+        }
+        None => Default::default(),
+    };
+
+    template
+        .replace("{level}", &escape(name))
+        .replace("{seeds}", &escape(seeds))
+        .replace("{progress}", &escape(progress))
+        .replace("{objectives}", &join(objectives))
+        .replace("{items}", &join(items))
+        .replace("{remaining}", &join(remaining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{GatherItem, Rundown, Zone};
+
+    #[test]
+    fn template_is_filled_in() {
+        let level = Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            gatherables: vec![
+                GatherItem::ID("Box<3>".into(), 12),
+                GatherItem::PD("Locker".into(), 34),
+            ],
+            collected: vec![GatherItem::PD("Locker".into(), 34)],
+            ..Default::default()
+        };
+        let template = "{level} {progress}\n{items}\n--\n{remaining}";
+
+        assert_eq!(
+            render(template, Some(&level), false),
+            "R1A1 1/2\nID Box<3> 12\nPD Locker 34 ✓\n--\nID Box<3> 12"
+        );
+        assert_eq!(
+            render("{items}", Some(&level), true),
+            "ID Box&lt;3&gt; 12<br>\nPD Locker 34 ✓"
+        );
+        assert_eq!(render(template, None, false), " \n\n--\n");
+    }
+
+    #[test]
+    fn cells_show_their_spawn_zone() {
+        let mut level = Level {
+            zones: vec![Zone {
+                layer: "MainLayer".into(),
+                ..Zone::placeholder(52)
+            }],
+            ..Default::default()
+        };
+        level.add_to_zone(52, GatherItem::Cell(3));
+
+        assert_eq!(
+            render("{items}", Some(&level), false),
+            "CELL_3 → ZONE_52 MainLayer Reality"
+        );
+    }
+}
@@ -0,0 +1,487 @@
+//! Tail and parser as tokio tasks, for embedding the core in async applications like web
+//! backends or bots. Messages come out of a [`Stream`] instead of an mpsc receiver that has
+//! to be polled, and the parser only runs when the tail has read something.
+
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::channel,
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use anyhow::bail;
+use log::{debug, error, info};
+use notify::RecommendedWatcher;
+use tokio::{
+    fs,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    task::{self, AbortHandle, JoinHandle},
+    time,
+};
+use tokio_stream::{wrappers::UnboundedReceiverStream, Stream};
+
+use crate::{
+    checkpoint::CheckpointFile,
+    config::{PollConfig, ResumeConfig},
+    discovery,
+    error::ParserError,
+    handler::LineHandler,
+    parser::{send_diagnostics, source_name, Parser, ParserMsg, TailParser},
+    profile,
+    tail::{OpenFrom, PollRate, TailCmd, TailLogs, TailMsg},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AsyncTail;
+
+impl AsyncTail {
+    /// Spawns the reader task on the current runtime, returning its command and data
+    /// channels and its handle. The file is read every `rate`, commands are handled as they
+    /// come in.
+    pub fn spawn(
+        rate: PollRate,
+    ) -> (
+        UnboundedSender<TailCmd>,
+        UnboundedReceiver<TailMsg>,
+        JoinHandle<anyhow::Result<()>>,
+    ) {
+        let (command_tx, command_rx) = unbounded_channel();
+        let (data_tx, data_rx) = unbounded_channel();
+
+        let handle = tokio::spawn(AsyncTail::tail_file(command_rx, data_tx, rate));
+
+        (command_tx, data_rx, handle)
+    }
+
+    pub async fn tail_file(
+        mut command_rx: UnboundedReceiver<TailCmd>,
+        data_tx: UnboundedSender<TailMsg>,
+        rate: PollRate,
+    ) -> anyhow::Result<()> {
+        let mut logs = TailLogs::default();
+        loop {
+            let cmd = tokio::select! {
+                cmd = command_rx.recv() => match cmd {
+                    Some(cmd) => Some(cmd),
+                    None => {
+                        debug!("Tail channel was disconnected");
+                        break;
+                    }
+                },
+                _ = time::sleep(rate.get()) => None,
+            };
+
+            // Logs are read like the threaded tail reads them, on the blocking pool tokio's
+            // own file IO runs on. Diagnostics are taken there too, before the thread changes.
+            let data_tx = data_tx.clone();
+            let running;
+            (logs, running) = task::spawn_blocking(move || {
+                let send = |msg| Ok(data_tx.send(msg)?);
+                let running = match cmd {
+                    Some(cmd) => logs.command(cmd, &send)?,
+                    None => true,
+                };
+                if running {
+                    logs.poll(&send)?;
+                }
+                anyhow::Ok((logs, running))
+            })
+            .await??;
+            if !running {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Async counterpart of [`Parser`]: watches a log folder and parses the latest log in it,
+/// with the messages coming out of the [`ParserStream`] that [`AsyncParser::start`] returns.
+#[derive(Debug)]
+pub struct AsyncParser {
+    watch_path: PathBuf,
+    open_from: OpenFrom,
+    poll: PollConfig,
+    resume: ResumeConfig,
+    /// Handed to the parser task when it starts
+    handlers: Vec<Box<dyn LineHandler>>,
+}
+
+impl AsyncParser {
+    pub fn new(watch_path: Option<PathBuf>) -> Self {
+        Self {
+            watch_path: watch_path.or_else(discovery::log_dir).unwrap_or_default(),
+            open_from: OpenFrom::Start,
+            poll: PollConfig::default(),
+            resume: ResumeConfig::default(),
+            handlers: vec![],
+        }
+    }
+
+    /// See [`Parser::add_handler`].
+    pub fn add_handler(&mut self, handler: Box<dyn LineHandler>) {
+        self.handlers.push(handler);
+    }
+
+    /// See [`Parser::set_tail_from_end`].
+    pub fn set_tail_from_end(&mut self, from_end: bool) {
+        self.open_from = if from_end {
+            OpenFrom::End
+        } else {
+            OpenFrom::Start
+        };
+    }
+
+    /// How often the tail reads the log, see [`Parser::set_poll`].
+    pub fn set_poll(&mut self, poll: PollConfig) {
+        self.poll = poll;
+    }
+
+    /// See [`Parser::set_resume`].
+    pub fn set_resume(&mut self, resume: ResumeConfig) {
+        self.resume = resume;
+    }
+
+    /// Spawns the tail, parser and folder watcher tasks on the current runtime. They stop
+    /// once the stream is dropped.
+    ///
+    /// A missing folder is reported as [`ParserError::WatchDirMissing`] on the stream, the
+    /// watcher keeps checking for it like it does when the folder goes away later.
+    pub fn start(self) -> ParserStream {
+        // Overrides have to be in place before the parser task compiles any pattern.
+        profile::load_user();
+
+        let rate = PollRate::new(self.poll.interval());
+        let (command_tx, data_rx, tail) = AsyncTail::spawn(rate.clone());
+        let (msg_tx, msg_rx) = unbounded_channel();
+
+        let checkpoint = CheckpointFile::new(&self.resume);
+        let parser = tokio::spawn(parse(
+            data_rx,
+            msg_tx,
+            self.poll,
+            rate,
+            checkpoint,
+            self.handlers,
+        ));
+        let watcher = tokio::spawn(watch(
+            self.watch_path,
+            self.open_from,
+            self.resume,
+            command_tx.clone(),
+        ));
+
+        ParserStream {
+            command_tx,
+            rx: UnboundedReceiverStream::new(msg_rx),
+            tasks: [
+                logged("tail", tail),
+                logged("parser", parser),
+                watcher.abort_handle(),
+            ],
+        }
+    }
+}
+
+/// Logs how a task ended, returning the handle to abort it with.
+fn logged(name: &'static str, handle: JoinHandle<anyhow::Result<()>>) -> AbortHandle {
+    let abort = handle.abort_handle();
+    tokio::spawn(async move {
+        match handle.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("{name} task failed: {e:?}"),
+            Err(e) if e.is_cancelled() => {}
+            Err(_) => error!("{name} task panicked"),
+        }
+    });
+    abort
+}
+
+/// Parser task, steps through the buffer whenever the tail sends something. With
+/// [`PollConfig::adaptive`] it sets `rate` for the tail as the log goes in and out of levels,
+/// with a `checkpoint` it writes how far it got every second.
+///
+/// Unlike the parser thread it only sends a [`ParserMsg::Status`] when the log changes, there
+/// is no loop to measure lines per second in.
+async fn parse(
+    mut data_rx: UnboundedReceiver<TailMsg>,
+    msg_tx: UnboundedSender<ParserMsg>,
+    poll: PollConfig,
+    rate: PollRate,
+    checkpoint: Option<CheckpointFile>,
+    handlers: Vec<Box<dyn LineHandler>>,
+) -> anyhow::Result<()> {
+    let mut tail = TailParser::new(checkpoint, handlers);
+    let mut checkpoints = time::interval(Duration::from_secs(1));
+    // The parser steps on a std channel, what it sent is passed on after each chunk.
+    let (parser_tx, parser_rx) = channel();
+
+    loop {
+        let msg = tokio::select! {
+            msg = data_rx.recv() => msg,
+            _ = checkpoints.tick() => {
+                tail.write_checkpoint();
+                continue;
+            }
+        };
+        let Some(msg) = msg else {
+            error!("Got disconnect from data channel");
+            tail.status.tail_alive = false;
+            msg_tx.send(ParserMsg::Status(tail.status))?;
+            break;
+        };
+
+        if !tail.receive(msg, &parser_tx)? {
+            break;
+        }
+        tail.manager.drain(&parser_tx)?;
+        tail.sources.drain(&parser_tx)?;
+        send_diagnostics(&parser_tx)?;
+        for msg in parser_rx.try_iter() {
+            msg_tx.send(msg)?;
+        }
+
+        if poll.adaptive {
+            rate.set(tail.manager.poll_interval(&poll));
+        }
+    }
+
+    Ok(())
+}
+
+async fn is_dir(path: &Path) -> bool {
+    fs::metadata(path).await.is_ok_and(|meta| meta.is_dir())
+}
+
+/// Watcher task, opens the latest log in `watch_path` and keeps a directory watcher on it.
+/// Like the parser's supervisor it re-creates the watcher when it dies, with an exponential
+/// backoff between failed attempts. Stops once the tail is gone.
+async fn watch(
+    watch_path: PathBuf,
+    open_from: OpenFrom,
+    resume: ResumeConfig,
+    command_tx: UnboundedSender<TailCmd>,
+) {
+    const MIN_BACKOFF: Duration = Duration::from_secs(1);
+    const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+    let failed = Arc::new(AtomicBool::new(false));
+    let mut watcher: Option<RecommendedWatcher> = None;
+    let mut backoff = MIN_BACKOFF;
+    let mut open_from = open_from;
+    let mut reported_missing = false;
+
+    while !command_tx.is_closed() {
+        let alive = watcher.is_some() && !failed.load(Ordering::Relaxed);
+        if alive && is_dir(&watch_path).await {
+            time::sleep(MIN_BACKOFF).await;
+            continue;
+        }
+        watcher = None;
+
+        if !is_dir(&watch_path).await {
+            if !reported_missing {
+                reported_missing = true;
+                let _ = command_tx.send(TailCmd::Report(ParserError::WatchDirMissing(
+                    watch_path.clone(),
+                )));
+            }
+            time::sleep(MIN_BACKOFF).await;
+            continue;
+        }
+        reported_missing = false;
+
+        // The game may already be running, in which case its log is already there. Logs
+        // found after a reconnect are new ones and read whole.
+        if let Some(path) = discovery::latest_log(&watch_path) {
+            info!("Found log {}", path.display());
+            let from = CheckpointFile::new(&resume).map_or(open_from, |checkpoint| {
+                checkpoint.open_from(&path, resume.from, open_from)
+            });
+            let _ = command_tx.send(TailCmd::Open(path, from));
+        }
+        open_from = OpenFrom::Start;
+
+        failed.store(false, Ordering::Relaxed);
+        let tx = command_tx.clone();
+        let send = move |cmd| {
+            let _ = tx.send(cmd);
+        };
+        match Parser::create_watcher(&watch_path, discovery::LOG_NAME, send, failed.clone()) {
+            Ok(created) => {
+                info!("Watching {}", watch_path.display());
+                watcher = Some(created);
+                backoff = MIN_BACKOFF;
+            }
+            Err(e) => {
+                error!("Failed to create watcher, retrying in {backoff:?}: {e}");
+                let _ = command_tx.send(TailCmd::Report(ParserError::WatcherFailed(e.to_string())));
+                time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Messages of an [`AsyncParser`], in the order the parser produced them. Dropping the
+/// stream stops its tasks.
+#[derive(Debug)]
+pub struct ParserStream {
+    command_tx: UnboundedSender<TailCmd>,
+    rx: UnboundedReceiverStream<ParserMsg>,
+    tasks: [AbortHandle; 3],
+}
+
+impl ParserStream {
+    /// See [`Parser::parse_file`].
+    pub fn parse_file(&self, path: PathBuf) -> anyhow::Result<()> {
+        if self.command_tx.send(TailCmd::Replay(path)).is_err() {
+            bail!("Tail task isn't running");
+        }
+
+        Ok(())
+    }
+
+    /// Asks the tail to pass on what it has read right away.
+    pub fn force_update(&self) -> anyhow::Result<()> {
+        if self.command_tx.send(TailCmd::ForceUpdate).is_err() {
+            bail!("Tail task isn't running");
+        }
+
+        Ok(())
+    }
+
+    /// See [`Parser::compare_with`].
+    pub fn compare_with(&self, path: PathBuf) -> anyhow::Result<String> {
+        let source = source_name(&path);
+        if self
+            .command_tx
+            .send(TailCmd::OpenSource(source.clone(), path))
+            .is_err()
+        {
+            bail!("Tail task isn't running");
+        }
+
+        Ok(source)
+    }
+
+    pub fn stop_comparing(&self, source: &str) -> anyhow::Result<()> {
+        if self
+            .command_tx
+            .send(TailCmd::CloseSource(source.into()))
+            .is_err()
+        {
+            bail!("Tail task isn't running");
+        }
+
+        Ok(())
+    }
+}
+
+impl Stream for ParserStream {
+    type Item = ParserMsg;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ParserMsg>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+impl Drop for ParserStream {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use super::*;
+    use crate::{checkpoint::Checkpoint, data::Seeds, handler::RegexHandler, testing::TempDir};
+
+    #[tokio::test]
+    async fn latest_log_is_streamed() {
+        let dir = TempDir::new("stream");
+        std::fs::write(
+            dir.join("GTFO.2024.06.01 20.03.13_NICKNAME_NETSTATUS.txt"),
+            include_str!("../tests/fixtures/seeds.log"),
+        )
+        .unwrap();
+
+        let mut stream = AsyncParser::new(Some(dir.path().to_path_buf())).start();
+        let seeds = time::timeout(Duration::from_secs(5), async {
+            while let Some(msg) = stream.next().await {
+                if let ParserMsg::LevelSeeds(seeds, _) = msg {
+                    return seeds;
+                }
+            }
+            panic!("stream ended without seeds");
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            seeds,
+            Seeds {
+                build: 1253890542,
+                host: 80125,
+                session: 7731,
+            }
+        );
+        drop(stream);
+    }
+
+    #[tokio::test]
+    async fn handlers_and_checkpoints_are_used() {
+        let dir = TempDir::new("stream-resume");
+        let log = dir.join("GTFO.2024.06.01 20.03.13_NICKNAME_NETSTATUS.txt");
+        let line = "20:03:18.001 - ReactorStartup wave: 3\n";
+        std::fs::write(&log, line).unwrap();
+        let checkpoint = dir.join("checkpoint.json");
+
+        let mut parser = AsyncParser::new(Some(dir.path().to_path_buf()));
+        parser.add_handler(Box::new(
+            RegexHandler::new("Reactor", r"ReactorStartup wave: (?<wave>\d+)").unwrap(),
+        ));
+        parser.set_resume(ResumeConfig {
+            enabled: true,
+            path: Some(checkpoint.clone()),
+            ..Default::default()
+        });
+        let mut stream = parser.start();
+        let custom = time::timeout(Duration::from_secs(5), async {
+            while let Some(msg) = stream.next().await {
+                if let ParserMsg::Custom(name, value) = msg {
+                    return (name, value);
+                }
+            }
+            panic!("stream ended without the handler's message");
+        })
+        .await
+        .unwrap();
+        assert_eq!(custom, ("Reactor".into(), serde_json::json!({"wave": "3"})));
+
+        // Written within a second, with nothing else logged.
+        let written = time::timeout(Duration::from_secs(5), async {
+            loop {
+                match Checkpoint::load_from(&checkpoint) {
+                    Ok(written) if written.offset > 0 => return written,
+                    _ => time::sleep(Duration::from_millis(100)).await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!((written.log, written.offset), (log, line.len() as u64));
+
+        drop(stream);
+    }
+}
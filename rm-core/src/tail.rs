@@ -1,66 +1,369 @@
 use std::{
-    fs::File,
-    io::{Read, Seek, SeekFrom},
-    path::PathBuf,
-    sync::mpsc::{channel, Receiver, Sender, TryRecvError},
-    thread,
-    time::Duration,
+    fs::{self, File, Metadata},
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
-use log::{debug, info};
+use log::{debug, error, info, warn};
 use might_sleep::cpu_limiter::CpuLimiter;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    archive,
+    diagnostics::{self, Diagnostic, Severity},
+    error::ParserError,
+};
+
+/// Where [`TailCmd::Open`] starts reading the file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OpenFrom {
+    #[default]
+    Start,
+    /// Skip everything already written, for attaching to a game that's been running
+    End,
+    /// Byte a [`crate::checkpoint::Checkpoint`] left off at, the end of shorter files
+    Offset(u64),
+}
+
 #[derive(Debug, Clone)]
 pub enum TailCmd {
-    Open(PathBuf),
+    Open(PathBuf, OpenFrom),
+    /// Reads a finished log once from the start, without tailing it afterwards. Every log in
+    /// a `.gz` or `.zip` is read, one after the other
+    Replay(PathBuf),
     ForceUpdate,
+    /// Tails another log from the start alongside the current one, e.g. the host's for
+    /// comparing runs. What it reads comes tagged with `source`, a source that's already
+    /// open is switched to the new path.
+    OpenSource(String, PathBuf),
+    CloseSource(String),
+    /// Error from outside the tail, passed on as [`TailMsg::Error`]
+    Report(ParserError),
     Stop,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TailMsg {
     Content(String),
-    NewFile,
+    /// Log was opened, `(path, byte reading starts at)`
+    NewFile(PathBuf, u64),
+    /// Log of a [`TailCmd::OpenSource`] was opened, or reopened after being truncated
+    NewSource(String, PathBuf),
+    /// Content of the log opened for a source, `(source, content)`
+    SourceContent(String, String),
+    /// Log was opened again after its reads failed, i.e. on a sharing violation. Reading
+    /// goes on from where it stopped, so it's still the same log
+    Reopened(PathBuf),
+    Error(ParserError),
+    Diagnostic(Diagnostic),
     Stop,
 }
 
+/// Poll interval of the tail and parser threads, shared so the parser can change it as
+/// levels come and go.
+#[derive(Debug, Clone)]
+pub struct PollRate(Arc<AtomicU64>);
+
+impl PollRate {
+    pub fn new(interval: Duration) -> Self {
+        Self(Arc::new(AtomicU64::new(interval.as_millis() as u64)))
+    }
+
+    pub fn get(&self) -> Duration {
+        Duration::from_millis(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, interval: Duration) {
+        self.0.store(interval.as_millis() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Default for PollRate {
+    fn default() -> Self {
+        PollRate::new(Duration::from_millis(250))
+    }
+}
+
+/// Lines of `partial` followed by `read` up to the last line break. What's after it is kept in
+/// `partial` until the rest of its line comes in, so a line is never split between reads.
+pub(crate) fn complete_lines(partial: &mut String, read: &str) -> String {
+    partial.push_str(read);
+    match partial.rfind('\n') {
+        Some(end) => {
+            let rest = partial.split_off(end + 1);
+            std::mem::replace(partial, rest)
+        }
+        None => String::new(),
+    }
+}
+
+/// Like [`complete_lines`] for bytes read from a file, the bytes after the last line break
+/// stay in `partial`. A character the game has only written part of is held back with the
+/// rest of its line, only whole lines are decoded.
+fn complete_bytes(partial: &mut Vec<u8>, read: &[u8]) -> String {
+    partial.extend_from_slice(read);
+    match partial.iter().rposition(|&b| b == b'\n') {
+        Some(end) => {
+            let rest = partial.split_off(end + 1);
+            String::from_utf8_lossy(&std::mem::replace(partial, rest)).into_owned()
+        }
+        None => String::new(),
+    }
+}
+
+/// Backoff of a log's failed reads. Reads are retried from a reopened file, i.e. for a
+/// sharing violation on Windows, for as long as the log is tailed.
+#[derive(Debug, Default)]
+struct ReadRetry {
+    /// Reads that failed in a row
+    failures: u32,
+    /// When to read again
+    at: Option<Instant>,
+}
+
+impl ReadRetry {
+    /// Wait after the first failed read, doubled for every one after it up to the longest
+    const BACKOFF: Duration = Duration::from_millis(100);
+    /// Failed reads in a row the log is reported as failing after, about 6 seconds of
+    /// retrying. It's retried at the longest backoff after that
+    const MAX_FAILURES: u32 = 6;
+
+    /// Whether the log shouldn't be read yet.
+    fn waiting(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() < at)
+    }
+
+    /// Whether the last read failed, so the file has to be opened again.
+    fn failing(&self) -> bool {
+        self.failures > 0
+    }
+
+    /// Schedules the next read after `e`. The error is given back once, when reads failed
+    /// too often in a row to be passing.
+    fn failed(&mut self, path: &Path, e: io::Error) -> io::Result<()> {
+        let backoff = Self::BACKOFF * 2u32.pow(self.failures.min(Self::MAX_FAILURES - 1));
+        self.failures += 1;
+        self.at = Some(Instant::now() + backoff);
+        if self.failures > Self::MAX_FAILURES {
+            debug!("Failed to read {} again: {e}", path.display());
+            return match self.failures == Self::MAX_FAILURES + 1 {
+                true => Err(e),
+                false => Ok(()),
+            };
+        }
+
+        warn!(
+            "Failed to read {}: {e}, retrying in {backoff:?}",
+            path.display()
+        );
+        diagnostics::report(
+            Severity::Warning,
+            format!(
+                "Failed to read {}, retrying in {backoff:?}: {e}",
+                path.display()
+            ),
+        );
+        Ok(())
+    }
+
+    fn succeeded(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Reads again on the next poll without waiting out the backoff, i.e. when the watcher
+    /// sends a failing log again.
+    fn retry_now(&mut self) {
+        self.at = None;
+    }
+}
+
+/// What [`OpenLog::poll`] read.
+#[derive(Debug, PartialEq)]
+enum LogRead {
+    /// What was added since the last read, empty if nothing was
+    Content(String),
+    /// Same, read after the file was opened again for failed reads
+    Reopened(String),
+    /// A read failed, the next one waits for the backoff
+    Retrying,
+}
+
+/// Log being tailed, with what's needed to notice it being truncated or replaced.
+#[derive(Debug)]
+struct OpenLog {
+    path: PathBuf,
+    file: File,
+    id: Option<FileId>,
+    /// Bytes read so far, a shorter file was truncated
+    read: u64,
+    /// Line the game is still writing, sent once its line break is read
+    partial: Vec<u8>,
+    retry: ReadRetry,
+}
+
+impl OpenLog {
+    fn open(path: &Path, from: OpenFrom) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let read = match from {
+            OpenFrom::Start => 0,
+            OpenFrom::End => {
+                let skipped = file.seek(SeekFrom::End(0))?;
+                info!("Skipping {skipped} bytes already in {}", path.display());
+                skipped
+            }
+            OpenFrom::Offset(offset) => {
+                let len = file.metadata()?.len();
+                let skipped = file.seek(SeekFrom::Start(offset.min(len)))?;
+                info!("Resuming {} at byte {skipped}", path.display());
+                skipped
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            id: file_id(&file.metadata()?),
+            file,
+            read,
+            partial: vec![],
+            retry: ReadRetry::default(),
+        })
+    }
+
+    /// Whether `path` is still the file this log was opened from.
+    fn is_same(&self, path: &Path) -> bool {
+        self.path == path && fs::metadata(path).is_ok_and(|meta| file_id(&meta) == self.id)
+    }
+
+    /// Complete lines added since the last read.
+    fn read_new(&mut self) -> io::Result<String> {
+        let mut buf = vec![];
+        self.read += self.file.read_to_end(&mut buf)? as u64;
+        Ok(complete_bytes(&mut self.partial, &buf))
+    }
+
+    /// Opens the file again at the byte it was read up to. A failed read may have moved the
+    /// old handle past bytes it didn't return.
+    fn reopen(&mut self) -> io::Result<()> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.read))?;
+        self.file = file;
+        Ok(())
+    }
+
+    /// Reads what was added, retrying failed reads with a [`ReadRetry`] backoff. The error is
+    /// only returned once reads failed too often, the log is still retried after it.
+    fn poll(&mut self) -> io::Result<LogRead> {
+        if self.retry.waiting() {
+            return Ok(LogRead::Retrying);
+        }
+
+        let reopening = self.retry.failing();
+        let read = match reopening {
+            true => self.reopen().and_then(|()| self.read_new()),
+            false => self.read_new(),
+        };
+        match read {
+            Ok(buf) => {
+                self.retry.succeeded();
+                Ok(match reopening {
+                    true => LogRead::Reopened(buf),
+                    false => LogRead::Content(buf),
+                })
+            }
+            Err(e) => {
+                self.retry.failed(&self.path, e)?;
+                Ok(LogRead::Retrying)
+            }
+        }
+    }
+
+    /// What happened to the file at the log's path since it was opened. A log that's gone
+    /// is left alone, the game may still be writing to it.
+    fn check(&self) -> Option<LogChange> {
+        let meta = fs::metadata(&self.path).ok()?;
+        if file_id(&meta) != self.id {
+            Some(LogChange::Recreated)
+        } else if meta.len() < self.read {
+            Some(LogChange::Truncated)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogChange {
+    Truncated,
+    /// Another file was put in its place
+    Recreated,
+}
+
+/// Tells files apart that had the same path. Device and inode on unix, elsewhere the
+/// creation time stands in for them.
+pub(crate) type FileId = (u64, u64);
+
+#[cfg(unix)]
+pub(crate) fn file_id(meta: &Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_id(meta: &Metadata) -> Option<FileId> {
+    let created = meta
+        .created()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some((created.as_secs(), created.subsec_nanos().into()))
+}
+
+/// Handle of the tail and parser threads.
+pub type ThreadHandle = JoinHandle<anyhow::Result<()>>;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Tail;
 
 impl Tail {
-    pub fn start_listen() -> anyhow::Result<(Sender<TailCmd>, Receiver<TailMsg>)> {
+    /// Spawns the reader thread, returning its command and data channels and its handle. The
+    /// file is read every `rate`.
+    pub fn start_listen(
+        rate: PollRate,
+    ) -> anyhow::Result<(Sender<TailCmd>, Receiver<TailMsg>, ThreadHandle)> {
         let (command_tx, command_rx) = channel::<TailCmd>();
         let (data_tx, data_rx) = channel::<TailMsg>();
 
-        thread::Builder::new()
+        let handle = thread::Builder::new()
             .name("tail file reader".into())
-            .spawn(|| Tail::tail_file(command_rx, data_tx))?;
+            .spawn(|| Tail::tail_file(command_rx, data_tx, rate))?;
 
-        Ok((command_tx, data_rx))
+        Ok((command_tx, data_rx, handle))
     }
 
     pub fn tail_file(
         command_rx: Receiver<TailCmd>,
         data_tx: Sender<TailMsg>,
+        rate: PollRate,
     ) -> anyhow::Result<()> {
-        let mut limiter = CpuLimiter::new(Duration::from_millis(250));
+        let mut limiter = CpuLimiter::new(rate.get());
+        let send = |msg| Ok(data_tx.send(msg)?);
 
-        let mut logfile: Option<File> = None;
+        let mut logs = TailLogs::default();
         loop {
             match command_rx.try_recv() {
-                Ok(val) => match val {
-                    TailCmd::Open(filepath) => {
-                        logfile.replace(File::open(filepath)?);
-                        data_tx.send(TailMsg::NewFile)?;
-                    }
-                    TailCmd::Stop => {
-                        data_tx.send(TailMsg::Stop)?;
-                        info!("Tail channel got command stop, stopping thread.");
+                Ok(cmd) => {
+                    if !logs.command(cmd, &send)? {
                         break;
                     }
-                    TailCmd::ForceUpdate => data_tx.send(TailMsg::Content("".into()))?,
-                },
+                }
                 Err(TryRecvError::Empty) => {}
                 Err(TryRecvError::Disconnected) => {
                     debug!("Tail channel was disconnected");
@@ -68,21 +371,434 @@ impl Tail {
                 }
             }
 
-            if let Some(ref mut file) = logfile {
-                let buf: &mut String = &mut Default::default();
+            logs.poll(&send)?;
+
+            limiter.duration = rate.get();
+            limiter.might_sleep();
+        }
+
+        Ok(())
+    }
+}
+
+/// Failing logs are reported and kept, they're read again once they can be.
+fn log_failed(
+    send: &impl Fn(TailMsg) -> anyhow::Result<()>,
+    path: &Path,
+    e: io::Error,
+) -> anyhow::Result<()> {
+    error!("Failed to read {}: {e}", path.display());
+    send(TailMsg::Error(ParserError::LogFailed(
+        path.to_path_buf(),
+        e.to_string(),
+    )))
+}
+
+/// Logs a tail reads, with what its commands do to them. Driven by the [`Tail`] thread and
+/// by the async tail, which only differ in how they wait between polls.
+#[derive(Debug, Default)]
+pub(crate) struct TailLogs {
+    logfile: Option<OpenLog>,
+    sources: Vec<(String, OpenLog)>,
+}
+
+impl TailLogs {
+    /// Carries out `cmd`, passing what comes of it to `send`. False once the tail has to
+    /// stop.
+    pub(crate) fn command(
+        &mut self,
+        cmd: TailCmd,
+        send: &impl Fn(TailMsg) -> anyhow::Result<()>,
+    ) -> anyhow::Result<bool> {
+        match cmd {
+            // The watcher sends the current log again when another one goes away.
+            TailCmd::Open(filepath, _)
+                if self
+                    .logfile
+                    .as_ref()
+                    .is_some_and(|log| log.is_same(&filepath)) =>
+            {
+                debug!("Already tailing {}", filepath.display());
+                self.logfile.as_mut().unwrap().retry.retry_now();
+            }
+            TailCmd::Open(filepath, from) => match OpenLog::open(&filepath, from) {
+                Ok(log) => {
+                    send(TailMsg::NewFile(filepath, log.read))?;
+                    self.logfile = Some(log);
+                }
+                Err(e) => log_failed(send, &filepath, e)?,
+            },
+            TailCmd::Replay(filepath) => match archive::read_logs(&filepath) {
+                Ok(logs) => {
+                    self.logfile = None;
+                    for (path, content) in logs {
+                        send(TailMsg::NewFile(path, 0))?;
+                        send(TailMsg::Content(content))?;
+                    }
+                }
+                Err(e) => log_failed(send, &filepath, e)?,
+            },
+            TailCmd::OpenSource(source, filepath) => {
+                self.sources.retain(|(open, _)| *open != source);
+                match OpenLog::open(&filepath, OpenFrom::Start) {
+                    Ok(log) => {
+                        self.sources.push((source.clone(), log));
+                        send(TailMsg::NewSource(source, filepath))?;
+                    }
+                    Err(e) => log_failed(send, &filepath, e)?,
+                }
+            }
+            TailCmd::CloseSource(source) => self.sources.retain(|(open, _)| *open != source),
+            TailCmd::Report(e) => send(TailMsg::Error(e))?,
+            TailCmd::Stop => {
+                send(TailMsg::Stop)?;
+                info!("Tail got command stop, stopping.");
+                return Ok(false);
+            }
+            TailCmd::ForceUpdate => send(TailMsg::Content("".into()))?,
+        }
+
+        Ok(true)
+    }
 
-                file.read_to_string(buf)?;
+    /// Reads what was added to the logs since the last poll. A log that was truncated or
+    /// replaced is read again from the start. Diagnostics reported on the way are sent last.
+    pub(crate) fn poll(
+        &mut self,
+        send: &impl Fn(TailMsg) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        if let Some(change) = self.logfile.as_ref().and_then(OpenLog::check) {
+            let path = self.logfile.take().map(|log| log.path).unwrap_or_default();
+            diagnostics::report(
+                Severity::Info,
+                format!("{} was {change:?}, reading it again", path.display()),
+            );
 
-                if !buf.is_empty() {
-                    data_tx.send(TailMsg::Content(buf.to_string()))?;
+            // Read like a new log, so the parser starts over instead of mixing both.
+            match OpenLog::open(&path, OpenFrom::Start) {
+                Ok(log) => {
+                    self.logfile = Some(log);
+                    send(TailMsg::NewFile(path, 0))?;
                 }
+                Err(e) => log_failed(send, &path, e)?,
+            }
+        }
 
-                file.seek(SeekFrom::Current(0))?;
+        if let Some(log) = &mut self.logfile {
+            match log.poll() {
+                Ok(LogRead::Content(buf)) if !buf.is_empty() => send(TailMsg::Content(buf))?,
+                Ok(LogRead::Reopened(buf)) => {
+                    send(TailMsg::Reopened(log.path.clone()))?;
+                    if !buf.is_empty() {
+                        send(TailMsg::Content(buf))?;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log_failed(send, &log.path, e)?,
             }
+        }
 
-            limiter.might_sleep();
+        // Sources are only read, a changed one starts over like the main log does.
+        let mut failed = vec![];
+        for (i, (source, log)) in self.sources.iter_mut().enumerate() {
+            if log.check().is_some() {
+                match OpenLog::open(&log.path, OpenFrom::Start) {
+                    Ok(reopened) => {
+                        *log = reopened;
+                        send(TailMsg::NewSource(source.clone(), log.path.clone()))?;
+                    }
+                    Err(e) => {
+                        log_failed(send, &log.path, e)?;
+                        failed.push(i);
+                        continue;
+                    }
+                }
+            }
+
+            match log.poll() {
+                Ok(LogRead::Content(buf) | LogRead::Reopened(buf)) if !buf.is_empty() => {
+                    send(TailMsg::SourceContent(source.clone(), buf))?
+                }
+                Ok(_) => {}
+                Err(e) => log_failed(send, &log.path, e)?,
+            }
+        }
+        for i in failed.into_iter().rev() {
+            self.sources.remove(i);
+        }
+
+        for diagnostic in diagnostics::take() {
+            send(TailMsg::Diagnostic(diagnostic))?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write};
+
+    use crate::testing::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn open_from_end_skips_existing_lines() {
+        let tmp = TempDir::new("tail");
+        let path = tmp.join("tail.log");
+        fs::write(&path, "old expedition\n").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::End))
+            .unwrap();
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::NewFile(..))
+        ));
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "new expedition").unwrap();
+
+        let content = loop {
+            match data_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                TailMsg::Content(content) if !content.is_empty() => break content,
+                _ => {}
+            }
+        };
+        assert_eq!(content, "new expedition\n");
+
+        command_tx.send(TailCmd::Stop).unwrap();
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn replay_reads_once() {
+        let tmp = TempDir::new("replay");
+        let path = tmp.join("replay.log");
+        fs::write(&path, "finished expedition\n").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx.send(TailCmd::Replay(path.clone())).unwrap();
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::NewFile(..))
+        ));
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::Content(content)) if content == "finished expedition\n"
+        ));
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "not tailed").unwrap();
+        command_tx.send(TailCmd::Stop).unwrap();
+
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::Stop)
+        ));
+        handle.join().unwrap().unwrap();
+    }
+
+    fn next_content(data_rx: &Receiver<TailMsg>) -> String {
+        loop {
+            match data_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                TailMsg::Content(content) if !content.is_empty() => break content,
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn lines_are_sent_whole() {
+        let tmp = TempDir::new("lines");
+        let path = tmp.join("lines.log");
+        fs::write(&path, "first line\nsecond ").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        assert_eq!(next_content(&data_rx), "first line\n");
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "line\nthird").unwrap();
+        assert_eq!(next_content(&data_rx), "second line\n");
+
+        command_tx.send(TailCmd::Stop).unwrap();
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn half_written_characters_wait_for_their_line() {
+        let tmp = TempDir::new("utf8");
+        let path = tmp.join("utf8.log");
+        // Cut in the middle of a character, like a write the game hasn't finished.
+        fs::write(&path, b"first line\n\xC3").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        assert_eq!(next_content(&data_rx), "first line\n");
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"\xA9\n").unwrap();
+        loop {
+            match data_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                TailMsg::Content(content) if !content.is_empty() => {
+                    break assert_eq!(content, "é\n")
+                }
+                TailMsg::Diagnostic(diagnostic) => {
+                    assert_ne!(diagnostic.severity, Severity::Warning)
+                }
+                TailMsg::Reopened(_) | TailMsg::Error(_) => panic!("read failed"),
+                _ => {}
+            }
+        }
+
+        command_tx.send(TailCmd::Stop).unwrap();
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn failed_read_reopens_the_log() {
+        let tmp = TempDir::new("reopen");
+        let path = tmp.join("reopen.log");
+        fs::write(&path, "first line\n").unwrap();
+
+        let mut log = OpenLog::open(&path, OpenFrom::Start).unwrap();
+        assert_eq!(log.poll().unwrap(), LogRead::Content("first line\n".into()));
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "second line").unwrap();
+        log.retry
+            .failed(&path, io::ErrorKind::PermissionDenied.into())
+            .unwrap();
+        assert_eq!(log.poll().unwrap(), LogRead::Retrying);
+        log.retry.retry_now();
+        assert_eq!(
+            log.poll().unwrap(),
+            LogRead::Reopened("second line\n".into())
+        );
+        assert!(!log.retry.failing());
+    }
+
+    #[test]
+    fn failing_reads_are_reported_once() {
+        let mut retry = ReadRetry::default();
+        let mut fail = || retry.failed(Path::new("GTFO.txt"), io::ErrorKind::Other.into());
+        for _ in 0..ReadRetry::MAX_FAILURES {
+            assert!(fail().is_ok());
+        }
+        assert!(fail().is_err());
+        assert!(fail().is_ok());
+
+        // Still retried, no later than the longest backoff.
+        let longest = ReadRetry::BACKOFF * 2u32.pow(ReadRetry::MAX_FAILURES - 1);
+        assert!(retry.waiting() && retry.failing());
+        assert!(retry.at.unwrap() <= Instant::now() + longest);
+
+        retry.succeeded();
+        assert!(!retry.waiting() && !retry.failing());
+    }
+
+    #[test]
+    fn truncated_log_is_read_again() {
+        let tmp = TempDir::new("truncate");
+        let path = tmp.join("truncate.log");
+        fs::write(&path, "first session, long enough to be cut\n").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        assert_eq!(
+            next_content(&data_rx),
+            "first session, long enough to be cut\n"
+        );
+
+        // Opening the same log again is a no-op.
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        fs::OpenOptions::new()
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"second\n")
+            .unwrap();
+
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::NewFile(..))
+        ));
+        assert_eq!(next_content(&data_rx), "second\n");
+
+        command_tx.send(TailCmd::Stop).unwrap();
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn sources_are_tailed_alongside() {
+        let tmp = TempDir::new("own");
+        let path = tmp.join("own.log");
+        let tmp = TempDir::new("host");
+        let host = tmp.join("host.log");
+        fs::write(&path, "own\n").unwrap();
+        fs::write(&host, "host\n").unwrap();
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        command_tx
+            .send(TailCmd::OpenSource("host".into(), host.clone()))
+            .unwrap();
+        assert_eq!(next_content(&data_rx), "own\n");
+
+        let mut file = fs::OpenOptions::new().append(true).open(&host).unwrap();
+        writeln!(file, "later").unwrap();
+        let mut content = String::new();
+        while content != "host\nlater\n" {
+            match data_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                TailMsg::NewSource(source, opened) => {
+                    assert_eq!((source.as_str(), opened), ("host", host.clone()))
+                }
+                TailMsg::SourceContent(source, read) => {
+                    assert_eq!(source, "host");
+                    content.push_str(&read);
+                }
+                _ => {}
+            }
+        }
+
+        command_tx.send(TailCmd::Stop).unwrap();
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn missing_log_is_reported() {
+        let tmp = TempDir::new("tail-missing");
+        let path = tmp.join("GTFO.txt");
+
+        let (command_tx, data_rx, handle) = Tail::start_listen(PollRate::default()).unwrap();
+        command_tx
+            .send(TailCmd::Open(path.clone(), OpenFrom::Start))
+            .unwrap();
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::Error(ParserError::LogFailed(failed, _))) if failed == path
+        ));
+
+        // Still running after the error.
+        command_tx.send(TailCmd::Stop).unwrap();
+        assert!(matches!(
+            data_rx.recv_timeout(Duration::from_secs(5)),
+            Ok(TailMsg::Stop)
+        ));
+        handle.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+//! Log excerpts of `NICKNAME_NETSTATUS`, with the messages the parser has to produce for
+//! them. The parser is a state machine, so every fixture is fed after the ones that come
+//! before it in a real log. `fixtures/README.md` says which lines are copied from real logs
+//! and which are only reconstructed.
+//!
+//! Whole runs live in `fixtures/runs`, each `.log` next to an `.expected` file with the
+//! messages it has to produce, one per line as they're displayed. `UPDATE_EXPECT=1 cargo
+//! test` writes them from what the parser produces now, to be checked by hand.
+
+use std::{fs, path::Path};
+
+use jiff::civil::Time;
+use rm_core::{
+    data::{GatherItem, Level, Rundown, Seeds, TimerEntry, Zone, ZoneKey},
+    parser::{line_time, parse_str, ParserMsg},
+    re,
+};
+
+const SEEDS: &str = include_str!("fixtures/seeds.log");
+const EXPEDITION: &str = include_str!("fixtures/expedition.log");
+const ZONES: &str = include_str!("fixtures/zones.log");
+const KEY_DISTRIBUTION: &str = include_str!("fixtures/key_distribution.log");
+const HSU: &str = include_str!("fixtures/hsu.log");
+const GENERATOR: &str = include_str!("fixtures/generator.log");
+
+fn seeds() -> ParserMsg {
+    ParserMsg::LevelSeeds(
+        Seeds {
+            build: 1253890542,
+            host: 80125,
+            session: 7731,
+        },
+        SEEDS.lines().next().and_then(line_time),
+    )
+}
+
+fn level() -> ParserMsg {
+    ParserMsg::LevelInit(Box::new(Level {
+        rundown: Rundown::R1,
+        exp_name: "A1".into(),
+        ..Default::default()
+    }))
+}
+
+/// Log time of a fixture line.
+fn at(time: &str) -> Option<Time> {
+    Some(time.parse().unwrap())
+}
+
+/// Key of a zone the fixtures generate in Reality's main layer.
+fn key(alias: u32) -> ZoneKey {
+    ZoneKey {
+        alias,
+        dimension: "Reality".into(),
+        layer: "MainLayer".into(),
+    }
+}
+
+/// Progress of the fixtures' build, the first build of a log doesn't know its total.
+fn progress(batch: u32) -> ParserMsg {
+    ParserMsg::BuildProgress(format!("Generating... (batch {batch})"))
+}
+
+fn zone(alias: u32, local: u32, time: &str) -> ParserMsg {
+    ParserMsg::GeneratedZone(
+        TimerEntry::Zone(Zone {
+            alias,
+            local,
+            dimension: "Reality".into(),
+            layer: "MainLayer".into(),
+            area: None,
+        }),
+        at(time),
+    )
+}
+
+#[test]
+fn seed_line() {
+    assert_eq!(parse_str(SEEDS), vec![seeds()]);
+}
+
+#[test]
+fn expedition_selection() {
+    assert_eq!(
+        parse_str(&[SEEDS, EXPEDITION].concat()),
+        vec![seeds(), level()]
+    );
+}
+
+#[test]
+fn zone_created_pair() {
+    assert_eq!(
+        parse_str(&[SEEDS, EXPEDITION, ZONES].concat()),
+        vec![
+            seeds(),
+            level(),
+            progress(1),
+            ParserMsg::GeneratedZone(TimerEntry::Start, at("20:03:19.031")),
+            zone(410, 0, "20:03:19.031"),
+            zone(411, 1, "20:03:19.035"),
+            ParserMsg::Connection(410, 411),
+            ParserMsg::GeneratedZone(TimerEntry::End, at("20:03:19.040")),
+        ]
+    );
+}
+
+#[test]
+fn key_distribution_block() {
+    let msgs = parse_str(&[SEEDS, EXPEDITION, ZONES, KEY_DISTRIBUTION].concat());
+
+    assert_eq!(
+        msgs[8..],
+        [
+            progress(2),
+            progress(3),
+            ParserMsg::Gatherable(
+                GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 411, 3),
+                at("20:03:19.051")
+            )
+        ]
+    );
+}
+
+#[test]
+fn key_area() {
+    let distribution = KEY_DISTRIBUTION.replace("ri: 3", "ri: 3 area: Area_C");
+    let msgs = parse_str(&[SEEDS, EXPEDITION, ZONES, &distribution].concat());
+
+    assert!(msgs.contains(&ParserMsg::AreaGatherable(
+        key(411).into(),
+        'C',
+        GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 411, 3),
+        at("20:03:19.051")
+    )));
+}
+
+#[test]
+fn hsu_line() {
+    let cap = re::DISTRIBUTE_HSU.captures(HSU.trim_end()).unwrap();
+
+    assert_eq!(&cap["alias"], "411");
+    assert_eq!(&cap["id"], "3");
+    assert_eq!(&cap["area"], "B");
+}
+
+#[test]
+fn hsu_in_distribution() {
+    let distribution = KEY_DISTRIBUTION.replace(
+        "20:03:19.060 - Last Batch: Distribution\n",
+        &format!("{HSU}20:03:19.060 - Last Batch: Distribution\n"),
+    );
+    let msgs = parse_str(&[SEEDS, EXPEDITION, ZONES, &distribution].concat());
+
+    assert!(msgs.contains(&ParserMsg::AreaGatherable(
+        key(411).into(),
+        'B',
+        GatherItem::HSU(3, 'B'),
+        at("20:03:19.057")
+    )));
+}
+
+#[test]
+fn generators_in_markers() {
+    let distribution = KEY_DISTRIBUTION.replace(
+        "20:03:19.080 - Last Batch: FunctionMarkers\n",
+        &format!("{GENERATOR}20:03:19.080 - Last Batch: FunctionMarkers\n"),
+    );
+    let msgs = parse_str(&[SEEDS, EXPEDITION, ZONES, &distribution].concat());
+
+    let generators: Vec<_> = msgs
+        .into_iter()
+        .filter(|msg| {
+            matches!(
+                msg,
+                ParserMsg::Gatherable(GatherItem::Generator(..), _)
+                    | ParserMsg::ZoneGatherable(_, GatherItem::Generator(..), _)
+            )
+        })
+        .collect();
+    assert_eq!(
+        generators,
+        vec![
+            ParserMsg::ZoneGatherable(
+                Box::new(412.into()),
+                GatherItem::Generator("GENERATOR_231".into(), 2, 0),
+                at("20:03:19.070")
+            ),
+            ParserMsg::Gatherable(
+                GatherItem::Generator("GENERATOR_232".into(), 2, 1),
+                at("20:03:19.070")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn whole_runs() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/runs");
+    let mut runs = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "log") {
+            continue;
+        }
+
+        let log = fs::read_to_string(&path).unwrap();
+        // Diagnostics and statuses depend on patterns and timing, not the log.
+        let msgs: Vec<String> = parse_str(&log)
+            .iter()
+            .filter(|msg| !matches!(msg, ParserMsg::Diagnostic(_) | ParserMsg::Status(_)))
+            .map(ToString::to_string)
+            .collect();
+        let expected_path = path.with_extension("expected");
+        if std::env::var_os("UPDATE_EXPECT").is_some_and(|update| update == "1") {
+            fs::write(&expected_path, msgs.join("\n") + "\n").unwrap();
+            runs += 1;
+            continue;
+        }
+        let Ok(expected) = fs::read_to_string(&expected_path) else {
+            panic!(
+                "no {}, run with UPDATE_EXPECT=1 to write it",
+                expected_path.display()
+            );
+        };
+        assert_eq!(
+            msgs,
+            expected.lines().collect::<Vec<_>>(),
+            "{}",
+            path.display()
+        );
+        runs += 1;
+    }
+    assert!(runs > 0, "no runs in {}", dir.display());
+}
@@ -0,0 +1,45 @@
+/*
+ * C ABI of rm-ffi, rusted mapper's log parser as a shared library.
+ *
+ * Messages are passed to the callback as nul-terminated UTF-8 JSON, adjacently tagged like
+ * {"type": "Gatherable", "data": ...}. The string is only valid during the call.
+ */
+
+#ifndef RM_FFI_H
+#define RM_FFI_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define RM_OK 0
+/* A pointer was null or a string wasn't UTF-8 */
+#define RM_INVALID_ARGUMENT -1
+/* The log couldn't be read */
+#define RM_IO_ERROR -2
+
+typedef void (*RmCallback)(const char *msg, void *user_data);
+
+typedef struct RmParser RmParser;
+typedef struct RmWatch RmWatch;
+
+/* Parser fed log lines by the caller, messages are passed to callback before the call
+ * that led to them returns. NULL if callback is NULL. */
+RmParser *rm_parser_new(RmCallback callback, void *user_data);
+void rm_parser_free(RmParser *parser);
+int rm_parse_line(RmParser *parser, const char *line);
+/* Parses the whole log at path. Its last line is parsed even without a line break, and
+ * bytes that aren't UTF-8 are replaced instead of failing like they do in rm_parse_line. */
+int rm_open_log(RmParser *parser, const char *path);
+
+/* Watches the log folder, GTFO's if dir is NULL, calling back from a thread of its own.
+ * NULL if the folder couldn't be watched or callback is NULL. No callback runs after
+ * rm_watch_stop returns. */
+RmWatch *rm_watch(const char *dir, RmCallback callback, void *user_data);
+void rm_watch_stop(RmWatch *watch);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
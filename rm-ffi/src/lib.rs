@@ -0,0 +1,292 @@
+//! C ABI over rm-core's parser, for mods and tools in other languages, i.e. C# with BepInEx.
+//! `include/rm_ffi.h` declares everything here.
+//!
+//! Messages are passed to a callback as JSON, the way [`ParserMsg`] serializes them, i.e.
+//! `{"type": "Gatherable", "data": ...}`. The string is only valid during the call.
+
+use std::{
+    ffi::{c_char, c_int, c_void, CStr, CString},
+    fs,
+    path::PathBuf,
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::RecvTimeoutError,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use log::error;
+use rm_core::{
+    parser::{LogParser, Parser, ParserMsg},
+    profile,
+};
+
+pub const RM_OK: c_int = 0;
+/// A pointer was null or a string wasn't UTF-8
+pub const RM_INVALID_ARGUMENT: c_int = -1;
+/// The log couldn't be read
+pub const RM_IO_ERROR: c_int = -2;
+
+/// Called with every message and the `user_data` it was registered with.
+pub type RmCallback = extern "C" fn(msg: *const c_char, user_data: *mut c_void);
+
+#[derive(Clone, Copy)]
+struct Sink {
+    callback: RmCallback,
+    user_data: *mut c_void,
+}
+
+// Callers of `rm_watch` vouch for `user_data` being usable from another thread.
+unsafe impl Send for Sink {}
+
+impl Sink {
+    fn send(&self, msg: &ParserMsg) {
+        let json = match serde_json::to_string(msg) {
+            Ok(json) => json,
+            Err(e) => {
+                error!("Failed to serialize {msg}: {e}");
+                return;
+            }
+        };
+        // JSON escapes control characters, so there's no nul in it.
+        let json = CString::new(json).unwrap();
+        (self.callback)(json.as_ptr(), self.user_data);
+    }
+}
+
+/// Borrows a nul-terminated UTF-8 string, `None` if it's null or not UTF-8.
+///
+/// # Safety
+///
+/// `s` has to be null or point to a nul-terminated string that outlives the borrow.
+unsafe fn str_arg<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Parser fed one log line at a time, see [`rm_parse_line`].
+pub struct RmParser {
+    parser: LogParser,
+    sink: Sink,
+}
+
+/// Creates a parser that calls `callback` with the messages of the lines it's given. The
+/// user's profiles and pattern overrides are loaded like the GUI does. Null if `callback` is
+/// null.
+#[no_mangle]
+pub extern "C" fn rm_parser_new(
+    callback: Option<RmCallback>,
+    user_data: *mut c_void,
+) -> *mut RmParser {
+    let Some(callback) = callback else {
+        return ptr::null_mut();
+    };
+    profile::load_user();
+
+    Box::into_raw(Box::new(RmParser {
+        parser: LogParser::new(),
+        sink: Sink {
+            callback,
+            user_data,
+        },
+    }))
+}
+
+/// # Safety
+///
+/// `parser` has to come from [`rm_parser_new`] and isn't valid afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn rm_parser_free(parser: *mut RmParser) {
+    if !parser.is_null() {
+        drop(Box::from_raw(parser));
+    }
+}
+
+/// Adds a line to the parser's log, calling back with every message it led to before
+/// returning. Levels span many lines, so most lines lead to nothing on their own.
+///
+/// # Safety
+///
+/// `parser` has to come from [`rm_parser_new`], `line` has to be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rm_parse_line(parser: *mut RmParser, line: *const c_char) -> c_int {
+    let (Some(parser), Some(line)) = (parser.as_mut(), str_arg(line)) else {
+        return RM_INVALID_ARGUMENT;
+    };
+
+    for msg in parser.parser.push_line(line) {
+        parser.sink.send(&msg);
+    }
+
+    RM_OK
+}
+
+/// Adds the whole log at `path` to the parser's log, calling back with every message it led
+/// to before returning. Its last line is parsed even without a line break, and bytes that
+/// aren't UTF-8 are replaced instead of failing like they do in [`rm_parse_line`].
+///
+/// # Safety
+///
+/// `parser` has to come from [`rm_parser_new`], `path` has to be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rm_open_log(parser: *mut RmParser, path: *const c_char) -> c_int {
+    let (Some(parser), Some(path)) = (parser.as_mut(), str_arg(path)) else {
+        return RM_INVALID_ARGUMENT;
+    };
+
+    let log = match fs::read(path) {
+        Ok(log) => log,
+        Err(e) => {
+            error!("Failed to read {path}: {e}");
+            return RM_IO_ERROR;
+        }
+    };
+    // A log that ends without a line break still ends with a line.
+    let mut msgs = parser.parser.push(&String::from_utf8_lossy(&log));
+    msgs.extend(parser.parser.finish());
+    for msg in msgs {
+        parser.sink.send(&msg);
+    }
+
+    RM_OK
+}
+
+/// Live messages of the GTFO logs, see [`rm_watch`].
+pub struct RmWatch {
+    parser: Parser,
+    stop: Arc<AtomicBool>,
+    forward: Option<JoinHandle<()>>,
+}
+
+/// Watches the log folder, GTFO's if `dir` is null, like the GUI does and calls `callback`
+/// with every message from a thread of its own. Null if the folder couldn't be watched or
+/// `callback` is null.
+///
+/// # Safety
+///
+/// `dir` has to be null or a nul-terminated string. `user_data` is used from the callback
+/// thread until [`rm_watch_stop`] returns.
+#[no_mangle]
+pub unsafe extern "C" fn rm_watch(
+    dir: *const c_char,
+    callback: Option<RmCallback>,
+    user_data: *mut c_void,
+) -> *mut RmWatch {
+    let Some(callback) = callback else {
+        return ptr::null_mut();
+    };
+    let dir = match dir.is_null() {
+        true => None,
+        false => match str_arg(dir) {
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => return ptr::null_mut(),
+        },
+    };
+
+    let mut parser = Parser::new(dir);
+    if let Err(e) = parser.start_watcher() {
+        error!("Failed to watch {}: {e}", parser.watch_path().display());
+        return ptr::null_mut();
+    }
+    let Some(rx) = parser.rx.take() else {
+        return ptr::null_mut();
+    };
+
+    let sink = Sink {
+        callback,
+        user_data,
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    let forward = thread::Builder::new()
+        .name("ffi callback".into())
+        .spawn(move || {
+            // Checks for `rm_watch_stop` every now and then, so no callback runs after it.
+            while !stopped.load(Ordering::Relaxed) {
+                match rx.recv_timeout(Duration::from_millis(100)) {
+                    Ok(msg) => sink.send(&msg),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+    let forward = match forward {
+        Ok(forward) => forward,
+        Err(e) => {
+            error!("Failed to start callback thread: {e}");
+            return ptr::null_mut();
+        }
+    };
+
+    Box::into_raw(Box::new(RmWatch {
+        parser,
+        stop,
+        forward: Some(forward),
+    }))
+}
+
+/// Stops watching, `callback` isn't called anymore once this returns.
+///
+/// # Safety
+///
+/// `watch` has to come from [`rm_watch`] and isn't valid afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn rm_watch_stop(watch: *mut RmWatch) {
+    if watch.is_null() {
+        return;
+    }
+
+    let mut watch = Box::from_raw(watch);
+    watch.stop.store(true, Ordering::Relaxed);
+    if let Some(forward) = watch.forward.take() {
+        let _ = forward.join();
+    }
+    watch.parser.shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn collect(msg: *const c_char, user_data: *mut c_void) {
+        let msgs = unsafe { &mut *(user_data as *mut Vec<serde_json::Value>) };
+        let msg = unsafe { CStr::from_ptr(msg) }.to_str().unwrap();
+        msgs.push(serde_json::from_str(msg).unwrap());
+    }
+
+    #[test]
+    fn lines_are_parsed_into_json() {
+        let mut msgs: Vec<serde_json::Value> = vec![];
+        assert!(rm_parser_new(None, ptr::null_mut()).is_null());
+        let parser = rm_parser_new(Some(collect), &mut msgs as *mut _ as *mut c_void);
+
+        let lines = [
+            c"20:03:18.001 - Builder.Build buildSeed: 12 hostIDSeed: 34 sessionSeed: 56",
+            c"20:03:18.002 - ServerManager: 'new session' rundown: Local_32, expedition: A1",
+        ];
+        for line in lines {
+            assert_eq!(unsafe { rm_parse_line(parser, line.as_ptr()) }, RM_OK);
+        }
+        assert_eq!(
+            unsafe { rm_parse_line(parser, ptr::null()) },
+            RM_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            unsafe { rm_open_log(parser, c"/nonexistent/rm-ffi.txt".as_ptr()) },
+            RM_IO_ERROR
+        );
+        unsafe { rm_parser_free(parser) };
+
+        let types: Vec<_> = msgs
+            .iter()
+            .map(|msg| msg["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["LevelSeeds", "LevelInit"]);
+        assert_eq!(msgs[0]["data"][0]["build"], 12);
+    }
+}
@@ -1,3 +1,3 @@
 fn main() {
     built::write_built_file().expect("Failed to acquire built-time information");
-}
\ No newline at end of file
+}
@@ -1,92 +1,982 @@
-use std::{iter::zip, sync::mpsc::TryRecvError, time::Duration};
+use std::{
+    iter::zip,
+    path::PathBuf,
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
 
-use egui::{scroll_area::ScrollBarVisibility, Align, Color32, Frame, ScrollArea};
-use log::debug;
+use egui::{
+    scroll_area::ScrollBarVisibility, viewport::ResizeDirection, Align, Color32, CursorIcon, Frame,
+    PointerButton, RichText, ScrollArea, Sense, ViewportCommand,
+};
+use jiff::civil::Time;
+use log::{debug, error, info, warn};
 use rm_core::{
-    data::{GatherItem, Level, TimerEntry},
-    parser::{Parser, ParserMsg},
+    chat::ChatTemplate,
+    config::Config,
+    control::{self, ControlCmd, ControlServer},
+    data::{
+        default_save_dir, elapsed, format_duration, layer_label, BestSplits, Bests, Comparison,
+        Delta, Dimension, ExpeditionDb, GatherItem, Level, LevelEvent, MapPack, SaveFormat,
+        SeedBreakdown, SeedDb, Seeds, Session, SessionInfo, SplitDelta, Splits, Splitter,
+        TimerEntry,
+    },
+    error::ParserError,
+    events::EventPublisher,
+    livesplit::LiveSplit,
+    locale::{Locale, DEFAULT_LOCALE},
+    parser::{Parser, ParserMsg, ParserStatus},
+    re,
+    recording::{self, Recorder},
+    server::OverlayServer,
+    snapshot::SnapshotWriter,
 };
 use serde::{self, Deserialize, Serialize};
 
-use crate::built_info;
+#[cfg(all(windows, feature = "tray"))]
+use crate::tray::{Tray, TrayAction};
+use crate::{
+    appearance::Appearance,
+    built_info,
+    compare::{CompareAction, CompareView},
+    filter::ItemFilter,
+    history::HistoryView,
+    hotkeys::{HotkeyAction, Hotkeys},
+    map::MapView,
+    parser_log::ParserLog,
+    presets::PresetEditor,
+    stats::StatsView,
+    Args,
+};
 
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
 pub struct Mapper {
     #[serde(skip)]
     parser: Parser,
+    /// Settings saved to the config file on exit
+    config: Config,
+    /// Seeds are logged before the level is selected and held until it is
+    #[serde(skip)]
+    pending_seeds: Option<Seeds>,
+    /// Levels played since the mapper started, the one being played last
+    #[serde(skip)]
+    session: Session,
+    /// Done level of the session shown in place of the live one
+    #[serde(skip)]
+    session_tab: Option<usize>,
+    /// Zone the local player is in, with the area when it's known
+    #[serde(skip)]
+    current_zone: Option<(u32, Option<char>)>,
+    /// The player changed zones since the level was last drawn
+    #[serde(skip)]
+    zone_changed: bool,
+    /// Follows the split preset of the current level, if it has one
+    #[serde(skip)]
+    splitter: Option<Splitter>,
+    /// Personal bests and gold splits of every expedition and preset
+    #[serde(skip)]
+    bests: Bests,
+    /// Map geometry shipped with the mapper or added by the user
+    #[serde(skip)]
+    maps: MapPack,
+    #[serde(skip)]
+    expeditions: ExpeditionDb,
+    /// Layouts of the build seeds played before
+    #[serde(skip)]
+    seed_db: SeedDb,
+    /// The current level shows the layout stored for its build seed, until the log has its
+    /// own
+    #[serde(skip)]
+    prefilled: bool,
+    /// Tells that the current build seed was played before, until the level is cleared
+    #[serde(skip)]
+    seen_seed: Option<String>,
+    /// How far the level's build is, until the level starts
+    #[serde(skip)]
+    build_progress: Option<String>,
+    /// Game build, platform and player from the header of the log being read
+    #[serde(skip)]
+    session_info: SessionInfo,
+    /// Strings of the configured language
+    #[serde(skip)]
+    locale: Locale,
+    /// Codes and names of the languages to pick from
+    #[serde(skip)]
+    locales: Vec<(String, String)>,
+    #[serde(skip)]
+    events: Option<EventPublisher>,
+    #[serde(skip)]
+    overlay: Option<OverlayServer>,
+    #[serde(skip)]
+    livesplit: Option<LiveSplit>,
+    #[serde(skip)]
+    snapshot: Option<SnapshotWriter>,
+    #[serde(skip)]
+    recorder: Option<Recorder>,
+    /// Messages of a recording, replayed instead of the parser's
+    #[serde(skip)]
+    playback: Option<Receiver<ParserMsg>>,
     #[serde(skip)]
-    scroll_to_bottom: bool,
+    hotkeys: Option<Hotkeys>,
+    /// Commands from scripts and StreamDeck buttons
     #[serde(skip)]
-    seeds: Option<[u32; 3]>,
+    control: Option<ControlServer>,
+    #[cfg(all(windows, feature = "tray"))]
     #[serde(skip)]
-    expedition: Option<Level>,
+    tray: Option<Tray>,
+    /// Hidden from the tray
+    #[cfg(all(windows, feature = "tray"))]
     #[serde(skip)]
-    gatherables: Vec<GatherItem>,
+    hidden: bool,
+    filter: ItemFilter,
+    map: MapView,
+    history: HistoryView,
+    presets: PresetEditor,
+    compare: CompareView,
+    stats: StatsView,
+    appearance: Appearance,
+    parser_log: ParserLog,
+    #[serde(skip)]
+    status: Option<String>,
+    /// Last error the parser reported, until it's dismissed
+    #[serde(skip)]
+    parser_error: Option<String>,
+    /// Problems with the current level's generation, shown until the level is cleared
+    #[serde(skip)]
+    level_warnings: Vec<String>,
+    #[serde(skip)]
+    last_watch_retry: Option<Instant>,
+    #[serde(skip)]
+    parser_status: Option<ParserStatus>,
+    #[serde(skip)]
+    parser_alive: bool,
+    /// When the current run's timer started
+    #[serde(skip)]
+    run_started: Option<Instant>,
+    /// Log time of the seed line, or of the first timestamped message of the run
+    #[serde(skip)]
+    run_clock: Option<Time>,
+    /// Saved level shown instead of the live one
+    #[serde(skip)]
+    viewing: Option<(PathBuf, Level)>,
+    #[serde(skip)]
+    file_error: Option<String>,
 }
 
 impl Default for Mapper {
     fn default() -> Self {
         Self {
             parser: Parser::new(None),
-            scroll_to_bottom: true,
-            seeds: None,
-            expedition: Default::default(),
-            gatherables: Default::default(),
+            config: Default::default(),
+            pending_seeds: None,
+            session: Default::default(),
+            session_tab: None,
+            current_zone: None,
+            zone_changed: false,
+            splitter: None,
+            bests: Default::default(),
+            maps: Default::default(),
+            expeditions: Default::default(),
+            seed_db: Default::default(),
+            prefilled: false,
+            seen_seed: None,
+            build_progress: None,
+            session_info: Default::default(),
+            locale: Locale::bundled(DEFAULT_LOCALE),
+            locales: vec![],
+            events: None,
+            overlay: None,
+            livesplit: None,
+            snapshot: None,
+            recorder: None,
+            playback: None,
+            hotkeys: None,
+            control: None,
+            #[cfg(all(windows, feature = "tray"))]
+            tray: None,
+            #[cfg(all(windows, feature = "tray"))]
+            hidden: false,
+            filter: Default::default(),
+            map: Default::default(),
+            history: Default::default(),
+            presets: Default::default(),
+            compare: Default::default(),
+            stats: Default::default(),
+            appearance: Default::default(),
+            parser_log: Default::default(),
+            status: None,
+            parser_error: None,
+            level_warnings: vec![],
+            last_watch_retry: None,
+            parser_status: None,
+            parser_alive: true,
+            run_started: None,
+            run_clock: None,
+            viewing: None,
+            file_error: None,
         }
     }
 }
 
 impl Mapper {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let mut s: Mapper = Default::default();
+    pub fn new(cc: &eframe::CreationContext<'_>, args: Args, config: Config) -> Self {
+        let mut s = Mapper {
+            locale: Locale::load(&config.language),
+            locales: Locale::available(),
+            config,
+            maps: MapPack::load(),
+            expeditions: ExpeditionDb::load(),
+            seed_db: SeedDb::load(),
+            bests: Bests::load(),
+            ..Default::default()
+        };
 
-        s.parser.start_watcher().unwrap();
+        if let Some(dir) = args.log_dir.or_else(|| s.config.log_dir.clone()) {
+            s.parser = Parser::new(Some(dir));
+        }
+        s.parser.set_tail_from_end(args.from_end);
+        s.parser.set_poll(s.config.poll);
+        s.parser.set_resume(s.config.resume.clone());
+        match &args.playback {
+            Some(path) => match recording::load(path) {
+                Ok(recording) => s.playback = Some(recording::play(recording, 1.0)),
+                Err(e) => s.file_error = Some(format!("{}: {e}", path.display())),
+            },
+            None => {
+                if let Err(e) = s.parser.start_watcher() {
+                    s.watcher_failed(e);
+                }
+            }
+        }
+
+        if let Some(path) = &args.record {
+            match Recorder::create(path) {
+                Ok(recorder) => s.recorder = Some(recorder),
+                Err(e) => error!("Failed to record to {}: {e}", path.display()),
+            }
+        }
+
+        if let Some(sink) = args.events.or_else(|| s.config.events.clone()) {
+            match EventPublisher::start(sink) {
+                Ok(publisher) => s.events = Some(publisher),
+                Err(e) => error!("Failed to start event publisher: {e}"),
+            }
+        }
+
+        if let Some(addr) = args.overlay {
+            match OverlayServer::start(addr) {
+                Ok(server) => s.overlay = Some(server),
+                Err(e) => error!("Failed to start overlay server: {e}"),
+            }
+        }
+
+        s.update_livesplit();
+
+        if let Some(path) = s.config.snapshot.path.clone() {
+            s.snapshot = Some(SnapshotWriter::new(path, &s.config.snapshot.template));
+        }
+
+        match Hotkeys::register(&s.config.hotkeys) {
+            Ok(hotkeys) => s.hotkeys = Some(hotkeys),
+            Err(e) => error!("Failed to set up hotkeys: {e}"),
+        }
+
+        if let Some(endpoint) = args.control {
+            // Commands may come in while the window is hidden, it doesn't repaint by itself then.
+            let ctx = cc.egui_ctx.clone();
+            let endpoint = endpoint.unwrap_or_else(control::default_endpoint);
+            match ControlServer::start(endpoint, move || ctx.request_repaint()) {
+                Ok(server) => s.control = Some(server),
+                Err(e) => error!("Failed to start control server: {e}"),
+            }
+        }
+
+        #[cfg(all(windows, feature = "tray"))]
+        match Tray::new(&cc.egui_ctx, &s.locale) {
+            Ok(tray) => s.tray = Some(tray),
+            Err(e) => error!("Failed to set up tray icon: {e}"),
+        }
 
         s
     }
+
+    /// Passes a message on to the publishers before handling it.
+    fn dispatch(&mut self, msg: ParserMsg) {
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(&msg) {
+                error!("Failed to record, stopping: {e}");
+                self.recorder = None;
+            }
+        }
+        if let Some(events) = &self.events {
+            events.publish(&msg);
+        }
+        if let Some(livesplit) = &self.livesplit {
+            livesplit.forward(&msg);
+        }
+        if let Some(overlay) = &self.overlay {
+            overlay.publish(&msg);
+        }
+        self.handle_msg(msg);
+        if let Some(overlay) = &self.overlay {
+            overlay.set_level(self.session.active());
+        }
+        if let Some(snapshot) = &mut self.snapshot {
+            if let Err(e) = snapshot.write(self.session.active()) {
+                error!("Failed to write snapshot, stopping: {e}");
+                self.snapshot = None;
+            }
+        }
+    }
+
+    fn on_hotkey(&mut self, action: HotkeyAction) {
+        debug!("Hotkey {action:?}");
+        match action {
+            HotkeyAction::Split => self.dispatch(ParserMsg::Split(Some(jiff::Zoned::now().time()))),
+            HotkeyAction::Reset => self.dispatch(ParserMsg::Reset),
+            HotkeyAction::Refresh => {
+                if let Err(e) = self.parser.force_update() {
+                    error!("Failed to refresh log: {e}");
+                }
+            }
+            HotkeyAction::ClickThrough => {
+                self.config.window.click_through = !self.config.window.click_through;
+            }
+        }
+    }
+
+    /// Runs a command from the control interface, the client gets the error if it fails.
+    fn on_control(&mut self, cmd: &ControlCmd) -> anyhow::Result<()> {
+        debug!("Control {cmd:?}");
+        match cmd {
+            ControlCmd::Reset => self.dispatch(ParserMsg::Reset),
+            ControlCmd::Open(path) if path.extension().is_some_and(|ext| ext == "json") => {
+                self.viewing = Some((path.clone(), Level::load(path)?));
+            }
+            ControlCmd::Open(path) => self.parser.parse_file(path.clone())?,
+            ControlCmd::Export(path) => {
+                let Some(level) = self.shown_level() else {
+                    anyhow::bail!("no level to export");
+                };
+                if path.extension().is_some_and(|ext| ext == "csv") {
+                    level.export_csv(path)?;
+                } else {
+                    level.export_json(path)?;
+                }
+                info!("Exported items of {level} to {}", path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(all(windows, feature = "tray"))]
+    fn on_tray(&mut self, ctx: &egui::Context, action: TrayAction) {
+        debug!("Tray {action:?}");
+        match action {
+            TrayAction::ToggleVisible => {
+                self.hidden = !self.hidden;
+                ctx.send_viewport_cmd(ViewportCommand::Visible(!self.hidden));
+            }
+            TrayAction::ReopenLog => {
+                if let Err(e) = self.parser.reopen_latest() {
+                    error!("Failed to re-open log: {e}");
+                }
+            }
+            TrayAction::ResetLevel => self.dispatch(ParserMsg::Reset),
+            TrayAction::Quit => ctx.send_viewport_cmd(ViewportCommand::Close),
+        }
+    }
+
+    /// Starts or stops forwarding splits to match the settings.
+    fn update_livesplit(&mut self) {
+        if !self.config.livesplit.enabled {
+            self.livesplit = None;
+            return;
+        }
+        if self
+            .livesplit
+            .as_ref()
+            .is_some_and(|l| l.addr() == self.config.livesplit.addr)
+        {
+            return;
+        }
+
+        // Drop the old connection before starting the new one.
+        self.livesplit = None;
+        match LiveSplit::start(self.config.livesplit.addr.clone()) {
+            Ok(livesplit) => self.livesplit = Some(livesplit),
+            Err(e) => {
+                error!("Failed to start LiveSplit forwarding: {e}");
+                self.config.livesplit.enabled = false;
+            }
+        }
+    }
+
+    fn watcher_failed(&mut self, e: anyhow::Error) {
+        if e.downcast_ref::<ParserError>().is_none() {
+            error!("Failed to start watcher: {e:?}");
+        }
+        self.status = Some(e.to_string());
+        self.last_watch_retry = Some(Instant::now());
+    }
+
+    /// Retries watching the log folder until GTFO creates it.
+    fn retry_watcher(&mut self) {
+        if self.parser.is_watching()
+            || self
+                .last_watch_retry
+                .is_some_and(|at| at.elapsed() < Duration::from_secs(2))
+        {
+            return;
+        }
+
+        match self.parser.watch_dir() {
+            Ok(_) => self.status = None,
+            Err(e) => self.watcher_failed(e),
+        }
+    }
+
+    fn status_bar(&mut self, ui: &mut egui::Ui) {
+        if let Some(e) = &self.parser_error {
+            let mut dismissed = false;
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(Color32::RED, e);
+                dismissed = ui.small_button("Dismiss").clicked();
+            });
+            if dismissed {
+                self.parser_error = None;
+            }
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            let status = self.parser_status.as_ref();
+
+            if !self.parser_alive {
+                ui.colored_label(Color32::RED, "Parser stopped, restart to continue");
+            } else if status.is_some_and(|s| !s.tail_alive) {
+                ui.colored_label(Color32::RED, "Tail stopped, restart to continue");
+            } else if let Some(file) = status.and_then(|s| s.file.as_ref()) {
+                let name = file.file_name().map_or_else(
+                    || file.display().to_string(),
+                    |n| n.to_string_lossy().into(),
+                );
+                ui.colored_label(
+                    Color32::GREEN,
+                    format!("{} {name}", self.locale.tr("status.tailing")),
+                );
+            } else {
+                ui.label(self.locale.tr("status.no_log"));
+            }
+
+            if let Some(start) = self.run_clock {
+                let elapsed = elapsed(start, jiff::Zoned::now().time());
+                ui.label(format!(
+                    "{} {}",
+                    self.locale.tr("status.elapsed"),
+                    format_duration(elapsed)
+                ));
+            }
+
+            if let Some(status) = status {
+                if let Some(time) = status.last_line {
+                    ui.label(format!("last line {}", time.strftime("%H:%M:%S")));
+                }
+                ui.label(format!("{:.1} lines/s", status.lines_per_sec));
+            }
+        });
+    }
+
+    /// Folder levels are saved to.
+    fn save_dir(&self) -> Option<PathBuf> {
+        self.config.save_dir.clone().or_else(default_save_dir)
+    }
+
+    fn open_level(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Level", &["json"])
+            .set_directory(self.save_dir().unwrap_or_default())
+            .pick_file()
+        else {
+            return;
+        };
+
+        self.view_level(path);
+    }
+
+    /// Shows a saved level in place of the live one.
+    fn view_level(&mut self, path: PathBuf) {
+        match Level::load(&path) {
+            Ok(level) => self.viewing = Some((path, level)),
+            Err(e) => self.file_error = Some(format!("{}: {e}", path.display())),
+        }
+    }
+
+    /// The saved level being viewed, the session's level of the picked tab or the live one.
+    fn shown_level(&self) -> Option<&Level> {
+        match &self.viewing {
+            Some((_, level)) => Some(level),
+            None => self.session.shown(self.session_tab),
+        }
+    }
+
+    /// Writes the item table of the shown level to a picked file.
+    fn export_items(&mut self, csv: bool) {
+        let Some(level) = self.shown_level() else {
+            return;
+        };
+        let (name, ext) = if csv {
+            ("CSV", "csv")
+        } else {
+            ("JSON", "json")
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(name, &[ext])
+            .set_file_name(format!(
+                "{}_items.{ext}",
+                level.file_stem(jiff::Zoned::now().datetime())
+            ))
+            .save_file()
+        else {
+            return;
+        };
+
+        let exported = if csv {
+            level.export_csv(&path)
+        } else {
+            level.export_json(&path)
+        };
+        match exported {
+            Ok(_) => info!("Exported items of {level} to {}", path.display()),
+            Err(e) => self.file_error = Some(format!("{}: {e}", path.display())),
+        }
+    }
+
+    /// Parses a finished log in place of the live one, or every log of an archived log folder
+    /// to add their runs to the history.
+    fn replay_log(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Log", &["txt", "gz", "zip"])
+            .set_directory(self.parser.watch_path())
+            .pick_file()
+        else {
+            return;
+        };
+
+        if let Err(e) = self.parser.parse_file(path.clone()) {
+            self.file_error = Some(format!("{}: {e}", path.display()));
+        }
+    }
+
+    /// Parses another log, e.g. the host's, alongside the live one to compare levels with.
+    fn compare_log(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Log", &["txt"])
+            .set_directory(self.parser.watch_path())
+            .pick_file()
+        else {
+            return;
+        };
+
+        match self.parser.compare_with(path.clone()) {
+            Ok(source) => self.compare.comparing(source),
+            Err(e) => self.file_error = Some(format!("{}: {e}", path.display())),
+        }
+    }
+
+    /// Exports the current level before it gets cleared. Failures are only logged.
+    fn save_level(&mut self) {
+        let Some(level) = self.session.active() else {
+            return;
+        };
+        let Some(dir) = self.save_dir() else {
+            error!("No folder to save {level} to");
+            return;
+        };
+
+        let dir = level.expedition_dir(&dir);
+        match level.export(&dir, self.config.save_format) {
+            Ok(files) => {
+                info!("Saved {level} to {files:?}");
+                self.history.refresh();
+                self.stats.refresh();
+            }
+            Err(e) => error!("Failed to save {level} to {}: {e}", dir.display()),
+        }
+    }
+
+    /// Takes the current run into the bests before it gets cleared.
+    fn record_bests(&mut self) {
+        let Some(level) = self.session.active() else {
+            return;
+        };
+        if level.splits.start.is_none() {
+            return;
+        }
+
+        let preset = self.splitter.as_ref().map_or(&[][..], Splitter::entries);
+        if self.bests.record(&level.to_string(), preset, &level.splits) {
+            info!("New personal best on {level}");
+        }
+        if let Err(e) = self.bests.save() {
+            error!("Failed to save bests: {e}");
+        }
+    }
+
+    /// Stores the current level's layout under its build seed before it gets cleared. A
+    /// layout that only came from the database isn't stored again.
+    fn record_seed(&mut self) {
+        let Some(level) = self.session.active() else {
+            return;
+        };
+        if self.prefilled {
+            return;
+        }
+
+        if self.seed_db.record(level, jiff::Zoned::now().datetime()) {
+            info!("Updated stored layout of {level}");
+        }
+        if let Err(e) = self.seed_db.save() {
+            error!("Failed to save seeds: {e}");
+        }
+    }
+
+    fn enter_zone(&mut self, alias: u32, area: Option<char>) {
+        if self.current_zone != Some((alias, area)) {
+            self.current_zone = Some((alias, area));
+            self.zone_changed = true;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.record_bests();
+        self.record_seed();
+        self.save_level();
+        self.run_started = None;
+        self.run_clock = None;
+        self.pending_seeds = None;
+        self.session.finish();
+        self.splitter = None;
+        self.current_zone = None;
+        self.level_warnings.clear();
+        self.prefilled = false;
+        self.seen_seed = None;
+        self.build_progress = None;
+    }
+
+    fn handle_msg(&mut self, msg: ParserMsg) {
+        if let ParserMsg::Sourced(source, msg) = &msg {
+            self.compare.push_theirs(source, msg);
+            return;
+        }
+        self.compare.push_ours(&msg);
+
+        // With a preset only its entries split, the timer still starts and stops as usual.
+        if let Some((entry, time)) = self.splitter.as_mut().and_then(|s| s.advance(&msg)) {
+            if let Some(level) = self.session.active_mut() {
+                if !matches!(entry, TimerEntry::Start | TimerEntry::End) {
+                    level.splits.split(entry, time);
+                }
+            }
+        }
+
+        // The log's own layout replaces a stored one as soon as it starts coming in.
+        if self.prefilled
+            && matches!(
+                msg,
+                ParserMsg::GeneratedZone(..)
+                    | ParserMsg::Connection(..)
+                    | ParserMsg::Gatherable(..)
+                    | ParserMsg::ZoneGatherable(..)
+                    | ParserMsg::AreaGatherable(..)
+                    | ParserMsg::Serial(..)
+            )
+        {
+            if let Some(level) = self.session.active_mut() {
+                level.clear_layout();
+            }
+            self.prefilled = false;
+        }
+
+        // The team goes through a door once it's open, into the zone behind it.
+        if let ParserMsg::ZoneDoorOpened(alias, _) = msg {
+            self.enter_zone(alias, None);
+        }
+
+        match msg {
+            ParserMsg::NewFile => {
+                self.session_info = SessionInfo::default();
+                self.reset();
+            }
+            ParserMsg::Reset => self.reset(),
+            ParserMsg::Session(info) => self.session_info = info,
+            ParserMsg::BuildProgress(progress) => self.build_progress = Some(progress),
+            ParserMsg::ZoneEntered(alias, area, _) => self.enter_zone(alias, area),
+            // Checkpoint reloads re-run parts of generation, the mapped level stays valid.
+            ParserMsg::Checkpoint => {
+                debug!("Checkpoint reloaded, keeping level");
+                if let Some(level) = self.session.active_mut() {
+                    level.outcome = None;
+                }
+            }
+            ParserMsg::LevelStart(time) => {
+                self.build_progress = None;
+                self.run_clock = self.run_clock.or(time);
+                if let Some(level) = self.session.active_mut() {
+                    level.started = time;
+                }
+                self.run_started = Some(Instant::now());
+            }
+            ParserMsg::Outcome(outcome) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.outcome = Some(outcome);
+                }
+            }
+            ParserMsg::LevelSeeds(seeds, time) => {
+                self.run_clock = self.run_clock.or(time);
+                match self.session.active_mut() {
+                    Some(level) => level.seeds = Some(seeds),
+                    None => self.pending_seeds = Some(seeds),
+                }
+            }
+            ParserMsg::LevelInit(level) => {
+                let maps = self.maps.get(&level).map(<[_]>::to_vec).unwrap_or_default();
+                let name = level.to_string();
+                self.splitter = self
+                    .config
+                    .split_presets
+                    .iter()
+                    .find(|preset| preset.level == name)
+                    .map(Splitter::new);
+                // Known objectives stand in until the log has the level's own.
+                let objectives = self
+                    .expeditions
+                    .get(&level)
+                    .map(|info| info.items.clone())
+                    .unwrap_or_default();
+                let seeds = self.pending_seeds.take();
+                let mut level = Level {
+                    seeds,
+                    maps,
+                    objectives,
+                    ..*level
+                };
+                // A build seed played before generates the same level, its stored layout is
+                // shown right away.
+                if let Some(seen) = seeds.and_then(|seeds| self.seed_db.get(&name, seeds.build)) {
+                    info!("Seen build seed {} on {name} before", seen.seeds.build);
+                    let layout = seen.layout.clone();
+                    level = Level {
+                        timer_zones: layout.timer_zones,
+                        zones: layout.zones,
+                        connections: layout.connections,
+                        gathatable_items: layout.gathatable_items,
+                        gatherables: layout.gatherables,
+                        serials: layout.serials,
+                        ..level
+                    };
+                    if !layout.objectives.is_empty() {
+                        level.objectives = layout.objectives;
+                    }
+                    self.prefilled = true;
+                    self.seen_seed = Some(format!(
+                        "{} {} ({}×)",
+                        self.locale.tr("level.seen_seed"),
+                        seen.seeds.build,
+                        seen.times
+                    ));
+                }
+                self.session.start(level);
+                self.session_tab = None;
+            }
+            ParserMsg::GeneratedZone(zone, time) => {
+                if let Some(level) = self.session.active_mut() {
+                    if let TimerEntry::Zone(z) = &zone {
+                        if let Some(time) = time {
+                            level.timeline.zones.push((z.alias, time));
+                        }
+                        level.zones.push(z.to_owned())
+                    }
+                    level.timer_zones.push(zone);
+                }
+            }
+            ParserMsg::Connection(from, to) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.connect(from, to);
+                }
+            }
+            ParserMsg::Gatherable(gatherable, time) => {
+                if let Some(level) = self.session.active_mut() {
+                    level
+                        .timeline
+                        .items
+                        .extend(time.map(|t| (gatherable.clone(), t)));
+                    level.gatherables.push(gatherable);
+                }
+            }
+            ParserMsg::ZoneGatherable(zone, gatherable, time) => {
+                if let Some(level) = self.session.active_mut() {
+                    level
+                        .timeline
+                        .items
+                        .extend(time.map(|t| (gatherable.clone(), t)));
+                    level.add_to_zone(*zone, gatherable);
+                }
+            }
+            ParserMsg::AreaGatherable(zone, area, gatherable, time) => {
+                if let Some(level) = self.session.active_mut() {
+                    level
+                        .timeline
+                        .items
+                        .extend(time.map(|t| (gatherable.clone(), t)));
+                    level.add_to_area(*zone, area, gatherable);
+                }
+            }
+            ParserMsg::Serial(alias, serial, _) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.serials.push((alias, serial));
+                }
+            }
+            ParserMsg::Objective(objective, _) => {
+                if let Some(level) = self.session.active_mut() {
+                    level
+                        .objectives
+                        .retain(|known| known.item != objective.item);
+                    level.objectives.push(objective);
+                }
+            }
+            ParserMsg::Collected(pickup, time) => {
+                if let Some(level) = self.session.active_mut() {
+                    if !level.collect(pickup.clone(), time) {
+                        debug!("Collected unmapped {pickup}");
+                    }
+                }
+            }
+            ParserMsg::LevelEvent(event, Some(time)) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.timeline.events.push((event, time));
+                }
+            }
+            ParserMsg::Status(status) => self.parser_status = Some(status),
+            ParserMsg::Error(e) => {
+                error!("Parser: {e}");
+                self.parser_error = Some(e.to_string());
+            }
+            ParserMsg::Diagnostic(diagnostic) => self.parser_log.push(diagnostic),
+            ParserMsg::Warning(warning, _) => {
+                warn!("{warning}");
+                self.level_warnings.push(warning);
+            }
+            ParserMsg::ElevatorDrop(Some(time)) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.splits.start(time);
+                }
+            }
+            ParserMsg::ZoneDoorOpened(alias, Some(time)) if self.splitter.is_none() => {
+                if let Some(level) = self.session.active_mut() {
+                    if !level.split_zone(alias, time) {
+                        debug!("No split for ZONE_{alias}");
+                    }
+                }
+            }
+            ParserMsg::BulkheadScanned(alias, Some(time)) if self.splitter.is_none() => {
+                if let Some(level) = self.session.active_mut() {
+                    let entry = TimerEntry::Custom(format!("Bulkhead ZONE_{alias}"));
+                    level.splits.split(entry, time);
+                }
+            }
+            ParserMsg::CheckpointActivated(Some(time)) if self.splitter.is_none() => {
+                if let Some(level) = self.session.active_mut() {
+                    let reached = level
+                        .splits
+                        .splits
+                        .iter()
+                        .filter(|(entry, _)| {
+                            matches!(entry, TimerEntry::Custom(name) if name.starts_with("Checkpoint"))
+                        })
+                        .count();
+                    let entry = TimerEntry::Custom(format!("Checkpoint {}", reached + 1));
+                    level.splits.split(entry, time);
+                }
+            }
+            ParserMsg::Split(Some(time)) if self.splitter.is_none() => {
+                if let Some(level) = self.session.active_mut() {
+                    let entry =
+                        TimerEntry::Custom(format!("Split {}", level.splits.splits.len() + 1));
+                    level.splits.split(entry, time);
+                }
+            }
+            ParserMsg::LevelFinish(Some(time)) => {
+                if let Some(level) = self.session.active_mut() {
+                    level.splits.end(time);
+                }
+            }
+            _ => {
+                debug!("{msg:?}");
+            }
+        }
+    }
 }
 
 impl eframe::App for Mapper {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        std::mem::replace(&mut self.parser, Parser::new(None)).shutdown();
+
+        if let Err(e) = self.config.save() {
+            error!("Failed to save config: {e}");
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.request_repaint_after(Duration::from_millis(25));
 
-        let data_msg = &self.parser.rx.as_ref().unwrap().try_recv();
-        match data_msg {
-            Ok(msg) => match msg {
-                ParserMsg::NewFile => {
-                    self.gatherables.clear();
-                    self.seeds = None;
-                    self.expedition = None;
-                }
-                ParserMsg::LevelSeeds(build_seed, host_seed, session_seed) => {
-                    self.seeds = Some([*build_seed, *host_seed, *session_seed]);
-                }
-                ParserMsg::LevelInit(level) => {
-                    self.expedition = Some(level.to_owned());
-                }
-                ParserMsg::GeneratedZone(zone) => {
-                    self.expedition
-                        .as_mut()
-                        .unwrap()
-                        .timer_zones
-                        .push(zone.to_owned());
-                    if let TimerEntry::Zone(z) = zone {
-                        self.expedition.as_mut().unwrap().zones.push(z.to_owned())
+        if self.playback.is_none() {
+            self.retry_watcher();
+        }
+
+        let pressed = self.hotkeys.as_ref().map(Hotkeys::pressed);
+        for action in pressed.unwrap_or_default() {
+            self.on_hotkey(action);
+        }
+        #[cfg(all(windows, feature = "tray"))]
+        for action in self.tray.as_ref().map(Tray::pressed).unwrap_or_default() {
+            self.on_tray(ctx, action);
+        }
+        let requests = self.control.as_ref().map(ControlServer::pending);
+        for request in requests.unwrap_or_default() {
+            let result = self.on_control(&request.cmd);
+            request.reply(result);
+        }
+
+        self.appearance.apply(ctx, &self.config.window);
+
+        ctx.input(|i| {
+            let viewport = i.viewport();
+            if let Some(rect) = viewport.outer_rect {
+                self.config.window.position = rect.min.into();
+            }
+            if let Some(rect) = viewport.inner_rect {
+                self.config.window.size = rect.size().into();
+            }
+        });
+
+        while let Some(rx) = &self.parser.rx {
+            match rx.try_recv() {
+                Ok(msg) => self.dispatch(msg),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.parser_alive {
+                        error!("Got disconnect from parser channel");
                     }
+                    self.parser_alive = false;
+                    break;
                 }
-                ParserMsg::Gatherable(gatherable) => {
-                    self.gatherables.push(gatherable.to_owned());
-                }
-                // ParserMsg::LevelStart => todo!(),
-                // ParserMsg::ZoneDoorOpened => todo!(),
-                // ParserMsg::LevelFinish => todo!(),
-                _ => {
-                    debug!("{msg:?}");
-                }
-            },
-            Err(TryRecvError::Empty) => {}
-            Err(TryRecvError::Disconnected) => debug!("Got disconnect from tail_data_rx"),
+            }
+        }
+        let played: Vec<ParserMsg> = self.playback.iter().flat_map(Receiver::try_iter).collect();
+        for msg in played {
+            self.dispatch(msg);
         }
 
         egui::TopBottomPanel::top("top_panel")
@@ -96,13 +986,138 @@ impl eframe::App for Mapper {
             })
             .show(ctx, |ui| {
                 egui::menu::bar(ui, |ui| {
-                    ui.menu_button("File", |ui| {
-                        if ui.button("Quit").clicked() {
+                    ui.menu_button(self.locale.tr("menu.file").to_owned(), |ui| {
+                        if ui.button(self.locale.tr("menu.open_level")).clicked() {
+                            ui.close_menu();
+                            self.open_level();
+                        }
+                        let has_level = self.shown_level().is_some();
+                        ui.add_enabled_ui(has_level, |ui| {
+                            ui.menu_button(self.locale.tr("menu.export_items").to_owned(), |ui| {
+                                if ui.button("CSV…").clicked() {
+                                    ui.close_menu();
+                                    self.export_items(true);
+                                }
+                                if ui.button("JSON…").clicked() {
+                                    ui.close_menu();
+                                    self.export_items(false);
+                                }
+                            });
+                        });
+                        if ui.button(self.locale.tr("menu.replay_log")).clicked() {
+                            ui.close_menu();
+                            self.replay_log();
+                        }
+                        if ui.button(self.locale.tr("menu.compare_log")).clicked() {
+                            ui.close_menu();
+                            self.compare.open = true;
+                            self.compare_log();
+                        }
+                        if ui.button(self.locale.tr("menu.quit")).clicked() {
                             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                         }
                     });
+                    ui.menu_button(self.locale.tr("menu.settings").to_owned(), |ui| {
+                        ui.label(self.locale.tr("menu.save_as"));
+                        ui.radio_value(&mut self.config.save_format, SaveFormat::Json, "JSON");
+                        ui.radio_value(
+                            &mut self.config.save_format,
+                            SaveFormat::Markdown,
+                            "Markdown",
+                        );
+                        ui.radio_value(
+                            &mut self.config.save_format,
+                            SaveFormat::Both,
+                            self.locale.tr("menu.both"),
+                        );
+                        if let Some(dir) = self.save_dir() {
+                            ui.label(format!(
+                                "{} {}",
+                                self.locale.tr("menu.save_to"),
+                                dir.display()
+                            ));
+                        }
+                        ui.separator();
+                        ui.label(format!(
+                            "{} {}",
+                            self.locale.tr("menu.watching"),
+                            self.parser.watch_path().display()
+                        ));
+                        if ui.button(self.locale.tr("menu.appearance")).clicked() {
+                            ui.close_menu();
+                            self.appearance.open = true;
+                        }
+                        ui.separator();
+                        let toggled = ui
+                            .checkbox(
+                                &mut self.config.livesplit.enabled,
+                                self.locale.tr("menu.livesplit"),
+                            )
+                            .changed();
+                        let edited = ui
+                            .horizontal(|ui| {
+                                ui.label(self.locale.tr("menu.server"));
+                                ui.text_edit_singleline(&mut self.config.livesplit.addr)
+                                    .lost_focus()
+                            })
+                            .inner;
+                        if toggled || edited {
+                            self.update_livesplit();
+                        }
+                        ui.separator();
+                        ui.label(self.locale.tr("menu.compare_with"));
+                        for comparison in Comparison::ALL {
+                            ui.radio_value(
+                                &mut self.config.comparison,
+                                comparison,
+                                comparison.to_string(),
+                            );
+                        }
+                        ui.separator();
+                        ui.label(self.locale.tr("menu.hotkeys"));
+                        let hotkeys = &self.config.hotkeys;
+                        for (action, binding) in [
+                            ("hotkey.split", &hotkeys.split),
+                            ("hotkey.reset", &hotkeys.reset),
+                            ("hotkey.refresh", &hotkeys.refresh),
+                            ("hotkey.click_through", &hotkeys.click_through),
+                        ] {
+                            let binding = match binding.as_str() {
+                                "" => self.locale.tr("hotkey.off"),
+                                binding => binding,
+                            };
+                            ui.label(format!("{}: {binding}", self.locale.tr(action)));
+                        }
+                        ui.separator();
+                        ui.label(self.locale.tr("menu.language"));
+                        let mut language = self.config.language.clone();
+                        for (code, name) in &self.locales {
+                            ui.radio_value(&mut language, code.clone(), name);
+                        }
+                        if language != self.config.language {
+                            self.locale = Locale::load(&language);
+                            self.config.language = language;
+                            #[cfg(all(windows, feature = "tray"))]
+                            if let Some(tray) = &self.tray {
+                                tray.set_locale(&self.locale);
+                            }
+                        }
+                    });
                     ui.add_space(8.0);
-                    ui.checkbox(&mut self.scroll_to_bottom, "Autoscroll to Bottom");
+                    ui.toggle_value(&mut self.map.open, self.locale.tr("view.map"));
+                    ui.toggle_value(&mut self.history.open, self.locale.tr("view.history"));
+                    ui.toggle_value(&mut self.presets.open, self.locale.tr("view.splits"));
+                    ui.toggle_value(&mut self.compare.open, self.locale.tr("view.compare"));
+                    ui.toggle_value(&mut self.stats.open, self.locale.tr("view.stats"));
+                    ui.checkbox(
+                        &mut self.config.autoscroll,
+                        self.locale.tr("view.autoscroll"),
+                    );
+                    ui.checkbox(
+                        &mut self.config.follow_zone,
+                        self.locale.tr("view.follow_zone"),
+                    );
+                    drag_handle(ui);
                 });
             });
 
@@ -124,29 +1139,125 @@ impl eframe::App for Mapper {
                                 ""
                             },
                         ));
+                        if !self.session_info.is_empty() {
+                            ui.label(format!("GTFO {}", self.session_info));
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(Align::Center), resize_grip);
                     });
+                    self.status_bar(ui);
                 });
             });
 
-        egui::CentralPanel::default()
+        egui::TopBottomPanel::bottom("parser_log_panel")
             .frame(Frame {
                 fill: Color32::TRANSPARENT,
                 ..Default::default()
             })
+            .show(ctx, |ui| self.parser_log.show(ui));
+
+        if let Some(e) = self.file_error.clone() {
+            egui::Window::new("File error")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(e);
+                    if ui.button("OK").clicked() {
+                        self.file_error = None;
+                    }
+                });
+        }
+
+        // Live updates keep coming in while a saved level or an earlier level of the session is
+        // open, they're just not shown.
+        let live = self.viewing.is_none() && self.session_tab.is_none();
+        let level = match &self.viewing {
+            Some((_, level)) => Some(level),
+            None => self.session.shown(self.session_tab),
+        };
+        // Saved levels don't say which preset they were run with.
+        let preset = self.splitter.as_ref().map_or(&[][..], Splitter::entries);
+        let best = match live {
+            true => level.and_then(|level| self.bests.get(&level.to_string(), preset)),
+            false => None,
+        };
+        let current = match live {
+            true => self.current_zone.map(|(alias, area)| CurrentZone {
+                alias,
+                area,
+                scroll: self.config.follow_zone && self.zone_changed,
+            }),
+            false => None,
+        };
+        let follows = current.is_some_and(|current| current.scroll);
+        self.zone_changed = false;
+        let mut close_viewing = false;
+        let mut picked_tab = None;
+        let mut toggled = None;
+
+        egui::CentralPanel::default()
+            .frame(Frame {
+                fill: Color32::from_black_alpha((self.config.window.opacity * 255.0) as u8),
+                ..Default::default()
+            })
             .show(ctx, |ui| {
                 ui.heading("Rusted Warden Mapper");
 
                 ui.separator();
 
-                if let Some(values) = &self.seeds {
-                    ui.vertical(|ui| {
-                        for (label, seed) in
-                            zip(vec!["Build Seed", "Host Seed", "Session Seed"], values)
-                        {
-                            ui.label(format!("{label}: {seed}"));
+                if let Some(status) = &self.status {
+                    ui.colored_label(Color32::YELLOW, status);
+                }
+
+                if live && !self.level_warnings.is_empty() {
+                    show_level_warnings(ui, &mut self.level_warnings);
+                }
+
+                if let Some(seen) = self.seen_seed.as_ref().filter(|_| live) {
+                    ui.colored_label(Color32::LIGHT_BLUE, seen);
+                }
+
+                if let Some(progress) = self.build_progress.as_ref().filter(|_| live) {
+                    ui.colored_label(Color32::LIGHT_BLUE, progress);
+                }
+
+                if self.viewing.is_none() && !self.session.done().is_empty() {
+                    picked_tab =
+                        show_session_tabs(ui, &self.session, self.session_tab, &self.locale);
+                }
+
+                if let Some((path, _)) = &self.viewing {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            format!("{} {}", self.locale.tr("level.viewing"), path.display()),
+                        );
+                        close_viewing = ui.button(self.locale.tr("level.close")).clicked();
+                    });
+                }
+
+                if let Some(seeds) = level.and_then(|level| level.seeds) {
+                    ui.horizontal(|ui| {
+                        ui.vertical(|ui| {
+                            for (label, seed) in zip(
+                                ["level.build_seed", "level.host_seed", "level.session_seed"],
+                                [seeds.build, seeds.host, seeds.session],
+                            ) {
+                                ui.label(format!("{}: {seed}", self.locale.tr(label)));
+                            }
+                        });
+                        if ui.button(self.locale.tr("level.copy")).clicked() {
+                            ui.output_mut(|o| o.copied_text = seeds.to_string());
                         }
                     });
                 }
+                if let Some(level) = level.filter(|level| level.seeds.is_some()) {
+                    show_seed_breakdown(ui, &SeedBreakdown::new(level), &self.locale);
+                }
+                if let Some(level) = level.filter(|level| !level.timeline.events.is_empty()) {
+                    show_events(ui, level, &self.locale);
+                }
+
+                self.filter.show(ui, &self.locale);
 
                 ScrollArea::vertical()
                     .auto_shrink(false)
@@ -155,52 +1266,764 @@ impl eframe::App for Mapper {
                         ui.with_layout(
                             egui::Layout::top_down(egui::Align::LEFT).with_cross_justify(true),
                             |ui| {
-                                if let Some(level) = &self.expedition {
-                                    ui.label(format!("Selected Expedition: {}", level));
-                                    for zone in &level.timer_zones {
-                                        match zone {
-                                            TimerEntry::Start => {
-                                                ui.label("Start");
+                                if let Some(level) = level {
+                                    ui.horizontal(|ui| {
+                                        match self.expeditions.get(level) {
+                                            Some(info) => {
+                                                let details = [
+                                                    info.objective.clone(),
+                                                    info.zones.map(|n| {
+                                                        format!(
+                                                            "{n} {}",
+                                                            self.locale.tr("level.zones")
+                                                        )
+                                                    }),
+                                                ];
+                                                let details: Vec<_> =
+                                                    details.into_iter().flatten().collect();
+                                                let response = ui.label(format!(
+                                                    "{}: {level} — {}",
+                                                    self.locale.tr("level.selected"),
+                                                    info.name
+                                                ));
+                                                if !details.is_empty() {
+                                                    response.on_hover_text(details.join(", "));
+                                                }
                                             }
-                                            TimerEntry::Zone(z) => {
+                                            None => {
                                                 ui.label(format!(
-                                                    "ZONE_{} {} {}",
-                                                    z.alias, z.layer, z.dimension
+                                                    "{}: {level}",
+                                                    self.locale.tr("level.selected")
                                                 ));
                                             }
-                                            TimerEntry::Custom(s) => {
-                                                ui.label(s);
-                                            }
-                                            TimerEntry::Invariance(_, _) => {}
-                                            TimerEntry::End => {
-                                                ui.label("End");
-                                            }
                                         }
+                                        let (collected, total) = level.progress();
+                                        if total > 0 {
+                                            ui.label(format!("{collected}/{total}"));
+                                        }
+                                        if let Some(outcome) = level.outcome {
+                                            ui.label(format!("({outcome})"));
+                                        } else if self.run_started.is_some() {
+                                            ui.colored_label(
+                                                Color32::GREEN,
+                                                self.locale.tr("level.ready"),
+                                            );
+                                        }
+                                        if ui.button(self.locale.tr("level.copy_text")).clicked() {
+                                            ui.output_mut(|o| o.copied_text = level.to_markdown());
+                                        }
+                                    });
+                                    show_splits(ui, &level.splits, best, self.config.comparison);
+                                    for objective in &level.objectives {
+                                        ui.label(level.objective_summary(objective));
                                     }
-                                    for gatherable in &self.gatherables {
-                                        match gatherable {
-                                            GatherItem::Key(_, dim, alias, _) => {
-                                                ui.label(format!(
-                                                    "{} {:?}",
-                                                    level[(alias.to_owned(), dim.to_owned())],
-                                                    gatherable
-                                                ));
-                                            }
-                                            GatherItem::Seeded(container, seed) => {
-                                                ui.label(format!("{} {}", container, seed));
-                                            }
-                                            other => {
-                                                ui.label(format!("{other:?}"));
-                                            }
+                                    self.filter.show_layers(ui, &level.layers(), &self.locale);
+                                    toggled = show_zones(
+                                        ui,
+                                        level,
+                                        &self.filter,
+                                        current,
+                                        &self.config.chat,
+                                        &self.locale,
+                                    );
+                                    if !level.unmapped_pickups.is_empty() {
+                                        ui.separator();
+                                        ui.label(self.locale.tr("level.unmapped"));
+                                        for pickup in &level.unmapped_pickups {
+                                            ui.label(RichText::new(pickup.to_string()).weak());
                                         }
                                     }
                                 }
                             },
                         );
-                        if self.scroll_to_bottom {
+                        if self.config.autoscroll && !follows {
                             ui.scroll_to_cursor(Some(Align::BOTTOM));
                         }
                     });
             });
+
+        if self.map.open {
+            self.map.show(ctx, level);
+        }
+
+        if let Some(item) = toggled {
+            let level = match &mut self.viewing {
+                Some((_, level)) => Some(level),
+                None => self.session.shown_mut(self.session_tab),
+            };
+            if let Some(level) = level {
+                level.toggle_collected(&item);
+            }
+        }
+
+        if close_viewing {
+            self.viewing = None;
+        }
+        if let Some(tab) = picked_tab {
+            self.session_tab = tab;
+        }
+
+        if self.presets.open {
+            let level = match &self.viewing {
+                Some((_, level)) => Some(level),
+                None => self.session.shown(self.session_tab),
+            };
+            self.presets
+                .show(ctx, level, &mut self.config.split_presets);
+        }
+
+        if self.compare.open {
+            match self.compare.show(ctx) {
+                Some(CompareAction::Open) => self.compare_log(),
+                Some(CompareAction::Close(source)) => {
+                    if let Err(e) = self.parser.stop_comparing(&source) {
+                        error!("Failed to stop comparing with {source}: {e}");
+                    }
+                    self.compare.stop();
+                }
+                None => {}
+            }
+        }
+
+        if self.appearance.open {
+            self.appearance.show(ctx, &mut self.config.window);
+        }
+
+        if self.stats.open {
+            let dir = self.save_dir();
+            self.stats.show(ctx, self.session.active(), dir.as_deref());
+        }
+
+        if self.history.open {
+            let dir = self.save_dir();
+            if let Some(path) = self.history.show(ctx, dir.as_deref()) {
+                self.view_level(path);
+            }
+        }
+    }
+}
+
+/// Moves the undecorated window when the rest of the menu bar is dragged.
+fn drag_handle(ui: &mut egui::Ui) {
+    let rect = ui.available_rect_before_wrap();
+    let response = ui
+        .interact(rect, ui.id().with("drag_handle"), Sense::drag())
+        .on_hover_cursor(CursorIcon::Grab);
+    if response.drag_started_by(PointerButton::Primary) {
+        ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
+    }
+}
+
+/// Corner grip resizing the undecorated window.
+fn resize_grip(ui: &mut egui::Ui) {
+    let response = ui
+        .add(egui::Label::new(RichText::new("◢").weak()).sense(Sense::drag()))
+        .on_hover_cursor(CursorIcon::ResizeNwSe);
+    if response.drag_started_by(PointerButton::Primary) {
+        ui.ctx()
+            .send_viewport_cmd(ViewportCommand::BeginResize(ResizeDirection::SouthEast));
+    }
+}
+
+/// Banner over the level telling that its generation went wrong, so a partial item list
+/// isn't taken for the whole level. Dismissing it clears the warnings.
+fn show_level_warnings(ui: &mut egui::Ui, warnings: &mut Vec<String>) {
+    let mut dismissed = false;
+    Frame::group(ui.style())
+        .fill(Color32::from_rgb(90, 40, 0))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    Color32::YELLOW,
+                    RichText::new("⚠ Level may have rerolled or the log is incomplete").strong(),
+                );
+                dismissed = ui.small_button("Dismiss").clicked();
+            });
+            for warning in warnings.iter() {
+                ui.label(RichText::new(warning).weak());
+            }
+        });
+    if dismissed {
+        warnings.clear();
+    }
+}
+
+/// Collapsed list of the item rolls of each zone, with a button copying all of them
+/// alongside the level seeds.
+fn show_seed_breakdown(ui: &mut egui::Ui, breakdown: &SeedBreakdown, locale: &Locale) {
+    ui.collapsing(locale.tr("level.seed_breakdown"), |ui| {
+        if ui.button(locale.tr("level.copy_seeds")).clicked() {
+            ui.output_mut(|o| o.copied_text = breakdown.to_text());
+        }
+        for zone in &breakdown.zones {
+            let heading = format!("ZONE_{} {}", zone.alias, zone.dimension.label());
+            ui.label(RichText::new(heading).strong());
+            for seed in &zone.seeds {
+                ui.label(RichText::new(seed.to_string()).monospace());
+            }
+        }
+        if !breakdown.unzoned.is_empty() {
+            ui.label(RichText::new(locale.tr("level.unknown_zone")).strong());
+            for seed in &breakdown.unzoned {
+                ui.label(RichText::new(seed.to_string()).monospace());
+            }
+        }
+    });
+}
+
+/// Tabs of the session's done levels and the live one, the tab picked if one was clicked.
+fn show_session_tabs(
+    ui: &mut egui::Ui,
+    session: &Session,
+    tab: Option<usize>,
+    locale: &Locale,
+) -> Option<Option<usize>> {
+    let mut picked = None;
+    ui.horizontal_wrapped(|ui| {
+        for (i, level) in session.done().iter().enumerate() {
+            let label = match level.outcome {
+                Some(outcome) => format!("{level} ({outcome})"),
+                None => level.to_string(),
+            };
+            if ui.selectable_label(tab == Some(i), label).clicked() {
+                picked = Some(Some(i));
+            }
+        }
+        let current = match session.active() {
+            Some(level) => format!("{} {level}", locale.tr("level.current")),
+            None => locale.tr("level.current").to_string(),
+        };
+        if ui.selectable_label(tab.is_none(), current).clicked() {
+            picked = Some(None);
+        }
+    });
+
+    picked
+}
+
+/// Collapsed timeline of the scans and alarms of the run, timed from the drop, with a button
+/// copying it for syncing with a VOD.
+fn show_events(ui: &mut egui::Ui, level: &Level, locale: &Locale) {
+    ui.collapsing(locale.tr("level.events"), |ui| {
+        if ui.button(locale.tr("level.copy_text")).clicked() {
+            ui.output_mut(|o| o.copied_text = level.events_text());
+        }
+        for (since, event) in level.events_since_drop() {
+            ui.horizontal(|ui| {
+                let since = since.map_or("--:--:--".to_string(), format_duration);
+                ui.label(RichText::new(since).monospace());
+                let color = match event {
+                    LevelEvent::ErrorAlarm(_) => Color32::LIGHT_RED,
+                    LevelEvent::Scan(_, Some(_)) => Color32::YELLOW,
+                    LevelEvent::Scan(_, None) => ui.visuals().text_color(),
+                };
+                ui.colored_label(color, event.to_string());
+            });
+        }
+    });
+}
+
+/// Running time and the splits of the run so far, each with how far ahead or behind
+/// `comparison` in `best` it is.
+fn show_splits(
+    ui: &mut egui::Ui,
+    splits: &Splits,
+    best: Option<&BestSplits>,
+    comparison: Comparison,
+) {
+    let Some(time) = splits.elapsed(jiff::Zoned::now().time()) else {
+        return;
+    };
+    let deltas = best
+        .map(|best| best.deltas(splits, comparison))
+        .unwrap_or_default();
+    let delta = |name: String| deltas.iter().find(|split| split.name == name);
+
+    ui.horizontal_wrapped(|ui| {
+        let time = RichText::new(format_duration(time)).monospace();
+        if splits.end.is_some() {
+            ui.colored_label(Color32::GREEN, time);
+            if let Some(split) = delta(TimerEntry::End.to_string()) {
+                show_delta(ui, split);
+            }
+        } else {
+            ui.label(time);
+        }
+        if let Some(to) = best.and_then(|best| best.final_time(comparison)) {
+            let label = format!("{} {}", comparison.label(), format_duration(to));
+            ui.label(RichText::new(label).weak());
+        }
+        for (entry, at) in &splits.splits {
+            ui.label(RichText::new(format!("{entry} {}", format_duration(*at))).weak());
+            if let Some(split) = delta(entry.to_string()) {
+                show_delta(ui, split);
+            }
+        }
+    });
+}
+
+/// Green ahead of the comparison, red behind it and gold when the segment beat the
+/// best one, like LiveSplit colors them.
+fn show_delta(ui: &mut egui::Ui, split: &SplitDelta) {
+    let (text, color) = match split.delta {
+        Some(Delta::Ahead(delta)) => (format!("-{}", format_duration(delta)), Color32::GREEN),
+        Some(Delta::Behind(delta)) => (format!("+{}", format_duration(delta)), Color32::RED),
+        None if split.gold => ("★".into(), Color32::GOLD),
+        None => return,
+    };
+    let color = if split.gold { Color32::GOLD } else { color };
+    ui.colored_label(color, RichText::new(text).monospace());
+}
+
+/// Zones grouped by dimension, layer then alias, each with its items, followed by items
+/// without a known zone. Portal levels get a collapsible section per dimension and levels
+/// with a secondary or overload layer one per layer, with its item count. Layers the filter
+/// hides are left out.
+///
+/// Items are a checklist ticked off by hand, or as they're picked up when `patterns.toml`
+/// sets a pickup pattern, shown with the serial players see on them when it was logged. Special carry items are highlighted and listed first. Zones
+/// list their terminals. The zone the player is in is highlighted, and scrolled to when
+/// `current` asks for it. Returns the item that was ticked or unticked by hand, if any.
+fn show_zones(
+    ui: &mut egui::Ui,
+    level: &Level,
+    filter: &ItemFilter,
+    current: Option<CurrentZone>,
+    chat: &ChatTemplate,
+    locale: &Locale,
+) -> Option<GatherItem> {
+    let mut toggled = None;
+    let reads_pickups = re::reads_pickups();
+    let mut item_label = |ui: &mut egui::Ui, alias: Option<u32>, item: &GatherItem| {
+        let label = match alias.and_then(|alias| level.serial(alias, item)) {
+            Some(serial) => format!("{serial} {}", locale.item(item)),
+            None => locale.item(item),
+        };
+        if !filter.matches(item, &label) {
+            return;
+        }
+
+        let mut collected = level.is_collected(item);
+        let text = if collected {
+            RichText::new(label).weak().strikethrough()
+        } else if item.is_carry_objective() {
+            RichText::new(format!("⛟ {label}"))
+                .strong()
+                .color(CARRY_COLOR)
+        } else {
+            RichText::new(label)
+        };
+        let response = ui.checkbox(&mut collected, text);
+        if response.changed() {
+            toggled = Some(item.clone());
+        }
+        if let Some(since) = level.collected_at(item).and_then(|at| level.since_drop(at)) {
+            response.on_hover_text(format!("Picked up at {}", format_duration(since)));
+        } else if !collected && !reads_pickups {
+            response.on_hover_text(locale.tr("level.tick_by_hand"));
+        }
+    };
+
+    let layers = level.layers();
+    let mut show_layer = |ui: &mut egui::Ui, dimension: &Dimension, layer: &str| {
+        for zone in level
+            .zones_in(dimension)
+            .into_iter()
+            .filter(|zone| zone.layer == layer)
+        {
+            // Carry objectives first, they're what players look for.
+            let in_area = |area: Option<char>| {
+                let mut items: Vec<_> = level
+                    .items_in(dimension)
+                    .filter(move |(z, _)| z.alias == zone.alias && z.area == area)
+                    .map(|(_, item)| item)
+                    .collect();
+                items.sort_by_key(|item| !item.is_carry_objective());
+                items
+            };
+
+            let terminals: Vec<_> = level.zone_serials(zone.alias, "TERMINAL").collect();
+            let heading = match terminals.as_slice() {
+                [] => format!("ZONE_{}", zone.alias),
+                terminals => format!("ZONE_{} {}", zone.alias, terminals.join(" ")),
+            };
+            let here = current.filter(|current| current.alias == zone.alias);
+            let frame = match here {
+                Some(_) => Frame::group(ui.style()).fill(CURRENT_ZONE_FILL),
+                None => Frame::none(),
+            };
+            frame.show(ui, |ui| {
+                let response = ui
+                    .horizontal(|ui| {
+                        let response = match here {
+                            Some(_) => ui.label(RichText::new(format!("▶ {heading}")).strong()),
+                            None => ui.label(heading),
+                        };
+                        let message = chat.zone(level, zone);
+                        if !message.is_empty()
+                            && ui
+                                .small_button("📋")
+                                .on_hover_text(format!(
+                                    "{}: {message}",
+                                    locale.tr("level.copy_chat")
+                                ))
+                                .clicked()
+                        {
+                            ui.output_mut(|o| o.copied_text = message);
+                        }
+                        response
+                    })
+                    .inner;
+                if here.is_some_and(|here| here.scroll) {
+                    response.scroll_to_me(Some(Align::Center));
+                }
+                ui.indent((zone.alias, dimension), |ui| {
+                    for item in in_area(None) {
+                        item_label(ui, Some(zone.alias), item);
+                    }
+                    for area in level.areas(zone) {
+                        let label = format!("ZONE_{} Area {area}", zone.alias);
+                        match here {
+                            Some(here) if here.area == Some(area) => {
+                                ui.label(RichText::new(format!("▶ {label}")).strong())
+                            }
+                            _ => ui.label(label),
+                        };
+                        ui.indent((zone.alias, dimension, area), |ui| {
+                            for item in in_area(Some(area)) {
+                                item_label(ui, Some(zone.alias), item);
+                            }
+                        });
+                    }
+                });
+            });
+        }
+    };
+
+    let mut show_dimension = |ui: &mut egui::Ui, dimension: &Dimension| match layers.as_slice() {
+        [layer] => show_layer(ui, dimension, layer),
+        layers => {
+            for &layer in layers.iter().filter(|&&layer| filter.shows_layer(layer)) {
+                let (collected, total) = level.layer_progress(layer);
+                let header = format!("{} {collected}/{total}", layer_label(layer));
+                egui::CollapsingHeader::new(
+                    RichText::new(header).strong().color(layer_color(layer)),
+                )
+                .id_source((dimension, layer))
+                .default_open(true)
+                .show(ui, |ui| show_layer(ui, dimension, layer));
+            }
+        }
+    };
+
+    match level.dimensions().as_slice() {
+        [dimension] => show_dimension(ui, dimension),
+        dimensions => {
+            for dimension in dimensions {
+                egui::CollapsingHeader::new(RichText::new(dimension.label()).strong())
+                    .id_source(*dimension)
+                    .default_open(true)
+                    .show(ui, |ui| show_dimension(ui, dimension));
+            }
+        }
+    }
+
+    for (_, item) in level.all_items().filter(|(zone, _)| zone.is_none()) {
+        item_label(ui, None, item);
+    }
+
+    toggled
+}
+
+/// Neonates, cryo cases, cargo crates and MWPs that weren't picked up yet.
+const CARRY_COLOR: Color32 = Color32::from_rgb(255, 170, 60);
+
+/// Behind the zone the player is in.
+const CURRENT_ZONE_FILL: Color32 = Color32::from_rgba_premultiplied(30, 50, 80, 120);
+
+/// Zone the local player is in, highlighted by [`show_zones`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CurrentZone {
+    alias: u32,
+    area: Option<char>,
+    /// Scroll the zone into view, it was just entered
+    scroll: bool,
+}
+
+/// Header color of a layer, roughly the one the game's map uses for it.
+fn layer_color(layer: &str) -> Color32 {
+    match layer {
+        "MainLayer" => Color32::from_rgb(120, 180, 255),
+        "SecondaryLayer" => Color32::from_rgb(255, 200, 80),
+        "ThirdLayer" => Color32::from_rgb(200, 120, 255),
+        _ => Color32::GRAY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rm_core::data::{Rundown, SplitPreset, Zone};
+
+    use super::*;
+
+    /// Temp folder removed once dropped, even when the test fails.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rm-gui-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn seeds() -> Seeds {
+        Seeds {
+            build: 1,
+            host: 2,
+            session: 3,
+        }
+    }
+
+    fn mapped_level(mapper: &mut Mapper) {
+        mapper.handle_msg(ParserMsg::LevelSeeds(seeds(), None));
+        mapper.handle_msg(ParserMsg::LevelInit(Box::new(Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            ..Default::default()
+        })));
+        mapper.handle_msg(ParserMsg::GeneratedZone(
+            TimerEntry::Zone(Zone {
+                alias: 49,
+                local: 0,
+                dimension: "Reality".into(),
+                layer: "MainLayer".into(),
+                area: None,
+            }),
+            Some(Time::constant(20, 3, 18, 0)),
+        ));
+        mapper.handle_msg(ParserMsg::Gatherable(
+            GatherItem::Key("KEY_GREEN_584".into(), "Reality".into(), 49, 3),
+            Some(Time::constant(20, 3, 19, 0)),
+        ));
+    }
+
+    #[test]
+    fn checkpoint_keeps_level() {
+        let mut mapper = Mapper::default();
+        mapped_level(&mut mapper);
+
+        mapper.handle_msg(ParserMsg::Checkpoint);
+
+        let level = mapper.session.active().unwrap();
+        assert_eq!(level.zones.len(), 1);
+        assert_eq!(level.gatherables.len(), 1);
+        assert_eq!(
+            level.timeline.zones,
+            vec![(49, Time::constant(20, 3, 18, 0))]
+        );
+        assert_eq!(level.timeline.items.len(), 1);
+        assert_eq!(level.seeds, Some(seeds()));
+    }
+
+    #[test]
+    fn reset_clears_level() {
+        let dir = TempDir::new("reset");
+        let mut mapper = Mapper {
+            config: Config {
+                save_dir: Some(dir.0.clone()),
+                save_format: SaveFormat::Both,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        mapped_level(&mut mapper);
+
+        mapper.handle_msg(ParserMsg::Reset);
+
+        assert!(mapper.session.active().is_none());
+        assert_eq!(mapper.session.done().len(), 1);
+        assert_eq!(std::fs::read_dir(dir.0.join("R1A1")).unwrap().count(), 3);
+        assert!(mapper.pending_seeds.is_none());
+    }
+
+    #[test]
+    fn session_info_lasts_until_a_new_file() {
+        let mut mapper = Mapper::default();
+        let info = SessionInfo {
+            build: Some(34853),
+            ..Default::default()
+        };
+        mapper.handle_msg(ParserMsg::Session(info.clone()));
+        mapper.handle_msg(ParserMsg::Reset);
+        assert_eq!(mapper.session_info, info);
+
+        mapper.handle_msg(ParserMsg::NewFile);
+        assert!(mapper.session_info.is_empty());
+    }
+
+    #[test]
+    fn build_progress_lasts_until_the_level_starts() {
+        let mut mapper = Mapper::default();
+        let progress = "Generating... (batch 2/3)".to_string();
+        mapper.handle_msg(ParserMsg::BuildProgress(progress.clone()));
+        assert_eq!(mapper.build_progress, Some(progress));
+
+        mapper.handle_msg(ParserMsg::LevelStart(None));
+        assert!(mapper.build_progress.is_none());
+    }
+
+    #[test]
+    fn seen_seed_shows_stored_layout() {
+        let mut mapper = Mapper::default();
+        mapper.handle_msg(ParserMsg::LevelSeeds(seeds(), None));
+        mapped_level(&mut mapper);
+        mapper.handle_msg(ParserMsg::Reset);
+        assert_eq!(mapper.seed_db.seeds.len(), 1);
+
+        mapper.handle_msg(ParserMsg::LevelSeeds(seeds(), None));
+        mapper.handle_msg(ParserMsg::LevelInit(Box::new(Level {
+            rundown: Rundown::R1,
+            exp_name: "A1".into(),
+            ..Default::default()
+        })));
+        assert!(mapper.seen_seed.is_some());
+        assert_eq!(mapper.session.active().unwrap().gatherables.len(), 1);
+
+        // The log's own layout takes over.
+        mapper.handle_msg(ParserMsg::GeneratedZone(
+            TimerEntry::Zone(Zone::placeholder(50)),
+            None,
+        ));
+        let level = mapper.session.active().unwrap();
+        assert_eq!(level.zones.len(), 1);
+        assert!(level.gatherables.is_empty());
+    }
+
+    #[test]
+    fn run_clock_falls_back_to_level_start() {
+        let mut mapper = Mapper::default();
+        let start: Time = "20:03:29.500".parse().unwrap();
+
+        mapper.handle_msg(ParserMsg::LevelSeeds(seeds(), None));
+        assert_eq!(mapper.run_clock, None);
+
+        mapper.handle_msg(ParserMsg::LevelStart(Some(start)));
+        assert_eq!(mapper.run_clock, Some(start));
+
+        mapper.handle_msg(ParserMsg::Reset);
+        assert_eq!(mapper.run_clock, None);
+    }
+
+    #[test]
+    fn manual_splits_are_numbered() {
+        let mut mapper = Mapper::default();
+        mapped_level(&mut mapper);
+        let t = |s: &str| s.parse::<Time>().unwrap();
+
+        mapper.handle_msg(ParserMsg::ElevatorDrop(Some(t("20:00:00"))));
+        mapper.handle_msg(ParserMsg::Split(Some(t("20:01:00"))));
+        mapper.handle_msg(ParserMsg::Split(Some(t("20:02:30"))));
+
+        let splits = &mapper.session.active().unwrap().splits.splits;
+        assert_eq!(
+            splits,
+            &vec![
+                (
+                    TimerEntry::Custom("Split 1".into()),
+                    Duration::from_secs(60)
+                ),
+                (
+                    TimerEntry::Custom("Split 2".into()),
+                    Duration::from_secs(150)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn progress_events_split() {
+        let mut mapper = Mapper::default();
+        mapped_level(&mut mapper);
+        let t = |s: &str| s.parse::<Time>().unwrap();
+
+        mapper.handle_msg(ParserMsg::ElevatorDrop(Some(t("20:00:00"))));
+        mapper.handle_msg(ParserMsg::CheckpointActivated(Some(t("20:01:00"))));
+        mapper.handle_msg(ParserMsg::BulkheadScanned(50, Some(t("20:02:00"))));
+        mapper.handle_msg(ParserMsg::CheckpointActivated(Some(t("20:03:00"))));
+
+        let entries: Vec<String> = mapper
+            .session
+            .active()
+            .unwrap()
+            .splits
+            .splits
+            .iter()
+            .map(|(entry, _)| entry.to_string())
+            .collect();
+        assert_eq!(
+            entries,
+            ["Checkpoint 1", "Bulkhead ZONE_50", "Checkpoint 2"]
+        );
+    }
+
+    #[test]
+    fn doors_and_zone_lines_move_the_player() {
+        let mut mapper = Mapper::default();
+        mapped_level(&mut mapper);
+
+        mapper.handle_msg(ParserMsg::ZoneDoorOpened(50, None));
+        assert_eq!(mapper.current_zone, Some((50, None)));
+        assert!(mapper.zone_changed);
+
+        mapper.zone_changed = false;
+        mapper.handle_msg(ParserMsg::ZoneEntered(50, None, None));
+        assert!(!mapper.zone_changed);
+        mapper.handle_msg(ParserMsg::ZoneEntered(51, Some('A'), None));
+        assert_eq!(mapper.current_zone, Some((51, Some('A'))));
+    }
+
+    #[test]
+    fn preset_decides_splits() {
+        let mut mapper = Mapper::default();
+        mapper.config.split_presets.push(SplitPreset {
+            level: "R1A1".into(),
+            entries: vec![
+                TimerEntry::Start,
+                TimerEntry::Custom("Bulkhead ZONE_50".into()),
+                TimerEntry::End,
+            ],
+        });
+        mapped_level(&mut mapper);
+        let t = |s: &str| s.parse::<Time>().unwrap();
+
+        mapper.handle_msg(ParserMsg::ElevatorDrop(Some(t("20:00:00"))));
+        mapper.handle_msg(ParserMsg::CheckpointActivated(Some(t("20:01:00"))));
+        mapper.handle_msg(ParserMsg::BulkheadScanned(50, Some(t("20:02:00"))));
+        mapper.handle_msg(ParserMsg::LevelFinish(Some(t("20:03:00"))));
+
+        let splits = &mapper.session.active().unwrap().splits;
+        assert_eq!(
+            splits.splits,
+            vec![(
+                TimerEntry::Custom("Bulkhead ZONE_50".into()),
+                Duration::from_secs(120)
+            )]
+        );
+        assert_eq!(splits.end, Some(Duration::from_secs(180)));
+
+        mapper.record_bests();
+        let preset = mapper.splitter.as_ref().unwrap().entries();
+        let best = mapper.bests.get("R1A1", preset).unwrap();
+        assert_eq!(best.pb_time(), Some(Duration::from_secs(180)));
+        assert_eq!(mapper.bests.get("R1A1", &[]), None);
     }
 }
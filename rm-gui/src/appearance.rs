@@ -0,0 +1,91 @@
+use egui::{Context, TextStyle, ViewportCommand, Visuals};
+use rm_core::config::{Theme, WindowConfig};
+use serde::{Deserialize, Serialize};
+
+/// Window for the overlay's look, applied to the viewport whenever it changes.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Appearance {
+    pub open: bool,
+    /// Settings last applied, none before the first frame
+    #[serde(skip)]
+    applied: Option<WindowConfig>,
+}
+
+impl Appearance {
+    pub fn show(&mut self, ctx: &Context, window: &mut WindowConfig) {
+        let mut open = self.open;
+        egui::Window::new("Appearance")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("appearance").num_columns(2).show(ui, |ui| {
+                    ui.label("Opacity");
+                    ui.add(egui::Slider::new(&mut window.opacity, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label("Text size");
+                    ui.add(egui::Slider::new(&mut window.font_scale, 0.5..=2.0).step_by(0.05));
+                    ui.end_row();
+
+                    ui.label("Theme");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut window.theme, Theme::Dark, "Dark");
+                        ui.radio_value(&mut window.theme, Theme::Light, "Light");
+                    });
+                    ui.end_row();
+                });
+                ui.checkbox(&mut window.click_through, "Click-through");
+                ui.label(egui::RichText::new("The click-through hotkey turns it back off").weak());
+            });
+        self.open = open;
+    }
+
+    /// Applies `window` to the viewport if it changed since the last call.
+    pub fn apply(&mut self, ctx: &Context, window: &WindowConfig) {
+        let applied = self.applied.replace(window.clone());
+
+        if applied.as_ref().map(|a| a.theme) != Some(window.theme) {
+            ctx.set_visuals(match window.theme {
+                Theme::Dark => Visuals::dark(),
+                Theme::Light => Visuals::light(),
+            });
+        }
+        if applied.as_ref().map(|a| a.font_scale) != Some(window.font_scale) {
+            let defaults = egui::Style::default().text_styles;
+            ctx.style_mut(|style| {
+                for (text_style, font) in &mut style.text_styles {
+                    font.size = scaled(&defaults, text_style, window.font_scale);
+                }
+            });
+        }
+        if applied.as_ref().map(|a| a.click_through) != Some(window.click_through) {
+            ctx.send_viewport_cmd(ViewportCommand::MousePassthrough(window.click_through));
+        }
+    }
+}
+
+/// Size of `text_style` at `scale` times egui's default.
+fn scaled(
+    defaults: &std::collections::BTreeMap<TextStyle, egui::FontId>,
+    text_style: &TextStyle,
+    scale: f32,
+) -> f32 {
+    let size = defaults.get(text_style).map_or(12.0, |font| font.size);
+    size * scale.clamp(0.5, 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_scaled_from_defaults() {
+        let defaults = egui::Style::default().text_styles;
+        let body = defaults[&TextStyle::Body].size;
+
+        assert_eq!(scaled(&defaults, &TextStyle::Body, 1.0), body);
+        assert_eq!(scaled(&defaults, &TextStyle::Body, 1.5), body * 1.5);
+        assert_eq!(scaled(&defaults, &TextStyle::Body, 10.0), body * 2.0);
+    }
+}
@@ -0,0 +1,42 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use clap::Parser;
+use rm_core::events::EventSink;
+
+#[derive(Parser, Debug, Default, Clone)]
+#[command(version, about)]
+pub struct Args {
+    /// Publish expedition events as newline-delimited JSON, e.g. `tcp:127.0.0.1:9870`
+    /// or `unix:/tmp/rusted-mapper.sock`. Takes precedence over `events` in the config.
+    #[arg(long, value_name = "SINK")]
+    pub events: Option<EventSink>,
+
+    /// Serve parser messages and the current level over WebSocket for overlays, e.g.
+    /// `127.0.0.1:9871`.
+    #[arg(long, value_name = "ADDR")]
+    pub overlay: Option<SocketAddr>,
+
+    /// Only read what GTFO logs from now on when it's already running, instead of replaying
+    /// the whole session.
+    #[arg(long)]
+    pub from_end: bool,
+
+    /// Folder GTFO writes its logs to, found automatically if unset. Also read from
+    /// `RUSTED_MAPPER_LOG_DIR`.
+    #[arg(long, value_name = "PATH")]
+    pub log_dir: Option<PathBuf>,
+
+    /// Write every parser message to a file, to replay it later with `--playback`.
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+
+    /// Take commands like `reset`, `open <path>` or `export <path>` from scripts, one per
+    /// line, on a Unix socket or a named pipe on Windows. `rusted-mapper.sock` in the runtime
+    /// folder or `\\.\pipe\rusted-mapper` if no path is given.
+    #[arg(long, value_name = "PATH")]
+    pub control: Option<Option<PathBuf>>,
+
+    /// Replay a file written by `--record` instead of watching GTFO's logs.
+    #[arg(long, value_name = "PATH")]
+    pub playback: Option<PathBuf>,
+}
@@ -0,0 +1,107 @@
+use egui::{Color32, Context, RichText, ScrollArea};
+use rm_core::{
+    compare::{compare, Discrepancy, LevelBuild},
+    parser::ParserMsg,
+};
+use serde::{Deserialize, Serialize};
+
+/// Window comparing the live level with the one in another log, e.g. the host's shared
+/// one, for spotting desynced seeds or items.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompareView {
+    pub open: bool,
+    /// Source the other log's messages are tagged with, while one is open
+    #[serde(skip)]
+    source: Option<String>,
+    #[serde(skip)]
+    ours: LevelBuild,
+    #[serde(skip)]
+    theirs: LevelBuild,
+}
+
+/// What the window asks the mapper to do with the parser.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareAction {
+    /// Pick a log and compare with it, in place of the current one
+    Open,
+    Close(String),
+}
+
+impl CompareView {
+    pub fn comparing(&mut self, source: String) {
+        self.source = Some(source);
+        self.theirs = LevelBuild::default();
+    }
+
+    pub fn stop(&mut self) {
+        self.source = None;
+        self.theirs = LevelBuild::default();
+    }
+
+    pub fn push_ours(&mut self, msg: &ParserMsg) {
+        self.ours.push(msg);
+    }
+
+    /// Takes a message of the other log, ones of a source that's no longer open are dropped.
+    pub fn push_theirs(&mut self, source: &str, msg: &ParserMsg) {
+        if self.source.as_deref() != Some(source) {
+            return;
+        }
+        match msg {
+            ParserMsg::NewFile => self.theirs = LevelBuild::default(),
+            msg => self.theirs.push(msg),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &Context) -> Option<CompareAction> {
+        let mut action = None;
+        let mut open = self.open;
+        egui::Window::new("Compare logs")
+            .open(&mut open)
+            .default_size([320.0, 240.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    match &self.source {
+                        Some(source) => ui.label(format!("Comparing with {source}")),
+                        None => ui.label("No log to compare with"),
+                    };
+                    if ui.button("Open log…").clicked() {
+                        action = Some(CompareAction::Open);
+                    }
+                    if let Some(source) = &self.source {
+                        if ui.button("Stop").clicked() {
+                            action = Some(CompareAction::Close(source.clone()));
+                        }
+                    }
+                });
+                if self.source.is_none() {
+                    return;
+                }
+
+                ui.separator();
+                if self.theirs.seeds.is_none() {
+                    ui.label(RichText::new("No level in the other log yet").weak());
+                    return;
+                }
+                let discrepancies = compare(&self.ours, &self.theirs);
+                if discrepancies.is_empty() {
+                    ui.colored_label(Color32::GREEN, "Both logs agree");
+                    return;
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    for discrepancy in discrepancies {
+                        let color = match discrepancy {
+                            Discrepancy::Level { .. } | Discrepancy::Seeds { .. } => Color32::RED,
+                            Discrepancy::Item { .. } => Color32::YELLOW,
+                        };
+                        ui.colored_label(color, discrepancy.to_string());
+                    }
+                });
+            });
+        self.open = open;
+
+        action
+    }
+}
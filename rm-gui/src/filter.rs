@@ -0,0 +1,72 @@
+use egui::Ui;
+use rm_core::{
+    data::{layer_label, GatherItem, ItemCategory, ItemQuery},
+    locale::Locale,
+};
+use serde::{Deserialize, Serialize};
+
+/// Search bar and category chips for the gatherables list, the filtering itself is
+/// [`ItemQuery`]'s.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemFilter {
+    pub query: ItemQuery,
+}
+
+impl ItemFilter {
+    pub fn show(&mut self, ui: &mut Ui, locale: &Locale) {
+        ui.horizontal(|ui| {
+            ui.label(locale.tr("filter.search"));
+            ui.text_edit_singleline(&mut self.query.text);
+            if !self.query.text.is_empty() && ui.small_button("✖").clicked() {
+                self.query.text.clear();
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            for category in ItemCategory::ALL {
+                let key = match category {
+                    ItemCategory::Keys => "filter.keys",
+                    ItemCategory::Cells => "filter.cells",
+                    ItemCategory::Containers => "filter.containers",
+                    ItemCategory::Objective => "filter.objective",
+                };
+                if ui
+                    .selectable_label(self.query.shows(category), locale.tr(key))
+                    .clicked()
+                {
+                    self.query.toggle(category);
+                }
+            }
+        });
+    }
+
+    /// Toggles for the level's `layers`, only shown when there's more than one.
+    pub fn show_layers(&mut self, ui: &mut Ui, layers: &[&str], locale: &Locale) {
+        if layers.len() < 2 {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(locale.tr("filter.layers"));
+            for &layer in layers {
+                let mut shown = self.shows_layer(layer);
+                if ui.checkbox(&mut shown, layer_label(layer)).changed() {
+                    if shown {
+                        self.query.hidden_layers.retain(|hidden| hidden != layer);
+                    } else {
+                        self.query.hidden_layers.push(layer.to_string());
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn shows_layer(&self, layer: &str) -> bool {
+        self.query.shows_layer(layer)
+    }
+
+    /// Whether an item with the rendered `label` passes the filter.
+    pub fn matches(&self, item: &GatherItem, label: &str) -> bool {
+        self.query.matches(item, label)
+    }
+}
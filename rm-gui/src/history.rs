@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use egui::{Context, RichText, ScrollArea};
+use log::error;
+use rm_core::data::{format_duration, saved_runs, SavedRun};
+use serde::{Deserialize, Serialize};
+
+/// Window listing the levels saved on reset.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryView {
+    pub open: bool,
+    /// Runs found in the save folder, `None` until it's read
+    #[serde(skip)]
+    runs: Option<Vec<SavedRun>>,
+}
+
+impl HistoryView {
+    /// Reads the save folder again the next time the window is shown.
+    pub fn refresh(&mut self) {
+        self.runs = None;
+    }
+
+    /// Returns the save picked to be opened.
+    pub fn show(&mut self, ctx: &Context, root: Option<&Path>) -> Option<PathBuf> {
+        let mut picked = None;
+        let mut open = self.open;
+        egui::Window::new("History")
+            .open(&mut open)
+            .default_size([320.0, 300.0])
+            .show(ctx, |ui| {
+                let Some(root) = root else {
+                    ui.label("No save folder");
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(root.display().to_string()).weak());
+                    if ui.button("Refresh").clicked() {
+                        self.refresh();
+                    }
+                });
+
+                let runs = self.runs.get_or_insert_with(|| {
+                    // The folder only exists once something was saved.
+                    if !root.is_dir() {
+                        return vec![];
+                    }
+                    saved_runs(root).unwrap_or_else(|e| {
+                        error!("Failed to read saves in {}: {e}", root.display());
+                        vec![]
+                    })
+                });
+                if runs.is_empty() {
+                    ui.label("No saved runs");
+                    return;
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("history")
+                        .striped(true)
+                        .num_columns(6)
+                        .show(ui, |ui| {
+                            for header in ["Expedition", "Saved", "Seeds", "Time", "Outcome"] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+
+                            for run in runs.iter() {
+                                ui.label(&run.level);
+                                ui.label(run.saved_at.map_or_else(
+                                    || "-".into(),
+                                    |at| at.strftime("%Y-%m-%d %H:%M").to_string(),
+                                ));
+                                ui.label(run.seeds.map_or_else(|| "-".into(), |s| s.to_string()));
+                                ui.label(run.duration.map_or_else(|| "-".into(), format_duration));
+                                ui.label(run.outcome.map_or_else(|| "-".into(), |o| o.to_string()));
+                                if ui.button("Open").clicked() {
+                                    picked = Some(run.path.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+
+        picked
+    }
+}
@@ -0,0 +1,103 @@
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use log::{error, info};
+use rm_core::config::HotkeyConfig;
+
+/// What a global hotkey does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    Split,
+    Reset,
+    Refresh,
+    ClickThrough,
+}
+
+/// Hotkeys from the config, registered system wide so they work while GTFO has focus.
+pub struct Hotkeys {
+    manager: GlobalHotKeyManager,
+    bindings: Vec<(HotKey, HotkeyAction)>,
+}
+
+impl Hotkeys {
+    pub fn register(config: &HotkeyConfig) -> anyhow::Result<Self> {
+        let manager = GlobalHotKeyManager::new()?;
+
+        let mut bindings = vec![];
+        for (hotkey, action) in parse_bindings(config) {
+            match manager.register(hotkey) {
+                Ok(_) => {
+                    info!("Registered {} for {action:?}", hotkey.into_string());
+                    bindings.push((hotkey, action));
+                }
+                Err(e) => error!("Failed to register hotkey for {action:?}: {e}"),
+            }
+        }
+
+        Ok(Self { manager, bindings })
+    }
+
+    /// Actions of the hotkeys pressed since the last call.
+    pub fn pressed(&self) -> Vec<HotkeyAction> {
+        GlobalHotKeyEvent::receiver()
+            .try_iter()
+            .filter(|event| event.state == HotKeyState::Pressed)
+            .filter_map(|event| {
+                self.bindings
+                    .iter()
+                    .find(|(hotkey, _)| hotkey.id() == event.id)
+                    .map(|(_, action)| *action)
+            })
+            .collect()
+    }
+}
+
+impl Drop for Hotkeys {
+    fn drop(&mut self) {
+        let hotkeys: Vec<HotKey> = self.bindings.iter().map(|(hotkey, _)| *hotkey).collect();
+        let _ = self.manager.unregister_all(&hotkeys);
+    }
+}
+
+/// Hotkeys set in the config, invalid ones are logged and skipped.
+fn parse_bindings(config: &HotkeyConfig) -> Vec<(HotKey, HotkeyAction)> {
+    [
+        (&config.split, HotkeyAction::Split),
+        (&config.reset, HotkeyAction::Reset),
+        (&config.refresh, HotkeyAction::Refresh),
+        (&config.click_through, HotkeyAction::ClickThrough),
+    ]
+    .into_iter()
+    .filter(|(binding, _)| !binding.is_empty())
+    .filter_map(|(binding, action)| match binding.parse::<HotKey>() {
+        Ok(hotkey) => Some((hotkey, action)),
+        Err(e) => {
+            error!("Invalid hotkey `{binding}` for {action:?}: {e}");
+            None
+        }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use global_hotkey::hotkey::{Code, Modifiers};
+
+    use super::*;
+
+    #[test]
+    fn bindings_skip_empty_and_invalid() {
+        let config = HotkeyConfig {
+            split: "Ctrl+Shift+S".into(),
+            reset: "".into(),
+            refresh: "NotAKey".into(),
+            click_through: "".into(),
+        };
+
+        assert_eq!(
+            parse_bindings(&config),
+            vec![(
+                HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyS),
+                HotkeyAction::Split
+            )]
+        );
+    }
+}
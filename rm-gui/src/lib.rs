@@ -3,4 +3,17 @@ pub mod built_info {
 }
 
 mod app;
-pub use app::Mapper;
\ No newline at end of file
+mod appearance;
+mod cli;
+mod compare;
+mod filter;
+mod history;
+mod hotkeys;
+mod map;
+mod parser_log;
+mod presets;
+mod stats;
+#[cfg(all(windows, feature = "tray"))]
+mod tray;
+pub use app::Mapper;
+pub use cli::Args;
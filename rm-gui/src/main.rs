@@ -1,18 +1,25 @@
-#![feature(duration_constructors)]
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use rm_gui::built_info;
+use clap::Parser;
+use rm_core::config::Config;
+use rm_gui::{built_info, Args};
 
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
     env_logger::init();
 
+    let args = Args::parse();
+    let config = Config::load();
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([330.0, 550.0])
+            .with_inner_size(config.window.size)
             .with_min_inner_size([330.0, 550.0])
-            .with_position([1.0, 300.0])
+            .with_position(config.window.position)
             .with_decorations(false)
+            .with_resizable(true)
+            // Lives in the tray instead.
+            .with_taskbar(!cfg!(all(windows, feature = "tray")))
             .with_transparent(true)
             .with_window_level(egui::WindowLevel::AlwaysOnTop),
         ..Default::default()
@@ -21,6 +28,6 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         built_info::PKG_NAME,
         native_options,
-        Box::new(|cc| Ok(Box::new(rm_gui::Mapper::new(cc)))),
+        Box::new(|cc| Ok(Box::new(rm_gui::Mapper::new(cc, args, config)))),
     )
 }
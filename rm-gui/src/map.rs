@@ -0,0 +1,154 @@
+use egui::{Color32, Context, Pos2, Rect, Sense, Shape, Stroke, Vec2};
+use rm_core::data::{GatherableMap, Level};
+use serde::{Deserialize, Serialize};
+
+/// Floor plan window drawn from the level's [`GatherableMap`]s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapView {
+    pub open: bool,
+    /// Screen offset from dragging, on top of fitting the maps to the window
+    #[serde(skip)]
+    pan: Vec2,
+    #[serde(skip)]
+    zoom: f32,
+}
+
+impl Default for MapView {
+    fn default() -> Self {
+        Self {
+            open: false,
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl MapView {
+    pub fn show(&mut self, ctx: &Context, level: Option<&Level>) {
+        let mut open = self.open;
+        egui::Window::new("Map")
+            .open(&mut open)
+            .default_size([300.0, 300.0])
+            .show(ctx, |ui| {
+                let Some(level) = level.filter(|level| !level.maps.is_empty()) else {
+                    ui.label("No map for this level");
+                    return;
+                };
+
+                let (response, painter) =
+                    ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+                if response.dragged() {
+                    self.pan += response.drag_delta();
+                }
+                if response.hovered() {
+                    let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+                    self.zoom = (self.zoom * (scroll * 0.002).exp()).clamp(0.1, 20.0);
+                }
+                if response.double_clicked() {
+                    self.pan = Vec2::ZERO;
+                    self.zoom = 1.0;
+                }
+
+                let Some(bounds) = bounds(&level.maps) else {
+                    return;
+                };
+                let scale = fit_scale(bounds, response.rect) * self.zoom;
+                let center = response.rect.center() + self.pan;
+                // Map y grows north, screen y grows down.
+                let to_screen = |x: f32, y: f32| {
+                    Pos2::new(
+                        center.x + (x - bounds.center().x) * scale,
+                        center.y - (y - bounds.center().y) * scale,
+                    )
+                };
+
+                let outline = Stroke::new(1.5_f32, Color32::LIGHT_GRAY);
+                for map in &level.maps {
+                    let points: Vec<Pos2> = map
+                        .outline_poly
+                        .iter()
+                        .map(|p| to_screen(p.x, p.y))
+                        .collect();
+                    painter.add(Shape::closed_line(points, outline));
+
+                    for blockout in &map.blockouts {
+                        painter.add(Shape::convex_polygon(
+                            blockout.iter().map(|p| to_screen(p.x, p.y)).collect(),
+                            Color32::from_gray(60),
+                            Stroke::NONE,
+                        ));
+                    }
+                }
+
+                let collected: Vec<String> =
+                    level.collected.iter().map(ToString::to_string).collect();
+                for (pos, label) in level.maps.iter().flat_map(|map| &map.markers) {
+                    let pos = to_screen(pos.x, pos.y);
+                    let color = if collected.contains(label) {
+                        Color32::DARK_GRAY
+                    } else {
+                        Color32::YELLOW
+                    };
+                    painter.circle_filled(pos, 3.0, color);
+                    painter.text(
+                        pos + Vec2::new(5.0, 0.0),
+                        egui::Align2::LEFT_CENTER,
+                        label,
+                        egui::FontId::proportional(11.0),
+                        color,
+                    );
+                }
+            });
+        self.open = open;
+    }
+}
+
+/// Box around every outline, blockout and marker, in map units.
+fn bounds(maps: &[GatherableMap]) -> Option<Rect> {
+    let points = maps.iter().flat_map(|map| {
+        map.outline_poly
+            .iter()
+            .chain(map.blockouts.iter().flatten())
+            .chain(map.markers.iter().map(|(pos, _)| pos))
+    });
+
+    points.fold(None, |bounds: Option<Rect>, p| {
+        let p = Pos2::new(p.x, p.y);
+        Some(match bounds {
+            Some(bounds) => bounds.union(Rect::from_min_max(p, p)),
+            None => Rect::from_min_max(p, p),
+        })
+    })
+}
+
+/// Scale that fits `bounds` into `rect` with a small margin.
+fn fit_scale(bounds: Rect, rect: Rect) -> f32 {
+    let size = bounds.size().max(Vec2::splat(1.0));
+    (rect.width() / size.x).min(rect.height() / size.y) * 0.9
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_cover_all_geometry() {
+        let v = |x, y| glam::Vec2::new(x, y);
+        let maps = vec![GatherableMap {
+            zone: Some(49),
+            outline_poly: vec![v(0.0, 0.0), v(10.0, 0.0), v(10.0, 5.0)],
+            blockouts: vec![[v(2.0, 1.0), v(3.0, 1.0), v(3.0, 2.0), v(2.0, 2.0)]],
+            markers: vec![(v(-4.0, 8.0), "ID Box 3".into())],
+        }];
+
+        assert_eq!(
+            bounds(&maps),
+            Some(Rect::from_min_max(
+                Pos2::new(-4.0, 0.0),
+                Pos2::new(10.0, 8.0)
+            ))
+        );
+        assert_eq!(bounds(&[]), None);
+    }
+}
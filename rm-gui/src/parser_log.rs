@@ -0,0 +1,59 @@
+use egui::{Color32, RichText, ScrollArea};
+use rm_core::diagnostics::{Diagnostic, DiagnosticLog, Severity};
+use serde::{Deserialize, Serialize};
+
+/// Collapsible list of the parser's diagnostics, for figuring out why a (modded) level
+/// doesn't map.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParserLog {
+    /// Least severe diagnostic shown
+    min: Severity,
+    #[serde(skip)]
+    log: DiagnosticLog,
+}
+
+impl Default for ParserLog {
+    fn default() -> Self {
+        Self {
+            min: Severity::Warning,
+            log: Default::default(),
+        }
+    }
+}
+
+impl ParserLog {
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.log.push(diagnostic);
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let shown = self.log.iter(self.min).count();
+        egui::CollapsingHeader::new(format!("Parser log ({shown})"))
+            .id_source("parser_log")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.min, Severity::Info, "All");
+                    ui.selectable_value(&mut self.min, Severity::Warning, "Warnings");
+                    ui.selectable_value(&mut self.min, Severity::Error, "Errors");
+                    if ui.small_button("Clear").clicked() {
+                        self.log.clear();
+                    }
+                });
+
+                ScrollArea::vertical()
+                    .max_height(120.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for diagnostic in self.log.iter(self.min) {
+                            let text = RichText::new(&diagnostic.message).small();
+                            match diagnostic.severity {
+                                Severity::Info => ui.label(text.weak()),
+                                Severity::Warning => ui.colored_label(Color32::YELLOW, text),
+                                Severity::Error => ui.colored_label(Color32::RED, text),
+                            };
+                        }
+                    });
+            });
+    }
+}
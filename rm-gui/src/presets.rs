@@ -0,0 +1,174 @@
+use egui::{Context, RichText};
+use rm_core::data::{Level, SplitPreset, TimerEntry, Zone};
+use serde::{Deserialize, Serialize};
+
+/// Window for building the split preset of the level being played or viewed.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresetEditor {
+    pub open: bool,
+    /// Preset being edited, only written to the config on save
+    #[serde(skip)]
+    draft: Option<SplitPreset>,
+    /// Name of the next custom split
+    #[serde(skip)]
+    custom: String,
+}
+
+impl PresetEditor {
+    pub fn show(&mut self, ctx: &Context, level: Option<&Level>, presets: &mut Vec<SplitPreset>) {
+        let mut open = self.open;
+        egui::Window::new("Split preset")
+            .open(&mut open)
+            .default_size([280.0, 300.0])
+            .show(ctx, |ui| {
+                let Some(level) = level else {
+                    ui.label("No level to make a preset for");
+                    return;
+                };
+
+                let name = level.to_string();
+                let saved = presets.iter().position(|preset| preset.level == name);
+                let draft = match &mut self.draft {
+                    Some(draft) if draft.level == name => draft,
+                    draft => draft.insert(
+                        saved
+                            .map(|i| presets[i].clone())
+                            .unwrap_or_else(|| SplitPreset::new(&name)),
+                    ),
+                };
+
+                ui.label(RichText::new(&name).strong());
+                let last = draft.entries.len().saturating_sub(1);
+                let mut edit = None;
+                for (i, entry) in draft.entries.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(i > 0, egui::Button::new("⏶").small())
+                            .clicked()
+                        {
+                            edit = Some(Edit::Up(i));
+                        }
+                        if ui
+                            .add_enabled(i < last, egui::Button::new("⏷").small())
+                            .clicked()
+                        {
+                            edit = Some(Edit::Down(i));
+                        }
+                        if ui.small_button("✖").clicked() {
+                            edit = Some(Edit::Remove(i));
+                        }
+                        ui.label(entry.to_string());
+                    });
+                }
+
+                ui.separator();
+                ui.menu_button("Add zone", |ui| {
+                    for zone in &level.zones {
+                        if ui
+                            .button(format!("ZONE_{} {}", zone.alias, zone.layer))
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            edit = Some(Edit::Add(TimerEntry::Zone(Zone {
+                                area: None,
+                                ..zone.clone()
+                            })));
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.custom);
+                    if ui
+                        .add_enabled(!self.custom.is_empty(), egui::Button::new("Add custom"))
+                        .clicked()
+                    {
+                        edit = Some(Edit::Add(TimerEntry::Custom(std::mem::take(
+                            &mut self.custom,
+                        ))));
+                    }
+                });
+
+                if let Some(edit) = edit {
+                    edit.apply(&mut draft.entries);
+                }
+
+                ui.separator();
+                let mut deleted = false;
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        match saved {
+                            Some(i) => presets[i] = draft.clone(),
+                            None => presets.push(draft.clone()),
+                        }
+                    }
+                    if ui
+                        .add_enabled(saved.is_some(), egui::Button::new("Delete"))
+                        .clicked()
+                    {
+                        if let Some(i) = saved {
+                            presets.remove(i);
+                        }
+                        deleted = true;
+                    }
+                });
+                if deleted {
+                    self.draft = None;
+                }
+                ui.label(RichText::new("Presets apply from the next run of the level").weak());
+            });
+        self.open = open;
+    }
+}
+
+/// Change to the draft's entries, applied after they're drawn.
+#[derive(Debug, Clone, PartialEq)]
+enum Edit {
+    Up(usize),
+    Down(usize),
+    Remove(usize),
+    Add(TimerEntry),
+}
+
+impl Edit {
+    fn apply(self, entries: &mut Vec<TimerEntry>) {
+        match self {
+            Edit::Up(i) => entries.swap(i - 1, i),
+            Edit::Down(i) => entries.swap(i, i + 1),
+            Edit::Remove(i) => {
+                entries.remove(i);
+            }
+            // New splits go before the end, if there is one.
+            Edit::Add(entry) => {
+                let at = match entries.last() {
+                    Some(TimerEntry::End) => entries.len() - 1,
+                    _ => entries.len(),
+                };
+                entries.insert(at, entry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_added_before_end() {
+        let mut entries = SplitPreset::new("R1A1").entries;
+        Edit::Add(TimerEntry::Custom("Bulkhead ZONE_51".into())).apply(&mut entries);
+        Edit::Add(TimerEntry::Custom("Checkpoint 1".into())).apply(&mut entries);
+        Edit::Up(2).apply(&mut entries);
+        Edit::Remove(0).apply(&mut entries);
+
+        assert_eq!(
+            entries,
+            vec![
+                TimerEntry::Custom("Checkpoint 1".into()),
+                TimerEntry::Custom("Bulkhead ZONE_51".into()),
+                TimerEntry::End,
+            ]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::{path::Path, time::Duration};
+
+use egui::{Color32, Context, RichText, ScrollArea, Ui};
+use log::error;
+use rm_core::data::{format_duration, Level, RunStats};
+use serde::{Deserialize, Serialize};
+
+/// Window with the stats of the current run next to the personal best of its expedition.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsView {
+    pub open: bool,
+    /// Expedition the best was looked up for and the best found, `None` until it's read
+    #[serde(skip)]
+    best: Option<(String, Option<RunStats>)>,
+}
+
+impl StatsView {
+    /// Looks the personal best up again the next time the window is shown.
+    pub fn refresh(&mut self) {
+        self.best = None;
+    }
+
+    pub fn show(&mut self, ctx: &Context, level: Option<&Level>, root: Option<&Path>) {
+        let mut open = self.open;
+        egui::Window::new("Run stats")
+            .open(&mut open)
+            .default_size([320.0, 300.0])
+            .show(ctx, |ui| {
+                let Some(level) = level else {
+                    ui.label("No level");
+                    return;
+                };
+                let current = RunStats::new(level);
+
+                if self
+                    .best
+                    .as_ref()
+                    .is_none_or(|(name, _)| *name != current.level)
+                {
+                    let best = root.and_then(|root| {
+                        let dir = level.expedition_dir(root);
+                        RunStats::personal_best(&dir, &current.level).unwrap_or_else(|e| {
+                            error!("Failed to read stats in {}: {e}", dir.display());
+                            None
+                        })
+                    });
+                    self.best = Some((current.level.clone(), best));
+                }
+                let best = self.best.as_ref().and_then(|(_, best)| best.as_ref());
+                if best.is_none() {
+                    ui.label(RichText::new("No finished run to compare with").weak());
+                }
+
+                ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("stats")
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            for header in ["", "Run", "Best", "Delta"] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+
+                            let row = |ui: &mut Ui, name: &str, run, best| {
+                                ui.label(name);
+                                duration_cells(ui, run, best);
+                                ui.end_row();
+                            };
+                            row(
+                                ui,
+                                "Build to drop",
+                                current.build_to_drop,
+                                best.and_then(|best| best.build_to_drop),
+                            );
+                            for (name, at) in &current.splits {
+                                row(ui, name, Some(*at), best.and_then(|best| best.split(name)));
+                            }
+                            row(
+                                ui,
+                                "Final time",
+                                current.duration,
+                                best.and_then(|best| best.duration),
+                            );
+                        });
+
+                    ui.separator();
+                    ui.label(format!(
+                        "Collected {} of {} items",
+                        current.collected,
+                        current.items_per_kind.values().sum::<usize>()
+                    ));
+                    egui::Grid::new("stats_items")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for (kind, count) in &current.items_per_kind {
+                                ui.label(kind);
+                                ui.label(count.to_string());
+                                ui.end_row();
+                            }
+                            for (alias, count) in &current.items_per_zone {
+                                ui.label(format!("ZONE_{alias}"));
+                                ui.label(count.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.open = open;
+    }
+}
+
+/// Run and best times with how far ahead or behind the run is, green when it's ahead.
+fn duration_cells(ui: &mut Ui, run: Option<Duration>, best: Option<Duration>) {
+    let or_dash = |duration: Option<Duration>| duration.map_or_else(|| "-".into(), format_duration);
+    ui.label(or_dash(run));
+    ui.label(or_dash(best));
+    match run.zip(best) {
+        Some((run, best)) if run <= best => {
+            ui.colored_label(Color32::GREEN, format!("-{}", format_duration(best - run)))
+        }
+        Some((run, best)) => {
+            ui.colored_label(Color32::RED, format!("+{}", format_duration(run - best)))
+        }
+        None => ui.label(""),
+    };
+}
@@ -0,0 +1,119 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use rm_core::locale::Locale;
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
+
+use crate::built_info;
+
+/// What a tray menu entry, or clicking the icon, does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ToggleVisible,
+    ReopenLog,
+    ResetLevel,
+    Quit,
+}
+
+/// Locale key of each menu entry's label.
+const LABELS: [(&str, TrayAction); 4] = [
+    ("tray.toggle", TrayAction::ToggleVisible),
+    ("tray.reopen_log", TrayAction::ReopenLog),
+    ("tray.reset_level", TrayAction::ResetLevel),
+    ("menu.quit", TrayAction::Quit),
+];
+
+/// Tray icon the overlay lives in instead of the taskbar.
+pub struct Tray {
+    _icon: TrayIcon,
+    /// Menu entries in the order of [`LABELS`]
+    entries: Vec<MenuItem>,
+    rx: Receiver<TrayAction>,
+}
+
+impl Tray {
+    pub fn new(ctx: &egui::Context, locale: &Locale) -> anyhow::Result<Self> {
+        let menu = Menu::new();
+        let mut entries = vec![];
+        let mut items = vec![];
+        for (key, action) in LABELS {
+            // Quit is set apart from the rest.
+            if action == TrayAction::Quit {
+                menu.append(&PredefinedMenuItem::separator())?;
+            }
+            let item = MenuItem::new(locale.tr(key), true, None);
+            menu.append(&item)?;
+            items.push((item.id().clone(), action));
+            entries.push(item);
+        }
+
+        // The handlers wake egui up, it doesn't repaint by itself while the window is hidden.
+        let (tx, rx) = channel();
+        let (menu_tx, menu_ctx) = (tx.clone(), ctx.clone());
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some((_, action)) = items.iter().find(|(id, _)| *id == event.id) {
+                let _ = menu_tx.send(*action);
+                menu_ctx.request_repaint();
+            }
+        }));
+        let ctx = ctx.clone();
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let _ = tx.send(TrayAction::ToggleVisible);
+                ctx.request_repaint();
+            }
+        }));
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip(built_info::PKG_NAME)
+            .with_icon(icon()?)
+            .build()?;
+
+        Ok(Self {
+            _icon: icon,
+            entries,
+            rx,
+        })
+    }
+
+    /// Labels the menu in `locale`, after the language was changed.
+    pub fn set_locale(&self, locale: &Locale) {
+        for (entry, (key, _)) in self.entries.iter().zip(LABELS) {
+            entry.set_text(locale.tr(key));
+        }
+    }
+
+    /// Actions picked since the last call.
+    pub fn pressed(&self) -> Vec<TrayAction> {
+        self.rx.try_iter().collect()
+    }
+}
+
+/// Yellow ring, like the markers of uncollected items on the map.
+fn icon() -> anyhow::Result<Icon> {
+    const SIZE: u32 = 32;
+
+    let center = (SIZE as f32 - 1.0) / 2.0;
+    let mut rgba = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            let alpha = if (9.0..15.0).contains(&distance) {
+                255
+            } else {
+                0
+            };
+            rgba.extend([255, 255, 0, alpha]);
+        }
+    }
+
+    Ok(Icon::from_rgba(rgba, SIZE, SIZE)?)
+}